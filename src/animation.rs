@@ -0,0 +1,187 @@
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use crate::game::Card;
+
+// How long each frame of an animation stays on screen.
+pub const FRAME_TIME: Duration = Duration::from_millis(30);
+// Frames a card takes to slide from the shoe to its hand
+const FRAMES_PER_CARD: usize = 6;
+// Frames the hole card takes to turn over
+const FRAMES_PER_FLIP: usize = 8;
+
+// How quickly cards are dealt and turned over, picked in the settings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum AnimationSpeed {
+    /// Cards land straight away and the dealer doesn't wait.
+    Off,
+    Fast,
+    #[default]
+    Normal,
+    Slow,
+}
+
+impl AnimationSpeed {
+    pub const ALL: [AnimationSpeed; 4] =
+        [AnimationSpeed::Off, AnimationSpeed::Fast, AnimationSpeed::Normal, AnimationSpeed::Slow];
+
+    /// Returns the name used for the speed in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            AnimationSpeed::Off => "off",
+            AnimationSpeed::Fast => "fast",
+            AnimationSpeed::Normal => "normal",
+            AnimationSpeed::Slow => "slow",
+        }
+    }
+
+    // Returns how many frames something taking `frames` at normal speed takes
+    fn frames(&self, frames: usize) -> usize {
+        match self {
+            AnimationSpeed::Off => 0,
+            AnimationSpeed::Fast => frames.div_ceil(2),
+            AnimationSpeed::Normal => frames,
+            AnimationSpeed::Slow => frames * 2,
+        }
+    }
+}
+
+// The hand a card is dealt to.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Seat {
+    Dealer,
+    /// One of the player's hands, by its index.
+    Player(usize),
+}
+
+// One thing the dealer does with a card.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Step {
+    /// Deals the card at a position in a hand.
+    Deal(Seat, usize),
+    /// Turns over the hole card.
+    Flip,
+    /// Waits for this many frames.
+    Pause(usize),
+}
+
+impl Step {
+    // Returns how many frames the step takes at normal speed
+    fn frames(&self) -> usize {
+        match self {
+            Step::Deal(..) => FRAMES_PER_CARD,
+            Step::Flip => FRAMES_PER_FLIP,
+            Step::Pause(frames) => *frames,
+        }
+    }
+}
+
+// Cards on their way from the shoe to the hands, dealt one after another,
+// and the hole card being turned over before them. The game has already
+// put the cards in the hands; this only holds back how they're shown.
+pub struct Dealing {
+    // What's still to happen, in order
+    steps: VecDeque<Step>,
+    // How far into the first step it's got
+    frame: usize,
+    speed: AnimationSpeed,
+}
+
+impl Dealing {
+    /// Works out which cards were dealt between two looks at the table,
+    /// returning None if there weren't any and the hole card wasn't turned
+    /// over. They're dealt the way a dealer would: a card to each hand in
+    /// turn, the dealer's last, once the hole card is showing. The dealer
+    /// waits `pause` before drawing each card past the first two. Every
+    /// step takes longer or shorter with the speed.
+    pub fn between(
+        before: &[(Seat, Vec<Card>)],
+        after: &[(Seat, Vec<Card>)],
+        flip: bool,
+        pause: Duration,
+        speed: AnimationSpeed,
+    ) -> Option<Self> {
+        if speed == AnimationSpeed::Off {
+            return None;
+        }
+
+        // A hand's new cards are the ones past what it held before
+        let new: Vec<(Seat, usize, usize)> = after
+            .iter()
+            .map(|(seat, cards)| {
+                let held = before.iter().find(|(s, _)| s == seat).map_or(&[][..], |(_, c)| c);
+                let kept = held.iter().zip(cards).take_while(|(a, b)| a == b).count();
+                (*seat, kept, cards.len())
+            })
+            .collect();
+
+        let mut steps = VecDeque::new();
+        if flip {
+            steps.push_back(Step::Flip);
+        }
+        let rounds = new.iter().map(|&(_, kept, len)| len - kept).max().unwrap_or(0);
+        for round in 0..rounds {
+            for &(seat, kept, len) in &new {
+                if kept + round < len {
+                    if seat == Seat::Dealer && kept + round >= 2 && !pause.is_zero() {
+                        steps.push_back(Step::Pause(pause.div_duration_f32(FRAME_TIME).ceil() as usize));
+                    }
+                    steps.push_back(Step::Deal(seat, kept + round));
+                }
+            }
+        }
+        (!steps.is_empty()).then_some(Dealing { steps, frame: 0, speed })
+    }
+
+    /// Returns how many of a hand's cards have landed, out of `len`.
+    pub fn shown(&self, seat: Seat, len: usize) -> usize {
+        self.steps
+            .iter()
+            .filter_map(|step| match *step {
+                Step::Deal(s, index) if s == seat => Some(index),
+                _ => None,
+            })
+            .min()
+            .unwrap_or(len)
+    }
+
+    /// Returns how far the hole card has turned, from 0 face down to 1 face
+    /// up, while it's being turned over.
+    pub fn flipping(&self) -> Option<f32> {
+        match self.steps.front()? {
+            step @ Step::Flip => Some(self.progress(step)),
+            Step::Deal(..) | Step::Pause(_) => None,
+        }
+    }
+
+    /// Returns true until the hole card has been turned over.
+    pub fn hides_hole_card(&self) -> bool {
+        self.steps.contains(&Step::Flip)
+    }
+
+    /// Returns the card sliding in: its seat, its position in the hand, and
+    /// how far it's come, from 0 at the shoe to 1 in the hand.
+    pub fn moving(&self) -> Option<(Seat, usize, f32)> {
+        match *self.steps.front()? {
+            step @ Step::Deal(seat, index) => Some((seat, index, self.progress(&step))),
+            Step::Flip | Step::Pause(_) => None,
+        }
+    }
+
+    // How far a step has got, from 0 to 1
+    fn progress(&self, step: &Step) -> f32 {
+        self.frame as f32 / self.speed.frames(step.frames()) as f32
+    }
+
+    /// Moves on a frame, returning false once every card has landed.
+    pub fn advance(&mut self) -> bool {
+        self.frame += 1;
+        if self.steps.front().is_some_and(|step| self.frame >= self.speed.frames(step.frames())) {
+            self.frame = 0;
+            self.steps.pop_front();
+        }
+        !self.steps.is_empty()
+    }
+}
@@ -0,0 +1,277 @@
+use crossterm::{
+    cursor,
+    event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers},
+    execute,
+    style::{Print, ResetColor},
+    terminal::{self, EnterAlternateScreen, LeaveAlternateScreen, disable_raw_mode, enable_raw_mode},
+};
+use std::io::{self, Write};
+use std::panic;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+// How long reading waits at a time before looking for a signal to stop
+const SIGNAL_CHECK: Duration = Duration::from_millis(100);
+
+// Whether the game has the terminal, so it can be handed back if the game
+// panics or gives up on an error part way through
+static ENTERED: AtomicBool = AtomicBool::new(false);
+// Whether it's drawing on the terminal's own screen rather than one of its own
+static INLINE: AtomicBool = AtomicBool::new(false);
+
+/// What the game needs of a terminal: somewhere to write frames to, its
+/// size, and the keys, clicks and resizes that come from it. The screens
+/// draw and read through this alone, so a terminal library other than
+/// crossterm, or a fake terminal, can stand in for it. Input comes as
+/// crossterm's events whichever library reads it.
+pub trait Backend: Write {
+    /// Takes the terminal over: raw input, a screen of the game's own and
+    /// the mouse.
+    fn enter(&mut self) -> io::Result<()>;
+
+    /// Hands the terminal back as it was before `enter`.
+    fn leave(&mut self) -> io::Result<()>;
+
+    /// Returns the terminal's size in columns and lines.
+    fn size(&self) -> io::Result<(u16, u16)>;
+
+    /// Waits up to `timeout` for an event, returning whether one came.
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool>;
+
+    /// Waits for the next event and returns it, or an `Interrupted` error
+    /// once the game's been asked to stop. A resize means the whole screen
+    /// needs drawing afresh, which is also what it's sent for when the
+    /// game comes back from being suspended.
+    fn read(&mut self) -> io::Result<Event>;
+
+    /// Whether the game's been asked to stop from outside, such as by
+    /// SIGTERM, and should save and leave.
+    fn interrupted(&self) -> bool;
+
+    /// Whether the terminal shows emoji such as ♥️ two columns wide, as
+    /// they're measured. Anything that can't tell says not. Finding out may
+    /// write to the screen, so it's best asked just before a full redraw.
+    fn wide_emoji(&mut self) -> bool;
+}
+
+/// Returns the plain ASCII character drawn in place of a symbol in ASCII
+/// mode, each taking a single column as the symbols do.
+pub fn ascii(c: char) -> char {
+    match c {
+        c if c.is_ascii() => c,
+        '│' | '║' | '┃' => '|',
+        '─' => '-',
+        '┌' | '┐' | '└' | '┘' | '├' | '┤' => '+',
+        '♠' => 'S',
+        '♥' => 'H',
+        '♦' => 'D',
+        '♣' => 'C',
+        '▶' | '→' => '>',
+        '◀' | '←' => '<',
+        '↑' => '^',
+        '↓' => 'v',
+        '≈' => '~',
+        '×' | '╳' | '✗' => 'x',
+        '○' => 'o',
+        '✓' => 'v',
+        // The card backs' patterns
+        '░' => '.',
+        '▒' => ':',
+        '▓' => '#',
+        '◆' => '*',
+        '◇' => 'o',
+        // Large print's big text
+        '█' => '#',
+        '▀' => '"',
+        '▄' => '_',
+        _ => '?',
+    }
+}
+
+/// Hands the terminal back if the game still has it. Errors are ignored,
+/// as this is the last thing done on the way out.
+pub fn restore() {
+    if ENTERED.swap(false, Ordering::SeqCst) {
+        let _ = hand_back();
+    }
+}
+
+// Puts the terminal back as it was before the game took it over. Inline,
+// the last screen drawn is left where it is, above the prompt.
+fn hand_back() -> io::Result<()> {
+    disable_raw_mode()?;
+    let mut stdout = io::stdout();
+    if INLINE.load(Ordering::SeqCst) {
+        let (_, height) = terminal::size()?;
+        let bottom = cursor::MoveTo(0, height.saturating_sub(1));
+        execute!(stdout, ResetColor, DisableMouseCapture, bottom, Print("\n"))
+    } else {
+        execute!(stdout, ResetColor, DisableMouseCapture, LeaveAlternateScreen)
+    }
+}
+
+/// Has a panic hand the terminal back before its message is printed, so
+/// the message can be read and the shell isn't left in raw mode.
+pub fn restore_on_panic() {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        restore();
+        hook(info);
+    }));
+}
+
+// The terminal the game runs in, through crossterm. It's handed back when
+// dropped, should an error end the game without leaving the screen first.
+pub struct Crossterm {
+    stdout: io::Stdout,
+    // Set by Ctrl+C, SIGINT, SIGTERM or the terminal hanging up
+    stop: Arc<AtomicBool>,
+    // Set by SIGTSTP, sent from outside the game to suspend it
+    suspend: Arc<AtomicBool>,
+    // Whether everything's written in plain ASCII
+    ascii: bool,
+    // Whether emoji come out two columns wide, once it's been measured
+    wide_emoji: Option<bool>,
+}
+
+impl Crossterm {
+    /// Opens the terminal, catching the signals that would otherwise end
+    /// the game without a chance to save. Inline, the game draws on the
+    /// terminal's own screen, so what it showed is kept in the scrollback
+    /// afterwards, rather than on a screen of its own. With `ascii`, box
+    /// lines, suits and other symbols are written as plain letters and
+    /// punctuation, for terminals and fonts that can't show them.
+    pub fn new(inline: bool, ascii: bool) -> io::Result<Self> {
+        INLINE.store(inline, Ordering::SeqCst);
+        let stop = Arc::new(AtomicBool::new(false));
+        for signal in [signal_hook::consts::SIGINT, signal_hook::consts::SIGTERM] {
+            signal_hook::flag::register(signal, Arc::clone(&stop))?;
+        }
+        let suspend = Arc::new(AtomicBool::new(false));
+        #[cfg(unix)]
+        {
+            signal_hook::flag::register(signal_hook::consts::SIGHUP, Arc::clone(&stop))?;
+            signal_hook::flag::register(signal_hook::consts::SIGTSTP, Arc::clone(&suspend))?;
+        }
+        Ok(Crossterm { stdout: io::stdout(), stop, suspend, ascii, wide_emoji: None })
+    }
+
+    // Hands the terminal back and stops, as Ctrl+Z does outside raw mode,
+    // then takes the terminal over again once the game's brought back with
+    // `fg`. What was on screen is gone, so it's sent as a resize to have
+    // it drawn afresh.
+    #[cfg(unix)]
+    fn suspend(&mut self) -> io::Result<Event> {
+        self.leave()?;
+        signal_hook::low_level::emulate_default_handler(signal_hook::consts::SIGTSTP)?;
+        self.enter()?;
+        let (width, height) = terminal::size()?;
+        Ok(Event::Resize(width, height))
+    }
+}
+
+// Writes an emoji in the corner and sees how far the cursor moves past it.
+// Terminals that don't know the emoji's wide move it one column or don't
+// answer at all.
+fn measure_emoji(out: &mut io::Stdout) -> io::Result<bool> {
+    execute!(out, cursor::MoveTo(0, 0), Print("\u{2665}\u{fe0f}"))?;
+    let (column, _) = cursor::position()?;
+    Ok(column == 2)
+}
+
+// Whether a key was pressed with Ctrl held. AltGr comes through as Ctrl and
+// Alt on Windows, and the characters typed with it are let through.
+fn ctrl_chord(modifiers: KeyModifiers) -> bool {
+    modifiers.contains(KeyModifiers::CONTROL) && !modifiers.contains(KeyModifiers::ALT)
+}
+
+impl Drop for Crossterm {
+    fn drop(&mut self) {
+        restore();
+    }
+}
+
+impl Write for Crossterm {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !self.ascii {
+            return self.stdout.write(buf);
+        }
+        let text: String = String::from_utf8_lossy(buf).chars().map(ascii).collect();
+        self.stdout.write_all(text.as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stdout.flush()
+    }
+}
+
+impl Backend for Crossterm {
+    fn enter(&mut self) -> io::Result<()> {
+        enable_raw_mode()?;
+        ENTERED.store(true, Ordering::SeqCst);
+        if INLINE.load(Ordering::SeqCst) {
+            // What's on screen is scrolled up out of the way, into the
+            // scrollback, rather than drawn over
+            let (_, height) = terminal::size()?;
+            let bottom = cursor::MoveTo(0, height.saturating_sub(1));
+            execute!(self.stdout, bottom, Print("\n".repeat(height as usize)), EnableMouseCapture)
+        } else {
+            execute!(self.stdout, EnterAlternateScreen, EnableMouseCapture)
+        }
+    }
+
+    fn leave(&mut self) -> io::Result<()> {
+        ENTERED.store(false, Ordering::SeqCst);
+        hand_back()
+    }
+
+    fn size(&self) -> io::Result<(u16, u16)> {
+        terminal::size()
+    }
+
+    fn poll(&mut self, timeout: Duration) -> io::Result<bool> {
+        Ok(self.suspend.load(Ordering::SeqCst) || event::poll(timeout)?)
+    }
+
+    fn read(&mut self) -> io::Result<Event> {
+        loop {
+            #[cfg(unix)]
+            if self.suspend.swap(false, Ordering::SeqCst) {
+                return self.suspend();
+            }
+            if self.interrupted() {
+                return Err(io::Error::new(io::ErrorKind::Interrupted, "asked to stop"));
+            }
+            if !event::poll(SIGNAL_CHECK)? {
+                continue;
+            }
+            match event::read()? {
+                // Windows sends keys being held down and let go as well as
+                // pressed
+                Event::Key(KeyEvent { kind, .. }) if kind != KeyEventKind::Press => {}
+                // Raw mode reads these as keys rather than signals
+                Event::Key(KeyEvent { code: KeyCode::Char('c'), modifiers, .. }) if ctrl_chord(modifiers) => {
+                    self.stop.store(true, Ordering::SeqCst);
+                }
+                Event::Key(KeyEvent { code: KeyCode::Char('z'), modifiers, .. }) if ctrl_chord(modifiers) => {
+                    #[cfg(unix)]
+                    return self.suspend();
+                }
+                event => return Ok(event),
+            }
+        }
+    }
+
+    fn interrupted(&self) -> bool {
+        self.stop.load(Ordering::SeqCst)
+    }
+
+    fn wide_emoji(&mut self) -> bool {
+        if self.ascii {
+            return false;
+        }
+        *self.wide_emoji.get_or_insert_with(|| measure_emoji(&mut self.stdout).unwrap_or(false))
+    }
+}
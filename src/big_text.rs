@@ -0,0 +1,57 @@
+// Big text for large print: ranks and totals written in characters three
+// lines tall, built from blocks, with a column between each.
+
+/// How many lines big text takes.
+pub const HEIGHT: usize = 3;
+
+// Columns each character takes, and the gap after it
+const WIDTH: usize = 3;
+const GAP: usize = 1;
+
+// The lines of a character, blank for any without a shape of its own
+fn shape(c: char) -> [&'static str; HEIGHT] {
+    match c {
+        '0' => ["█▀█", "█ █", "▀▀▀"],
+        '1' => ["▀█ ", " █ ", "▀▀▀"],
+        '2' => ["▀▀█", "█▀▀", "▀▀▀"],
+        '3' => ["▀▀█", " ▀█", "▀▀▀"],
+        '4' => ["█ █", "▀▀█", "  ▀"],
+        '5' => ["█▀▀", "▀▀█", "▀▀▀"],
+        '6' => ["█▀▀", "█▀█", "▀▀▀"],
+        '7' => ["▀▀█", "  █", "  ▀"],
+        '8' => ["█▀█", "█▀█", "▀▀▀"],
+        '9' => ["█▀█", "▀▀█", "▀▀▀"],
+        'A' => ["█▀█", "█▀█", "▀ ▀"],
+        'J' => ["  █", "▄ █", "▀▀▀"],
+        'Q' => ["█▀█", "█ █", "▀▀▄"],
+        'K' => ["█ █", "█▀▄", "▀ ▀"],
+        '/' => ["  █", " █ ", "▀  "],
+        '?' => ["▀▀█", " █▀", " ▀ "],
+        _ => ["   ", "   ", "   "],
+    }
+}
+
+/// Returns how many columns text takes in big text.
+pub fn width(text: &str) -> usize {
+    (text.chars().count() * (WIDTH + GAP)).saturating_sub(GAP)
+}
+
+/// Returns the character at a spot in text written big, or None where
+/// it's blank.
+pub fn char_at(text: &str, line: usize, column: usize) -> Option<char> {
+    if column % (WIDTH + GAP) >= WIDTH {
+        return None;
+    }
+    let c = text.chars().nth(column / (WIDTH + GAP))?;
+    shape(c)[line].chars().nth(column % (WIDTH + GAP)).filter(|&c| c != ' ')
+}
+
+/// Returns text written big, a line of it per line.
+pub fn lines(text: &str) -> Vec<String> {
+    (0..HEIGHT)
+        .map(|line| {
+            let shapes: Vec<&str> = text.chars().map(|c| shape(c)[line]).collect();
+            shapes.join(&" ".repeat(GAP))
+        })
+        .collect()
+}
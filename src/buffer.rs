@@ -0,0 +1,317 @@
+use crossterm::{
+    cursor, queue,
+    style::{Attribute, Color, Colors, Print, SetAttribute, SetColors},
+};
+use std::io::{self, Write};
+use std::mem;
+use unicode_width::UnicodeWidthChar;
+
+// Stands in the column after a wide character, which is drawn along with it
+const PLACEHOLDER: char = '\0';
+// Follows a symbol, such as a suit, to have it drawn as a wide emoji
+const EMOJI: char = '\u{fe0f}';
+// Unchanged characters it's worth drawing again rather than moving the
+// cursor past them, which takes about as many bytes
+const MAX_GAP: usize = 6;
+
+/// How a character on screen is drawn.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Style {
+    pub fg: Color,
+    pub bg: Color,
+    pub bold: bool,
+    pub reverse: bool,
+}
+
+// The terminal's own colors, as after a reset
+impl Default for Style {
+    fn default() -> Self {
+        Style { fg: Color::Reset, bg: Color::Reset, bold: false, reverse: false }
+    }
+}
+
+impl Style {
+    /// Changes the style as an SGR sequence such as `38;5;9;48;5;0` says.
+    pub fn apply_sgr(&mut self, params: &str) {
+        let mut values = params.split(';').map(|value| value.parse::<u8>().unwrap_or(0));
+        while let Some(value) = values.next() {
+            match value {
+                0 => *self = Style::default(),
+                1 => self.bold = true,
+                22 => self.bold = false,
+                7 => self.reverse = true,
+                27 => self.reverse = false,
+                30..=37 => self.fg = Color::AnsiValue(value - 30),
+                90..=97 => self.fg = Color::AnsiValue(value - 90 + 8),
+                39 => self.fg = Color::Reset,
+                40..=47 => self.bg = Color::AnsiValue(value - 40),
+                100..=107 => self.bg = Color::AnsiValue(value - 100 + 8),
+                49 => self.bg = Color::Reset,
+                38 | 48 => {
+                    let color = match values.next() {
+                        Some(5) => values.next().map(Color::AnsiValue),
+                        Some(2) => match (values.next(), values.next(), values.next()) {
+                            (Some(r), Some(g), Some(b)) => Some(Color::Rgb { r, g, b }),
+                            _ => None,
+                        },
+                        _ => None,
+                    };
+                    if let Some(color) = color {
+                        if value == 38 {
+                            self.fg = color;
+                        } else {
+                            self.bg = color;
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    /// Switches the terminal over to drawing in this style.
+    fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, SetAttribute(Attribute::Reset), SetColors(Colors::new(self.fg, self.bg)))?;
+        if self.bold {
+            queue!(out, SetAttribute(Attribute::Bold))?;
+        }
+        if self.reverse {
+            queue!(out, SetAttribute(Attribute::Reverse))?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Cell {
+    c: char,
+    style: Style,
+    // Whether it's drawn as an emoji, taking the next column as well
+    emoji: bool,
+}
+
+impl Cell {
+    fn new(c: char, style: Style) -> Self {
+        Cell { c, style, emoji: false }
+    }
+
+    // Writes the character, with the selector after it for an emoji
+    fn write(&self, out: &mut impl Write) -> io::Result<()> {
+        queue!(out, Print(self.c))?;
+        if self.emoji {
+            queue!(out, Print(EMOJI))?;
+        }
+        Ok(())
+    }
+}
+
+// Draws frames to the terminal, keeping what's on screen so that each
+// frame only writes what's changed. Its memory is kept from frame to frame,
+// so drawing one doesn't allocate.
+pub struct Screen {
+    // The last frame drawn, handed back out to draw the next into
+    frame: Vec<u8>,
+    shown: Buffer,
+    next: Buffer,
+    changes: Vec<u8>,
+}
+
+impl Screen {
+    pub fn new() -> Self {
+        Screen {
+            frame: Vec::new(),
+            shown: Buffer::default(),
+            next: Buffer::default(),
+            changes: Vec::new(),
+        }
+    }
+
+    /// Forgets what's on screen, for when something else has drawn over
+    /// it, so that the next frame is written whole.
+    pub fn forget(&mut self) {
+        self.shown = Buffer::default();
+    }
+
+    /// Hands out an empty frame to draw into.
+    pub fn take_frame(&mut self) -> Vec<u8> {
+        let mut frame = mem::take(&mut self.frame);
+        frame.clear();
+        frame
+    }
+
+    /// Takes a drawn frame back, returning what to write to the terminal
+    /// for it: the changes since the last one, or all of it the first time
+    /// and after the terminal's been resized.
+    pub fn update(&mut self, frame: Vec<u8>, width: u16, height: u16) -> io::Result<&[u8]> {
+        self.frame = frame;
+        self.next.play(&self.frame, width, height);
+        mem::swap(&mut self.shown, &mut self.next);
+        if self.shown.size() != self.next.size() {
+            return Ok(&self.frame);
+        }
+        self.changes.clear();
+        self.shown.diff(&self.next, &mut self.changes)?;
+        Ok(&self.changes)
+    }
+}
+
+/// Plays a frame back onto a blank screen of the given size and returns the
+/// text it leaves there without its colors, a line for each row with the
+/// spaces at the end trimmed off.
+pub fn text(frame: &[u8], width: u16, height: u16) -> String {
+    let mut buffer = Buffer::default();
+    buffer.play(frame, width, height);
+    let mut text = String::new();
+    for cells in buffer.cells.chunks(width.max(1) as usize) {
+        let mut line = String::new();
+        for cell in cells.iter().filter(|cell| cell.c != PLACEHOLDER) {
+            line.push(cell.c);
+            if cell.emoji {
+                line.push(EMOJI);
+            }
+        }
+        text.push_str(line.trim_end());
+        text.push('\n');
+    }
+    text
+}
+
+// What a frame leaves on screen, character by character, worked out by
+// playing back the commands that drew it. Comparing it with the last
+// frame's means only what's changed needs to be drawn again.
+#[derive(Default)]
+struct Buffer {
+    width: u16,
+    height: u16,
+    cells: Vec<Cell>,
+}
+
+impl Buffer {
+    /// Plays a frame back onto a blank screen of the given size. Anything
+    /// drawn past its edges is left out.
+    fn play(&mut self, frame: &[u8], width: u16, height: u16) {
+        let blank = Cell::new(' ', Style::default());
+        (self.width, self.height) = (width, height);
+        self.cells.clear();
+        self.cells.resize(width as usize * height as usize, blank);
+        let mut style = Style::default();
+        let (mut x, mut y) = (0u16, 0u16);
+
+        let text = String::from_utf8_lossy(frame);
+        let mut chars = text.char_indices().peekable();
+        while let Some((_, c)) = chars.next() {
+            match c {
+                '\x1b' => {
+                    let Some((start, _)) = chars.next_if(|&(_, c)| c == '[') else {
+                        chars.next();
+                        continue;
+                    };
+                    // A control sequence runs from the bracket to its final letter
+                    let Some((end, command)) = chars.find(|&(_, c)| ('\x40'..='\x7e').contains(&c)) else {
+                        break;
+                    };
+                    let params = &text[start + 1..end];
+                    match command {
+                        'H' => {
+                            let mut position = params.split(';').map(|p| p.parse::<u16>().unwrap_or(1));
+                            y = position.next().unwrap_or(1).saturating_sub(1);
+                            x = position.next().unwrap_or(1).saturating_sub(1);
+                        }
+                        // Clearing fills the screen with the background color
+                        'J' if params == "2" => self.cells.fill(Cell::new(' ', style)),
+                        'm' => style.apply_sgr(params),
+                        _ => {}
+                    }
+                }
+                '\r' => x = 0,
+                '\n' => y += 1,
+                // The selector widens the narrow symbol before it
+                EMOJI => {
+                    if x > 0 && x <= self.width && y < self.height {
+                        let i = y as usize * self.width as usize + x as usize - 1;
+                        if self.cells[i].c != PLACEHOLDER && !self.cells[i].emoji {
+                            self.cells[i].emoji = true;
+                            self.put(x, y, Cell::new(PLACEHOLDER, style));
+                            x += 1;
+                        }
+                    }
+                }
+                c if c.is_control() => {}
+                c => {
+                    let width = c.width().unwrap_or(0) as u16;
+                    if width == 0 {
+                        continue;
+                    }
+                    self.put(x, y, Cell::new(c, style));
+                    if width == 2 {
+                        self.put(x + 1, y, Cell::new(PLACEHOLDER, style));
+                    }
+                    x += width;
+                }
+            }
+        }
+    }
+
+    /// Returns the size of the screen it holds.
+    fn size(&self) -> (u16, u16) {
+        (self.width, self.height)
+    }
+
+    // Sets a cell, blanking what's left of a wide character it draws over
+    fn put(&mut self, x: u16, y: u16, cell: Cell) {
+        if x >= self.width || y >= self.height {
+            return;
+        }
+        let i = y as usize * self.width as usize + x as usize;
+        if self.cells[i].c == PLACEHOLDER && cell.c != PLACEHOLDER && x > 0 {
+            self.cells[i - 1] = Cell::new(' ', self.cells[i - 1].style);
+        }
+        if x + 1 < self.width && self.cells[i + 1].c == PLACEHOLDER {
+            self.cells[i + 1].c = ' ';
+        }
+        self.cells[i] = cell;
+    }
+
+    /// Writes what it takes to turn the screen from `previous`, which must
+    /// be the same size, into this one. Rows that are as they were, such
+    /// as the table's header while cards are dealt, are skipped whole, and
+    /// the cursor is only moved to the characters that have changed.
+    fn diff(&self, previous: &Buffer, out: &mut impl Write) -> io::Result<()> {
+        let width = self.width as usize;
+        let mut style = None;
+        for (y, (cells, before)) in self.cells.chunks(width).zip(previous.cells.chunks(width)).enumerate() {
+            if cells == before {
+                continue;
+            }
+            // Where the cursor was left on this row, after the last change
+            let mut at = None;
+            let mut x = 0;
+            while x < width {
+                let span = if x + 1 < width && cells[x + 1].c == PLACEHOLDER { 2 } else { 1 };
+                if cells[x].c != PLACEHOLDER && cells[x..x + span] != before[x..x + span] {
+                    // A few unchanged characters in the same style are
+                    // cheaper to draw again than to move the cursor past
+                    let gap = at.filter(|&from: &usize| {
+                        x - from <= MAX_GAP && cells[from..x].iter().all(|cell| Some(cell.style) == style)
+                    });
+                    match gap {
+                        Some(from) => {
+                            for cell in cells[from..x].iter().filter(|cell| cell.c != PLACEHOLDER) {
+                                cell.write(out)?;
+                            }
+                        }
+                        None => queue!(out, cursor::MoveTo(x as u16, y as u16))?,
+                    }
+                    if style != Some(cells[x].style) {
+                        cells[x].style.write(out)?;
+                        style = Some(cells[x].style);
+                    }
+                    cells[x].write(out)?;
+                    at = Some(x + span);
+                }
+                x += span;
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,273 @@
+use crossterm::{
+    Command,
+    style::{Color, SetForegroundColor},
+};
+use serde::{Deserialize, Serialize};
+use std::iter;
+
+use crate::big_text;
+use crate::game::Card;
+use crate::theme::Palette;
+
+// Columns between cards laid side by side
+const GAP: usize = 1;
+// The least of a card left showing when they have to overlap
+const MIN_STEP: usize = 3;
+
+// How big cards are drawn: small boxes, or bigger ones on terminals with
+// room to spare, or in large print with the rank written big.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CardSize {
+    Normal,
+    Large,
+    Huge,
+}
+
+impl CardSize {
+    /// Returns how many lines tall cards are.
+    pub fn height(&self) -> usize {
+        match self {
+            CardSize::Normal => 5,
+            CardSize::Large | CardSize::Huge => 7,
+        }
+    }
+
+    /// Returns how many columns wide cards are.
+    pub fn width(&self) -> usize {
+        match self {
+            CardSize::Normal => 7,
+            CardSize::Large => 9,
+            CardSize::Huge => 11,
+        }
+    }
+}
+
+// The patterns a face-down card can show, picked in the settings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum CardBack {
+    #[default]
+    Checker,
+    Lattice,
+    Diamonds,
+    Stripes,
+    Waves,
+    Solid,
+}
+
+impl CardBack {
+    pub const ALL: [CardBack; 6] = [
+        CardBack::Checker,
+        CardBack::Lattice,
+        CardBack::Diamonds,
+        CardBack::Stripes,
+        CardBack::Waves,
+        CardBack::Solid,
+    ];
+
+    /// Returns the name used for the design in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            CardBack::Checker => "checker",
+            CardBack::Lattice => "lattice",
+            CardBack::Diamonds => "diamonds",
+            CardBack::Stripes => "stripes",
+            CardBack::Waves => "waves",
+            CardBack::Solid => "solid",
+        }
+    }
+
+    // The character at a spot inside the card's border, the pattern
+    // repeating to fill a card of any size
+    fn fill(&self, row: usize, column: usize) -> char {
+        let even = (row + column).is_multiple_of(2);
+        match self {
+            CardBack::Checker if even => '░',
+            CardBack::Checker => '▒',
+            CardBack::Lattice => '╳',
+            CardBack::Diamonds if even => '◆',
+            CardBack::Diamonds => '◇',
+            CardBack::Stripes => '║',
+            CardBack::Waves => '≈',
+            CardBack::Solid => '▓',
+        }
+    }
+}
+
+// The character at a spot on a card and the color it's drawn in, if any:
+// its suit's for the rank and suit, none for the border and the back
+fn glyph(
+    card: Option<&Card>,
+    back: CardBack,
+    size: CardSize,
+    line: usize,
+    column: usize,
+    palette: &Palette,
+) -> (char, Option<Color>) {
+    let (top, bottom) = (line == 0, line == size.height() - 1);
+    let (left, right) = (column == 0, column == size.width() - 1);
+    let border = match (top, bottom, left, right) {
+        (true, _, true, _) => '┌',
+        (true, _, _, true) => '┐',
+        (_, true, true, _) => '└',
+        (_, true, _, true) => '┘',
+        (true, ..) | (_, true, ..) => '─',
+        (.., true, _) | (.., true) => '│',
+        _ => ' ',
+    };
+    if border != ' ' {
+        return (border, None);
+    }
+
+    let (row, column) = (line - 1, column - 1);
+    let Some(card) = card else {
+        return (back.fill(row, column), None);
+    };
+    let inside = size.width() - 2;
+    let rank = card.rank.label();
+    if size == CardSize::Huge {
+        return match big_glyph(card, row, column, inside, palette) {
+            Some(c) => (c, Some(palette.suit(card.suit))),
+            None => (' ', None),
+        };
+    }
+    // The rank in the top left and bottom right corners, and the suit in
+    // the middle, with room around it on big cards. An emoji suit takes
+    // the column after it too, for the selector that makes it one.
+    let middle = line == size.height() / 2;
+    let c = if row == 0 {
+        rank.chars().nth(column)
+    } else if row == size.height() - 3 {
+        column.checked_sub(inside - rank.len()).and_then(|i| rank.chars().nth(i))
+    } else if middle && column == inside / 2 {
+        card.suit.symbol().chars().next()
+    } else if middle && column == inside / 2 + 1 && palette.emoji_suits {
+        card.suit.emoji().chars().nth(1)
+    } else {
+        None
+    };
+    match c {
+        Some(c) => (c, Some(palette.suit(card.suit))),
+        None => (' ', None),
+    }
+}
+
+// The character at a spot inside a card in large print: the rank written
+// big in the middle, and the suit in the top left and bottom right corners
+fn big_glyph(card: &Card, row: usize, column: usize, inside: usize, palette: &Palette) -> Option<char> {
+    let rank = card.rank.label();
+    let suit = palette.suit_symbol(card.suit);
+    let last = CardSize::Huge.height() - 3;
+    let margin = (inside - big_text::width(rank)) / 2;
+    if row == 0 {
+        suit.chars().nth(column)
+    } else if row == last {
+        column.checked_sub(inside - suit.chars().count()).and_then(|i| suit.chars().nth(i))
+    } else if row <= big_text::HEIGHT {
+        big_text::char_at(rank, row - 1, column.checked_sub(margin)?)
+    } else {
+        None
+    }
+}
+
+// Writes characters onto text, switching color only where it changes
+fn render(text: &mut String, line: impl Iterator<Item = (char, Option<Color>)>, palette: &Palette) {
+    let mut current = None;
+    for (c, color) in line {
+        if color != current {
+            // Writing into a string can't fail
+            let _ = SetForegroundColor(color.unwrap_or(palette.text)).write_ansi(text);
+            current = color;
+        }
+        text.push(c);
+    }
+    if current.is_some() {
+        let _ = SetForegroundColor(palette.text).write_ansi(text);
+    }
+}
+
+/// Draws a card part way through being turned over, from 0 face down to 1
+/// face up. It narrows to its edge and widens again showing the other side,
+/// keeping to its place in a row. Only the first `columns` are drawn, for a
+/// card that the next one in its row overlaps.
+pub fn flipping(
+    card: &Card,
+    progress: f32,
+    columns: usize,
+    back: CardBack,
+    size: CardSize,
+    palette: &Palette,
+) -> Vec<String> {
+    let side = if progress < 0.5 { None } else { Some(card) };
+    let width = size.width();
+    // The width it shows, down to just its two edges half way over
+    let inside = ((width - 2) as f32 * (1.0 - 2.0 * progress).abs()).round() as usize;
+    let margin = (width - 2 - inside) / 2;
+    (0..size.height())
+        .map(|line| {
+            let glyph = |column| glyph(side, back, size, line, column, palette);
+            let narrowed = iter::repeat_n((' ', None), margin)
+                .chain(iter::once(glyph(0)))
+                .chain((1 + margin..1 + margin + inside).map(glyph))
+                .chain(iter::once(glyph(width - 1)))
+                .chain(iter::repeat((' ', None)))
+                .take(width.min(columns));
+            let mut text = String::new();
+            render(&mut text, narrowed, palette);
+            text
+        })
+        .collect()
+}
+
+// Columns from one card to the next in a row of `count` cards
+fn step(count: usize, width: usize, size: CardSize) -> usize {
+    let card_width = size.width();
+    if count * (card_width + GAP) - GAP <= width {
+        card_width + GAP
+    } else {
+        (width.saturating_sub(card_width) / (count - 1).max(1)).clamp(MIN_STEP, card_width)
+    }
+}
+
+/// Returns how far into a row of `count` cards the card at `index` starts.
+pub fn offset(count: usize, index: usize, width: usize, size: CardSize) -> usize {
+    index * step(count, width, size)
+}
+
+/// Lays cards out side by side, each line of the result being one line of
+/// every card with its suit in color, `None` standing for a card lying
+/// face down and showing the back design. Cards that wouldn't fit in
+/// `width` columns overlap, leaving their corners showing.
+pub fn row(
+    cards: &[Option<&Card>],
+    width: usize,
+    back: CardBack,
+    size: CardSize,
+    palette: &Palette,
+) -> Vec<String> {
+    let Some(last) = cards.len().checked_sub(1) else {
+        return vec![String::new(); size.height()];
+    };
+
+    let step = step(cards.len(), width, size);
+    (0..size.height())
+        .map(|line| {
+            // Each card takes its step, but for the last which shows whole
+            let glyphs = cards.iter().enumerate().flat_map(|(i, card)| {
+                let columns = if i == last { size.width() } else { step };
+                (0..columns).map(move |column| {
+                    if column < size.width() {
+                        glyph(*card, back, size, line, column, palette)
+                    } else {
+                        (' ', None)
+                    }
+                })
+            });
+            // Room for every character at its widest, and a color code or
+            // two for each card, so the line is only allocated once
+            let mut text = String::with_capacity(cards.len() * (step * 3 + 24));
+            render(&mut text, glyphs, palette);
+            text
+        })
+        .collect()
+}
@@ -0,0 +1,43 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+// Records what's drawn to the terminal as an asciinema v2 cast file: a JSON
+// header line followed by one `[time, "o", data]` line per frame.
+pub struct Cast {
+    writer: BufWriter<File>,
+    start: Instant,
+}
+
+impl Cast {
+    /// Creates the cast file for a terminal of the given size.
+    pub fn create(path: &Path, width: u16, height: u16) -> io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        let header = serde_json::json!({
+            "version": 2,
+            "width": width,
+            "height": height,
+            "timestamp": timestamp,
+            "title": "rustjack",
+        });
+        writeln!(writer, "{}", header)?;
+        Ok(Cast {
+            writer,
+            start: Instant::now(),
+        })
+    }
+
+    /// Adds a frame of terminal output, timed from when recording started.
+    pub fn record(&mut self, output: &[u8]) -> io::Result<()> {
+        let time = self.start.elapsed().as_secs_f64();
+        let event = (time, "o", String::from_utf8_lossy(output));
+        serde_json::to_writer(&mut self.writer, &event).map_err(io::Error::other)?;
+        self.writer.write_all(b"\n")?;
+        // Flushed every frame so the cast is usable even if the game is killed
+        self.writer.flush()
+    }
+}
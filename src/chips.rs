@@ -0,0 +1,108 @@
+use crossterm::style::Color;
+use serde::{Deserialize, Serialize};
+
+// Casino chip denominations a bet is built from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Chip {
+    One,
+    Five,
+    TwentyFive,
+    Hundred,
+    FiveHundred,
+}
+
+impl Chip {
+    /// Every denomination, from smallest to largest.
+    pub const ALL: [Chip; 5] = [
+        Chip::One,
+        Chip::Five,
+        Chip::TwentyFive,
+        Chip::Hundred,
+        Chip::FiveHundred,
+    ];
+
+    pub fn value(&self) -> u32 {
+        match self {
+            Chip::One => 1,
+            Chip::Five => 5,
+            Chip::TwentyFive => 25,
+            Chip::Hundred => 100,
+            Chip::FiveHundred => 500,
+        }
+    }
+
+    /// Returns the chip's face colour and the colour its value is printed in,
+    /// following the usual casino scheme.
+    pub fn colors(&self) -> (Color, Color) {
+        match self {
+            Chip::One => (Color::White, Color::Black),
+            Chip::Five => (Color::DarkRed, Color::White),
+            Chip::TwentyFive => (Color::DarkGreen, Color::White),
+            Chip::Hundred => (Color::Black, Color::White),
+            Chip::FiveHundred => (Color::DarkMagenta, Color::White),
+        }
+    }
+}
+
+// The chips making up a bet, in the order they were put down.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChipStack {
+    chips: Vec<Chip>,
+}
+
+impl ChipStack {
+    /// Breaks an amount down into as few chips as possible.
+    pub fn from_amount(mut amount: u32) -> Self {
+        let mut chips = Vec::new();
+        for chip in Chip::ALL.into_iter().rev() {
+            while amount >= chip.value() {
+                chips.push(chip);
+                amount -= chip.value();
+            }
+        }
+        ChipStack { chips }
+    }
+
+    pub fn push(&mut self, chip: Chip) {
+        self.chips.push(chip);
+    }
+
+    /// Takes back the chip put down last.
+    pub fn pop(&mut self) -> Option<Chip> {
+        self.chips.pop()
+    }
+
+    /// Takes back the chip of a denomination put down last, if there's any.
+    pub fn take(&mut self, chip: Chip) -> bool {
+        match self.chips.iter().rposition(|&c| c == chip) {
+            Some(i) => {
+                self.chips.remove(i);
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.chips.clear();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chips.is_empty()
+    }
+
+    pub fn total(&self) -> u32 {
+        self.chips.iter().map(|c| c.value()).sum()
+    }
+
+    /// Returns how many of each denomination are in the stack, largest first,
+    /// leaving out the ones that aren't there.
+    pub fn counts(&self) -> Vec<(Chip, usize)> {
+        Chip::ALL
+            .into_iter()
+            .rev()
+            .map(|chip| (chip, self.chips.iter().filter(|&&c| c == chip).count()))
+            .filter(|&(_, count)| count > 0)
+            .collect()
+    }
+}
@@ -0,0 +1,240 @@
+use clap::{Args, Parser, Subcommand};
+
+use crate::config::{Config, DEFAULT_PROFILE};
+use crate::keys::{Key, KeyScheme};
+use crate::rules::{Payout, Preset, Rules};
+use crate::session::PushPolicy;
+
+// Terminal blackjack. Run without a subcommand to play.
+#[derive(Parser)]
+#[command(version, about = "Blackjack in the terminal")]
+#[command(args_conflicts_with_subcommands = true)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+    #[command(flatten)]
+    pub play: PlayArgs,
+}
+
+#[derive(Subcommand)]
+pub enum Command {
+    /// Sit down at the table (the default)
+    Play(PlayArgs),
+    /// Play rounds with basic strategy to see what the rules cost
+    Sim(SimArgs),
+    /// Export a profile's stats as JSON
+    Stats(StatsArgs),
+    /// Print the table as text after pressing some keys, without playing
+    Frame(FrameArgs),
+}
+
+// Options for starting a game.
+#[derive(Args)]
+pub struct PlayArgs {
+    #[command(flatten)]
+    pub table: TableArgs,
+    /// Play as this profile instead of picking one on the profile screen
+    #[arg(long, value_parser = parse_profile)]
+    pub profile: Option<String>,
+    /// Keep the table rules as the custom preset for future sessions
+    #[arg(long)]
+    pub save_rules: bool,
+    /// Play today's daily challenge
+    #[arg(long)]
+    pub daily: bool,
+    /// Shuffle the shoe the same way every time
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Record every deal and decision to a replay file
+    #[arg(long, value_name = "FILE")]
+    pub record: Option<String>,
+    /// Save everything drawn as an asciinema cast
+    #[arg(long, value_name = "FILE")]
+    pub cast: Option<String>,
+    /// Step through a replay file instead of playing
+    #[arg(long, value_name = "FILE")]
+    pub replay: Option<String>,
+    /// Draw on the terminal's own screen, leaving the game in the scrollback
+    #[arg(long)]
+    pub inline: bool,
+    /// Draw boxes, suits and symbols with plain ASCII characters
+    #[arg(long)]
+    pub ascii: bool,
+    /// Tell the game as plain text for a screen reader, to a file or pipe,
+    /// or to the terminal in place of the table with `-`
+    #[arg(long, value_name = "FILE")]
+    pub narrate: Option<String>,
+    /// Play with the standard keys or vim-style ones
+    #[arg(long, value_name = "standard|vim")]
+    pub keys: Option<KeyScheme>,
+    /// Whether a push keeps or resets a win or loss streak
+    #[arg(long, value_name = "keep|reset")]
+    pub push_streak: Option<PushPolicy>,
+    /// End the session once this much is lost
+    #[arg(long, value_name = "AMOUNT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub stop_loss: Option<u32>,
+    /// End the session once this much is won
+    #[arg(long, value_name = "AMOUNT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub win_goal: Option<u32>,
+}
+
+// Options for simulating a table.
+#[derive(Args)]
+pub struct SimArgs {
+    #[command(flatten)]
+    pub table: TableArgs,
+    /// Profile whose custom preset the rules can come from
+    #[arg(long, value_parser = parse_profile, default_value = DEFAULT_PROFILE)]
+    pub profile: String,
+    /// Number of rounds to play
+    #[arg(long, default_value_t = 100_000)]
+    pub rounds: u64,
+    /// Shuffle the shoe the same way every time
+    #[arg(long)]
+    pub seed: Option<u64>,
+}
+
+// Options for exporting stats.
+#[derive(Args)]
+pub struct StatsArgs {
+    /// Profile whose stats are exported
+    #[arg(long, value_parser = parse_profile, default_value = DEFAULT_PROFILE)]
+    pub profile: String,
+    /// File to write the JSON to, or - for stdout
+    #[arg(short, long, value_name = "FILE", default_value = "-")]
+    pub output: String,
+}
+
+// Options for printing a frame of the table.
+#[derive(Args)]
+pub struct FrameArgs {
+    #[command(flatten)]
+    pub table: TableArgs,
+    /// Profile whose rules, theme and keys the table has
+    #[arg(long, value_parser = parse_profile, default_value = DEFAULT_PROFILE)]
+    pub profile: String,
+    /// Shuffle the shoe the same way every time
+    #[arg(long)]
+    pub seed: Option<u64>,
+    /// Columns the frame is drawn in
+    #[arg(long, default_value_t = 100)]
+    pub width: u16,
+    /// Lines the frame is drawn in
+    #[arg(long, default_value_t = 40)]
+    pub height: u16,
+    /// Draw boxes, suits and symbols with plain ASCII characters
+    #[arg(long)]
+    pub ascii: bool,
+    /// Keys pressed before the frame is drawn, e.g. `5 enter h`
+    #[arg(value_name = "KEYS")]
+    pub keys: Vec<Key>,
+}
+
+// The table rules, shared by playing and simulating. A preset is the
+// starting point and the other flags adjust it.
+#[derive(Args)]
+pub struct TableArgs {
+    /// Rules preset to start from
+    #[arg(long, value_name = "PRESET")]
+    pub rules: Option<Preset>,
+    /// Number of decks in the shoe
+    #[arg(long, value_parser = clap::value_parser!(u8).range(1..=8))]
+    pub decks: Option<u8>,
+    /// Percentage of the shoe dealt before the cut card
+    #[arg(long, value_name = "PERCENT", value_parser = clap::value_parser!(u8).range(10..=100))]
+    pub penetration: Option<u8>,
+    /// What a blackjack pays, e.g. 6:5
+    #[arg(long, value_name = "RATIO")]
+    pub payout: Option<Payout>,
+    /// The dealer hits soft 17
+    #[arg(long)]
+    pub h17: bool,
+    /// No doubling after a split
+    #[arg(long)]
+    pub no_das: bool,
+    /// A dealer 22 pushes every hand still standing
+    #[arg(long)]
+    pub push_22: bool,
+    /// Split aces can take more cards
+    #[arg(long)]
+    pub hit_split_aces: bool,
+    /// An ace and a ten after splitting aces pays as blackjack
+    #[arg(long)]
+    pub split_aces_blackjack: bool,
+    /// A hand of this many cards that hasn't busted wins
+    #[arg(long, value_name = "CARDS", value_parser = clap::value_parser!(u8).range(3..=10))]
+    pub charlie: Option<u8>,
+    /// The dealer takes no hole card until the player is done
+    #[arg(long)]
+    pub no_hole_card: bool,
+    /// No hole card, and a dealer blackjack only takes the original bets
+    #[arg(long)]
+    pub obo: bool,
+    /// Smallest bet per spot
+    #[arg(long, value_name = "AMOUNT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub min_bet: Option<u32>,
+    /// Largest bet per spot
+    #[arg(long, value_name = "AMOUNT", value_parser = clap::value_parser!(u32).range(1..))]
+    pub max_bet: Option<u32>,
+}
+
+impl TableArgs {
+    /// Works out the rules from the chosen preset, or the profile's usual
+    /// one, with the individual flags applied on top.
+    pub fn rules(&self, config: &Config) -> Rules {
+        let mut rules = self
+            .rules
+            .or(config.preset)
+            .map(|p| config.preset_rules(p))
+            .unwrap_or_default();
+
+        if let Some(decks) = self.decks {
+            rules.decks = decks as usize;
+        }
+        if let Some(percent) = self.penetration {
+            rules.penetration = percent as f32 / 100.0;
+        }
+        if let Some(payout) = self.payout {
+            rules.blackjack_payout = payout;
+        }
+        if self.h17 {
+            rules.dealer_hits_soft_17 = true;
+        }
+        if self.no_das {
+            rules.double_after_split = false;
+        }
+        if self.push_22 {
+            rules.push_on_dealer_22 = true;
+        }
+        if self.hit_split_aces {
+            rules.hit_split_aces = true;
+        }
+        if self.split_aces_blackjack {
+            rules.split_aces_blackjack = true;
+        }
+        if let Some(cards) = self.charlie {
+            rules.charlie = Some(cards as usize);
+        }
+        if self.no_hole_card || self.obo {
+            rules.no_hole_card = true;
+        }
+        if self.obo {
+            rules.original_bets_only = true;
+        }
+        if let Some(bet) = self.min_bet {
+            rules.min_bet = bet;
+        }
+        if let Some(bet) = self.max_bet {
+            rules.max_bet = bet;
+        }
+        rules
+    }
+}
+
+fn parse_profile(name: &str) -> Result<String, String> {
+    if Config::valid_profile_name(name) {
+        Ok(name.to_string())
+    } else {
+        Err(String::from("expected a name made of letters, digits, - or _"))
+    }
+}
@@ -0,0 +1,200 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::animation::AnimationSpeed;
+use crate::card_art::CardBack;
+use crate::count::{QuizRecord, Rounding, System};
+use crate::keys::{Key, KeyBindings, KeyScheme};
+use crate::locale::{Currency, Locale};
+use crate::rules::{Preset, Rules};
+use crate::session::PushPolicy;
+use crate::theme::Theme;
+
+// The profile played when none is picked. It keeps its settings in the
+// top-level config file, so configs from before profiles still load.
+pub const DEFAULT_PROFILE: &str = "default";
+
+// Milliseconds the dealer waits before drawing a card, unless the config
+// says otherwise.
+const DEFAULT_DEALER_DELAY: u64 = 500;
+
+// Units bet at a count of +1 or less, +2, +3 and so on, unless the config
+// says otherwise.
+const DEFAULT_BET_SPREAD: [u32; 5] = [1, 2, 4, 6, 8];
+
+// Milliseconds the counting trainer shows each card for, unless the config
+// says otherwise.
+const DEFAULT_TRAINER_SPEED: u64 = 1000;
+
+// Settings kept between sessions in the platform config directory, one set
+// per player profile.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    /// Rules preset to play with unless one is given on the command line.
+    pub preset: Option<Preset>,
+    /// The rules used by the custom preset.
+    pub custom_rules: Option<Rules>,
+    /// Whether a push keeps or ends a win or loss streak.
+    pub push_policy: PushPolicy,
+    /// Bankroll left at the end of the last session.
+    pub bankroll: Option<u32>,
+    /// The set of keys to play with.
+    pub key_scheme: KeyScheme,
+    /// The language everything's written in.
+    pub locale: Locale,
+    /// How amounts of chips are written.
+    pub currency: Currency,
+    /// The colors the table is drawn in.
+    pub theme: Theme,
+    /// The design on the back of face-down cards.
+    pub card_back: CardBack,
+    /// Whether suits are drawn as emoji, on terminals that can show them.
+    pub emoji_suits: bool,
+    /// Whether ranks and totals are written big, on terminals with room.
+    pub large_print: bool,
+    /// Whether the cards and decks left in the shoe are shown, if not the
+    /// default of showing them.
+    pub shoe_left: Option<bool>,
+    /// The counting system the running count is kept in.
+    pub count_system: System,
+    /// How the true count is rounded.
+    pub true_count_rounding: Rounding,
+    /// Units of the table minimum to bet at a count of +1 or less, +2, +3
+    /// and so on, if not the default.
+    pub bet_spread: Option<Vec<u32>>,
+    /// How the count quizzes in practice games have gone.
+    pub count_quiz: QuizRecord,
+    /// Milliseconds the counting trainer shows each card for, if not the
+    /// default.
+    pub trainer_speed: Option<u64>,
+    /// How the counting trainer's checks have gone.
+    pub count_drill: QuizRecord,
+    /// Milliseconds the dealer waits before drawing each card, if not the
+    /// default.
+    pub dealer_delay: Option<u64>,
+    /// How quickly cards are dealt and turned over, if at all.
+    pub animation_speed: AnimationSpeed,
+    /// Keys remapped from the scheme's, by action name.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub keys: BTreeMap<String, Key>,
+}
+
+impl Config {
+    /// Returns where a profile's config file lives, e.g.
+    /// `~/.config/rustjack/config.toml` for the default profile and
+    /// `~/.config/rustjack/profiles/alice.toml` for others.
+    pub fn path(profile: &str) -> Option<PathBuf> {
+        let dir = dirs::config_dir()?.join("rustjack");
+        Some(if profile == DEFAULT_PROFILE {
+            dir.join("config.toml")
+        } else {
+            dir.join("profiles").join(format!("{}.toml", profile))
+        })
+    }
+
+    /// Returns the names of every profile, the default one first.
+    pub fn profiles() -> io::Result<Vec<String>> {
+        let mut profiles = Vec::new();
+        if let Some(dir) = dirs::config_dir().map(|dir| dir.join("rustjack").join("profiles")) {
+            match fs::read_dir(dir) {
+                Ok(entries) => {
+                    for entry in entries {
+                        let path = entry?.path();
+                        if path.extension().is_some_and(|ext| ext == "toml")
+                            && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+                        {
+                            profiles.push(name.to_string());
+                        }
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        profiles.sort();
+        profiles.insert(0, String::from(DEFAULT_PROFILE));
+        Ok(profiles)
+    }
+
+    /// Checks a new profile's name, which ends up in a file name.
+    pub fn valid_profile_name(name: &str) -> bool {
+        !name.is_empty()
+            && name.len() <= 20
+            && name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    }
+
+    /// Loads a profile's config file, falling back to defaults if there
+    /// isn't one yet.
+    pub fn load(profile: &str) -> io::Result<Self> {
+        let Some(path) = Self::path(profile) else {
+            return Ok(Config::default());
+        };
+
+        let invalid = |err: &dyn fmt::Display| {
+            io::Error::other(format!("invalid config file {}: {}", path.display(), err))
+        };
+        let config: Config = match fs::read_to_string(&path) {
+            Ok(contents) => toml::from_str(&contents).map_err(|err| invalid(&err))?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Config::default()),
+            Err(err) => return Err(err),
+        };
+        // Rules written by hand could deal from an empty shoe
+        if let Some(rules) = &config.custom_rules {
+            rules.check().map_err(|err| invalid(&format!("custom_rules: {}", err)))?;
+        }
+        Ok(config)
+    }
+
+    /// Writes a profile's config file, creating its directory if needed.
+    pub fn save(&self, profile: &str) -> io::Result<()> {
+        let path = Self::path(profile)
+            .ok_or_else(|| io::Error::other("no config directory on this platform"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let contents = toml::to_string_pretty(self).map_err(io::Error::other)?;
+        fs::write(path, contents)
+    }
+
+    /// Returns the key bindings the config asks for.
+    pub fn key_bindings(&self) -> io::Result<KeyBindings> {
+        KeyBindings::new(self.key_scheme, &self.keys).map_err(io::Error::other)
+    }
+
+    /// Returns how long the dealer waits before drawing each card.
+    pub fn dealer_delay(&self) -> Duration {
+        Duration::from_millis(self.dealer_delay.unwrap_or(DEFAULT_DEALER_DELAY))
+    }
+
+    /// Returns the units of the table minimum to bet at each count.
+    pub fn bet_spread(&self) -> Vec<u32> {
+        match &self.bet_spread {
+            Some(spread) if !spread.is_empty() => spread.clone(),
+            _ => DEFAULT_BET_SPREAD.to_vec(),
+        }
+    }
+
+    /// Returns how long the counting trainer shows each card for.
+    pub fn trainer_speed(&self) -> Duration {
+        Duration::from_millis(self.trainer_speed.unwrap_or(DEFAULT_TRAINER_SPEED))
+    }
+
+    /// Returns whether the cards and decks left in the shoe are shown.
+    pub fn shoe_left(&self) -> bool {
+        self.shoe_left.unwrap_or(true)
+    }
+
+    /// Returns the rules for a preset, reading the custom one from the config.
+    pub fn preset_rules(&self, preset: Preset) -> Rules {
+        match preset {
+            Preset::Custom => self.custom_rules.clone().unwrap_or_default(),
+            preset => preset.rules(),
+        }
+    }
+}
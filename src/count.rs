@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::Rank;
+
+// Cards in a deck, for judging how many decks the cards left come to
+const DECK: f32 = 52.0;
+
+// A way of keeping count of the cards dealt: what each rank adds to the
+// running count, and where the count starts.
+pub trait CountingSystem {
+    /// Returns the name the system goes by.
+    fn name(&self) -> &'static str;
+
+    /// Returns what each value adds to the running count, from the twos up
+    /// to the tens and picture cards and then the aces.
+    fn tags(&self) -> [i32; 10];
+
+    /// Returns what a card of the rank adds to the running count.
+    fn tag(&self, rank: Rank) -> i32 {
+        self.tags()[rank.value() as usize - 2]
+    }
+
+    /// Returns where the running count starts for a shoe of `decks`.
+    fn initial_count(&self, _decks: usize) -> i32 {
+        0
+    }
+
+    /// Returns whether a full deck counts to nothing, which a running count
+    /// has to for it to be turned into a true count.
+    fn balanced(&self) -> bool {
+        Rank::ALL.iter().map(|&rank| self.tag(rank)).sum::<i32>() == 0
+    }
+
+    /// Returns whether the aces are kept in a count of their own, as they
+    /// are by systems that leave them out of the running count.
+    fn ace_side_count(&self) -> bool {
+        self.tags()[9] == 0
+    }
+}
+
+// The level one count most players learn first.
+pub struct HiLo;
+
+impl CountingSystem for HiLo {
+    fn name(&self) -> &'static str {
+        "Hi-Lo"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 1, 1, 1, 0, 0, 0, -1, -1]
+    }
+}
+
+// Knock-Out, which counts sevens as low cards too, so a shoe counts up as
+// it's dealt and isn't turned into a true count.
+pub struct Ko;
+
+impl CountingSystem for Ko {
+    fn name(&self) -> &'static str {
+        "KO"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 1, 1, 1, 1, 0, 0, -1, -1]
+    }
+
+    // Four lower for each deck past the first, so the pivot of +4 means
+    // about the same however many decks there are
+    fn initial_count(&self, decks: usize) -> i32 {
+        4 - 4 * decks as i32
+    }
+}
+
+// Hi-Opt I, which leaves the twos and the aces out.
+pub struct HiOptI;
+
+impl CountingSystem for HiOptI {
+    fn name(&self) -> &'static str {
+        "Hi-Opt I"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [0, 1, 1, 1, 1, 0, 0, 0, -1, 0]
+    }
+}
+
+// Hi-Opt II, a level two count leaving the aces out.
+pub struct HiOptII;
+
+impl CountingSystem for HiOptII {
+    fn name(&self) -> &'static str {
+        "Hi-Opt II"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 2, 2, 1, 1, 0, 0, -2, 0]
+    }
+}
+
+// Omega II, a level two count that takes the nines off and leaves the aces
+// out.
+pub struct OmegaII;
+
+impl CountingSystem for OmegaII {
+    fn name(&self) -> &'static str {
+        "Omega II"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 2, 2, 2, 1, 0, -1, -2, 0]
+    }
+}
+
+// The Zen count, a level two count that keeps the aces in.
+pub struct Zen;
+
+impl CountingSystem for Zen {
+    fn name(&self) -> &'static str {
+        "Zen"
+    }
+
+    fn tags(&self) -> [i32; 10] {
+        [1, 1, 2, 2, 2, 1, 0, 0, -2, -1]
+    }
+}
+
+// The counting systems to pick from in the settings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum System {
+    #[default]
+    HiLo,
+    Ko,
+    #[serde(rename = "hi-opt-i")]
+    HiOptI,
+    #[serde(rename = "hi-opt-ii")]
+    HiOptII,
+    #[serde(rename = "omega-ii")]
+    OmegaII,
+    Zen,
+}
+
+impl System {
+    pub const ALL: [System; 6] =
+        [System::HiLo, System::Ko, System::HiOptI, System::HiOptII, System::OmegaII, System::Zen];
+
+    /// Returns the system's way of counting.
+    pub fn counting(&self) -> &'static dyn CountingSystem {
+        match self {
+            System::HiLo => &HiLo,
+            System::Ko => &Ko,
+            System::HiOptI => &HiOptI,
+            System::HiOptII => &HiOptII,
+            System::OmegaII => &OmegaII,
+            System::Zen => &Zen,
+        }
+    }
+}
+
+// How the true count is rounded to the whole number bets and plays are
+// judged by, picked in the settings.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Rounding {
+    /// Down to the number below, so +2.7 is +2 and -0.5 is -1.
+    #[default]
+    Floor,
+    /// Towards zero, so +2.7 is +2 and -0.5 is 0.
+    Truncate,
+    /// To the nearest number, so +2.7 is +3 and -0.5 is -1.
+    Nearest,
+    /// Not rounded, but shown to a decimal place.
+    Exact,
+}
+
+impl Rounding {
+    pub const ALL: [Rounding; 4] = [Rounding::Floor, Rounding::Truncate, Rounding::Nearest, Rounding::Exact];
+
+    /// Rounds a true count.
+    pub fn round(&self, count: f32) -> f32 {
+        match self {
+            Rounding::Floor => count.floor(),
+            Rounding::Truncate => count.trunc(),
+            Rounding::Nearest => count.round(),
+            Rounding::Exact => count,
+        }
+    }
+}
+
+/// Returns the units a bet spread says to bet at a count: its first at +1
+/// or less, its second at +2 and so on, and its last at any count above.
+/// An empty spread bets a unit at every count.
+pub fn spread_units(spread: &[u32], count: f32) -> u32 {
+    let step = (count.floor() as i64 - 1).min(spread.len() as i64 - 1).max(0) as usize;
+    spread.get(step).copied().unwrap_or(1)
+}
+
+/// Returns the true count: the running count for each deck's worth of the
+/// `unseen` cards, rounded as asked.
+pub fn true_count(running: i32, unseen: usize, rounding: Rounding) -> f32 {
+    // The last few cards would make it run off, so there's always a card
+    let decks = unseen.max(1) as f32 / DECK;
+    rounding.round(running as f32 / decks)
+}
+
+// How close a run of answers came to the right ones, such as the counts
+// given at the quizzes in practice games, or the bets made against what
+// the bet spread said.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct QuizRecord {
+    /// Quizzes answered.
+    pub answered: u32,
+    /// Answers that were spot on.
+    pub right: u32,
+    /// How far off every answer was, added up.
+    pub off_by: u32,
+}
+
+impl QuizRecord {
+    /// Counts an answer `off_by` from the right one.
+    pub fn record(&mut self, off_by: u32) {
+        self.answered += 1;
+        self.right += u32::from(off_by == 0);
+        self.off_by += off_by;
+    }
+
+    /// Returns how far off the answers have been on average.
+    pub fn average_miss(&self) -> f64 {
+        if self.answered == 0 {
+            return 0.0;
+        }
+        self.off_by as f64 / self.answered as f64
+    }
+}
@@ -0,0 +1,37 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Today's daily challenge. Everyone playing on the same day (in UTC) gets
+// the same seed, and so the same shuffles.
+pub struct Daily {
+    /// The day, e.g. 2026-10-16.
+    pub date: String,
+    pub seed: u64,
+}
+
+impl Daily {
+    pub fn today() -> Self {
+        let days = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs() / 86_400);
+        Daily {
+            date: date_from_days(days as i64),
+            seed: days,
+        }
+    }
+}
+
+// Turns a count of days since 1970-01-01 into a calendar date, using the
+// civil-from-days algorithm from Howard Hinnant's date library
+fn date_from_days(days: i64) -> String {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_index + 2) / 5 + 1;
+    let month = if month_index < 10 { month_index + 3 } else { month_index - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
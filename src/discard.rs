@@ -0,0 +1,37 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::Card;
+
+// The cards dealt since the shoe was shuffled, put in the tray once the
+// round they were played in is over, as a dealer clears the table.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscardTray {
+    cards: Vec<Card>,
+}
+
+impl DiscardTray {
+    /// Puts a round's cards in the tray.
+    pub fn add(&mut self, cards: impl IntoIterator<Item = Card>) {
+        self.cards.extend(cards);
+    }
+
+    /// Empties the tray, as its cards go back into the shoe.
+    pub fn clear(&mut self) {
+        self.cards.clear();
+    }
+
+    /// Returns how many cards are in the tray.
+    pub fn count(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns how many cards of each value are in the tray, from the twos
+    /// up to the tens and picture cards and then the aces.
+    pub fn by_value(&self) -> [usize; 10] {
+        let mut counts = [0; 10];
+        for card in &self.cards {
+            counts[card.rank.value() as usize - 2] += 1;
+        }
+        counts
+    }
+}
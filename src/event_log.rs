@@ -0,0 +1,60 @@
+use std::collections::VecDeque;
+use unicode_width::UnicodeWidthStr;
+
+// Events kept before the oldest are let go
+const KEPT: usize = 500;
+
+// What's happened at the table this session, a line per event, such as a
+// card taken or the dealer busting, oldest first.
+#[derive(Default)]
+pub struct EventLog {
+    events: VecDeque<String>,
+}
+
+impl EventLog {
+    /// Adds an event as the newest.
+    pub fn push(&mut self, event: String) {
+        if self.events.len() == KEPT {
+            self.events.pop_front();
+        }
+        self.events.push_back(event);
+    }
+
+    /// Returns every event kept, a line each, oldest first.
+    pub fn lines(&self) -> Vec<String> {
+        self.events.iter().map(|event| format!("  {}", event)).collect()
+    }
+
+    /// Returns how many events are kept.
+    pub fn count(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Returns the newest of the first `shown` events, wrapped to fit
+    /// `width` columns, as the last `count` lines they take. Each is
+    /// indented two spaces, and any line it's wrapped onto four.
+    pub fn recent(&self, shown: usize, count: usize, width: usize) -> Vec<String> {
+        let mut lines = Vec::new();
+        for event in self.events.iter().take(shown).rev() {
+            let mut wrapped = Vec::new();
+            let mut current = String::from("  ");
+            for word in event.split(' ') {
+                if current.trim_start().is_empty() || current.width() + 1 + word.width() <= width {
+                    if !current.trim_start().is_empty() {
+                        current.push(' ');
+                    }
+                } else {
+                    wrapped.push(std::mem::replace(&mut current, String::from("    ")));
+                }
+                current.push_str(word);
+            }
+            wrapped.push(current);
+            lines.splice(0..0, wrapped);
+            if lines.len() >= count {
+                break;
+            }
+        }
+        let extra = lines.len().saturating_sub(count);
+        lines.split_off(extra)
+    }
+}
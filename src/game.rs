@@ -1,8 +1,14 @@
 use rand::seq::SliceRandom;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
 use std::fmt;
 
+use crate::count::CountingSystem;
+use crate::locale::tr;
+
 // Represents the four suits of a card deck.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Suit {
     Hearts,
     Diamonds,
@@ -11,7 +17,7 @@ pub enum Suit {
 }
 
 // Represents the 13 ranks of a card.
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
 pub enum Rank {
     Two,
     Three,
@@ -29,6 +35,23 @@ pub enum Rank {
 }
 
 impl Rank {
+    /// Every rank, from the twos up to the aces.
+    pub const ALL: [Rank; 13] = [
+        Rank::Two,
+        Rank::Three,
+        Rank::Four,
+        Rank::Five,
+        Rank::Six,
+        Rank::Seven,
+        Rank::Eight,
+        Rank::Nine,
+        Rank::Ten,
+        Rank::Jack,
+        Rank::Queen,
+        Rank::King,
+        Rank::Ace,
+    ];
+
     /// Returns the primary blackjack value for a card rank.
     /// Ace is initially counted as 11.
     pub fn value(&self) -> u8 {
@@ -45,25 +68,10 @@ impl Rank {
             Rank::Ace => 11,
         }
     }
-}
-
-// A single playing card with a suit and rank.
-#[derive(Debug, Clone, Copy)]
-pub struct Card {
-    pub suit: Suit,
-    pub rank: Rank,
-}
 
-impl Card {
-    pub fn new(suit: Suit, rank: Rank) -> Self {
-        Card { suit, rank }
-    }
-}
-
-// For displaying the card in a user-friendly way.
-impl fmt::Display for Card {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let rank = match self.rank {
+    /// Returns how the rank is written on a card, e.g. "10" or "K".
+    pub fn label(&self) -> &'static str {
+        match self {
             Rank::Two => "2",
             Rank::Three => "3",
             Rank::Four => "4",
@@ -77,64 +85,205 @@ impl fmt::Display for Card {
             Rank::Queen => "Q",
             Rank::King => "K",
             Rank::Ace => "A",
-        };
-        let suit = match self.suit {
+        }
+    }
+
+    /// Returns the rank as it's said, e.g. "10" or "king".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Rank::Jack => tr!("jack"),
+            Rank::Queen => tr!("queen"),
+            Rank::King => tr!("king"),
+            Rank::Ace => tr!("ace"),
+            _ => self.label(),
+        }
+    }
+}
+
+impl Suit {
+    pub fn symbol(&self) -> &'static str {
+        match self {
             Suit::Hearts => "♥",
             Suit::Diamonds => "♦",
             Suit::Clubs => "♣",
             Suit::Spades => "♠",
-        };
-        write!(f, "{}{}", rank, suit)
+        }
+    }
+
+    /// Returns the suit as it's said, e.g. "hearts".
+    pub fn name(&self) -> &'static str {
+        match self {
+            Suit::Hearts => tr!("hearts"),
+            Suit::Diamonds => tr!("diamonds"),
+            Suit::Clubs => tr!("clubs"),
+            Suit::Spades => tr!("spades"),
+        }
+    }
+
+    /// The suit as an emoji, two columns wide on terminals that show them.
+    pub fn emoji(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "♥\u{fe0f}",
+            Suit::Diamonds => "♦\u{fe0f}",
+            Suit::Clubs => "♣\u{fe0f}",
+            Suit::Spades => "♠\u{fe0f}",
+        }
+    }
+}
+
+// A single playing card with a suit and rank.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Card {
+    pub suit: Suit,
+    pub rank: Rank,
+}
+
+impl Card {
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        Card { suit, rank }
+    }
+}
+
+// For displaying the card in a user-friendly way.
+impl fmt::Display for Card {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.rank.label(), self.suit.symbol())
     }
 }
 
-// Represents a deck of cards.
-pub struct Deck {
+// A shoe holding one or more 52-card decks shuffled together. Saved games
+// keep the cards left in it and where its shuffle source is up to, so a
+// seeded shoe goes on shuffling the same way once it's loaded.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct Shoe {
     cards: Vec<Card>,
+    decks: usize,
+    // Number of cards left in the shoe when the cut card comes out
+    cut_card: usize,
+    // ChaCha shuffles the same way for a seed whatever version of rand
+    // the game's built with, unlike StdRng. Games saved before it was
+    // kept get a fresh one.
+    #[serde(default = "fresh_rng")]
+    rng: ChaCha8Rng,
 }
 
-impl Deck {
-    /// Creates a new, standard 52-card deck.
-    pub fn new() -> Self {
-        let mut cards = Vec::with_capacity(52);
+impl Shoe {
+    /// Creates a new, shuffled shoe with the given number of standard decks
+    /// and the cut card placed at `penetration` (a fraction of the shoe).
+    pub fn new(decks: usize, penetration: f32) -> Self {
+        Self::with_rng(decks, penetration, fresh_rng())
+    }
+
+    /// Creates a shoe that always shuffles the same way for the same seed,
+    /// so several players can be dealt an identical run of cards.
+    pub fn seeded(decks: usize, penetration: f32, seed: u64) -> Self {
+        Self::with_rng(decks, penetration, ChaCha8Rng::seed_from_u64(seed))
+    }
+
+    fn with_rng(decks: usize, penetration: f32, rng: ChaCha8Rng) -> Self {
+        let total = decks * 52;
+        let dealt_before_cut = (total as f32 * penetration.clamp(0.0, 1.0)) as usize;
+        let mut shoe = Shoe {
+            cards: Vec::with_capacity(total),
+            decks,
+            cut_card: total - dealt_before_cut,
+            rng,
+        };
+        shoe.reshuffle();
+        shoe
+    }
+
+    /// Returns every card back to the shoe and shuffles it.
+    pub fn reshuffle(&mut self) {
         let suits = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
-        let ranks = [
-            Rank::Two,
-            Rank::Three,
-            Rank::Four,
-            Rank::Five,
-            Rank::Six,
-            Rank::Seven,
-            Rank::Eight,
-            Rank::Nine,
-            Rank::Ten,
-            Rank::Jack,
-            Rank::Queen,
-            Rank::King,
-            Rank::Ace,
-        ];
-
-        for &suit in &suits {
-            for &rank in &ranks {
-                cards.push(Card::new(suit, rank));
+        self.cards.clear();
+        for _ in 0..self.decks {
+            for &suit in &suits {
+                for rank in Rank::ALL {
+                    self.cards.push(Card::new(suit, rank));
+                }
             }
         }
-        Deck { cards }
+
+        self.cards.shuffle(&mut self.rng);
+    }
+
+    /// Returns the number of cards left to deal.
+    pub fn remaining(&self) -> usize {
+        self.cards.len()
+    }
+
+    /// Returns how many cards the shoe holds when it's full.
+    pub fn size(&self) -> usize {
+        self.decks * 52
+    }
+
+    /// Returns how many cards are left in the shoe when the cut card comes
+    /// out.
+    pub fn cut_card(&self) -> usize {
+        self.cut_card
+    }
+
+    /// Returns how many cards worth ten are left to deal.
+    pub fn tens_left(&self) -> usize {
+        self.cards.iter().filter(|card| card.rank.value() == 10).count()
+    }
+
+    /// Returns how many aces are left to deal.
+    pub fn aces_left(&self) -> usize {
+        self.cards.iter().filter(|card| card.rank == Rank::Ace).count()
+    }
+
+    /// Returns the running count of the cards dealt since the shuffle, in
+    /// a counting system. It's what the full shoe's cards count to less
+    /// what the cards left do, from where the system starts.
+    pub fn running_count(&self, system: &dyn CountingSystem) -> i32 {
+        let full = 4 * self.decks as i32 * Rank::ALL.iter().map(|&rank| system.tag(rank)).sum::<i32>();
+        let left = self.cards.iter().map(|card| system.tag(card.rank)).sum::<i32>();
+        system.initial_count(self.decks) + full - left
+    }
+
+    /// Returns true once the cut card has come out, meaning the shoe is
+    /// reshuffled at the end of the current round.
+    pub fn cut_card_reached(&self) -> bool {
+        self.cards.len() <= self.cut_card
     }
 
-    /// Shuffles the deck randomly.
-    pub fn shuffle(&mut self) {
-        let mut rng = rand::rng();
-        self.cards.shuffle(&mut rng);
+    /// Deals one card from the top of the shoe. An empty shoe is reshuffled
+    /// first, so dealing never runs out mid-round.
+    pub fn deal(&mut self) -> Card {
+        if self.cards.is_empty() {
+            self.reshuffle();
+        }
+        self.cards.pop().expect("a reshuffled shoe is never empty")
     }
+}
+
+// A shuffle source seeded from the thread's random generator.
+fn fresh_rng() -> ChaCha8Rng {
+    ChaCha8Rng::from_rng(&mut rand::rng())
+}
+
+// The value of a hand, remembering whether an Ace is counted as 11.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HandValue {
+    pub total: u8,
+    pub soft: bool,
+}
 
-    /// Deals one card from the top of the deck.
-    pub fn deal(&mut self) -> Option<Card> {
-        self.cards.pop()
+// Soft totals below 21 show both ways of counting the Ace, e.g. "7/17".
+impl fmt::Display for HandValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.soft && self.total < 21 {
+            write!(f, "{}/{}", self.total - 10, self.total)
+        } else {
+            write!(f, "{}", self.total)
+        }
     }
 }
 
 // Represents a player's or dealer's hand.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Hand {
     cards: Vec<Card>,
 }
@@ -144,14 +293,58 @@ impl Hand {
         Hand { cards: Vec::new() }
     }
 
+    /// Returns the cards in the order they were dealt.
+    pub fn cards(&self) -> &[Card] {
+        &self.cards
+    }
+
+    /// Returns the number of cards in the hand.
+    pub fn len(&self) -> usize {
+        self.cards.len()
+    }
+
     /// Adds a card to the hand.
     pub fn add_card(&mut self, card: Card) {
         self.cards.push(card);
     }
 
+    /// Returns the dealer's face-up card, which is the second card dealt,
+    /// or the first when the dealer doesn't take a hole card, even after
+    /// the dealer's drawn to it.
+    pub fn up_card(&self, no_hole_card: bool) -> Option<Card> {
+        self.cards.get(usize::from(!no_hole_card)).copied()
+    }
+
+    /// Returns true if the hand is a natural 21 on its first two cards.
+    pub fn is_blackjack(&self) -> bool {
+        self.cards.len() == 2 && self.value() == 21
+    }
+
+    /// Returns true if the hand is exactly two cards of the same rank.
+    pub fn is_pair(&self) -> bool {
+        self.cards.len() == 2 && self.cards[0].rank == self.cards[1].rank
+    }
+
+    /// Removes the second card of a pair and returns it as a new hand.
+    pub fn split(&mut self) -> Hand {
+        let card = self.cards.pop().expect("cannot split an empty hand");
+        Hand { cards: vec![card] }
+    }
+
     /// Calculates the total value of the hand.
     /// It correctly handles the flexible value of Aces (1 or 11).
     pub fn value(&self) -> u8 {
+        self.total().total
+    }
+
+    /// Returns true if the hand's value counts an Ace as 11, so another
+    /// card can't bust it.
+    pub fn is_soft(&self) -> bool {
+        self.total().soft
+    }
+
+    /// Returns the hand value along with whether an Ace is still counted as 11.
+    pub fn total(&self) -> HandValue {
         let mut value = 0;
         let mut ace_count = 0;
 
@@ -168,7 +361,10 @@ impl Hand {
             ace_count -= 1;
         }
 
-        value
+        HandValue {
+            total: value,
+            soft: ace_count > 0,
+        }
     }
 
     /// Returns a string representation of all cards in the hand.
@@ -180,3 +376,79 @@ impl Hand {
             .join(" ")
     }
 }
+
+// Where a player's hand is in its play.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HandStatus {
+    Active,
+    Stood,
+    Busted,
+    // Reached the table's card-count limit without busting and wins outright
+    Charlie,
+    // A natural on the first two cards, paid out at settlement
+    Blackjack,
+    // Given up for half the bet back
+    Surrendered,
+}
+
+// A player's hand together with the bet riding on it and the betting spot
+// it was dealt to.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerHand {
+    pub hand: Hand,
+    pub bet: u32,
+    pub status: HandStatus,
+    pub spot: usize,
+}
+
+impl PlayerHand {
+    pub fn new(bet: u32, spot: usize) -> Self {
+        PlayerHand {
+            hand: Hand::new(),
+            bet,
+            status: HandStatus::Active,
+            spot,
+        }
+    }
+
+    /// Applies the split-aces rules once a split ace has its second card:
+    /// an ace and a ten can count as blackjack, and unless hitting split aces
+    /// is allowed the hand stands.
+    pub fn close_split_ace(&mut self, can_hit: bool, ten_is_blackjack: bool) {
+        if ten_is_blackjack && self.hand.is_blackjack() {
+            self.status = HandStatus::Blackjack;
+        } else if !can_hit && self.status == HandStatus::Active {
+            self.status = HandStatus::Stood;
+        }
+    }
+
+    /// Adds a card and closes the hand if it busted or reached 21.
+    pub fn add_card(&mut self, card: Card) {
+        self.hand.add_card(card);
+
+        let value = self.hand.value();
+        if value > 21 {
+            self.status = HandStatus::Busted;
+        } else if value == 21 {
+            self.status = HandStatus::Stood;
+        }
+    }
+}
+
+/// Returns what OBO rules give back when the dealer turns out to have
+/// blackjack: what doubling and splitting added to each spot's
+/// `original_bet`, but only out of hands still standing. A hand that
+/// busted was lost before the dealer turned blackjack over.
+pub fn original_bets_refund(hands: &[PlayerHand], original_bet: u32) -> u32 {
+    let spots = hands.iter().map(|h| h.spot + 1).max().unwrap_or(0);
+    (0..spots)
+        .map(|spot| {
+            let hands = hands.iter().filter(|h| h.spot == spot);
+            let (staked, standing) = hands.fold((0, 0), |(staked, standing), h| match h.status {
+                HandStatus::Active | HandStatus::Stood => (staked + h.bet, standing + h.bet),
+                _ => (staked + h.bet, standing),
+            });
+            standing.min(staked.saturating_sub(original_bet))
+        })
+        .sum()
+}
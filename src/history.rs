@@ -0,0 +1,465 @@
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use crate::game::{Card, Hand, PlayerHand};
+use crate::session::{HandOutcome, Session};
+use crate::stats::{Leaderboard, Scope, Stats};
+
+// Everything kept about a finished round.
+pub struct RoundRecord<'a> {
+    pub dealer: &'a Hand,
+    /// The dealer's up-card as it was dealt, before the dealer drew.
+    pub up_card: Option<Card>,
+    pub hands: &'a [PlayerHand],
+    /// How each of the hands was settled, in the same order.
+    pub outcomes: &'a [HandOutcome],
+    /// Decisions the player made, in the order they were taken.
+    pub actions: &'a [String],
+    /// What the round won or lost overall, side bets included.
+    pub net: i64,
+}
+
+// A round read back from the history for viewing.
+pub struct RoundSummary {
+    pub dealer_cards: String,
+    pub dealer_total: u8,
+    pub actions: String,
+    pub net: i64,
+    pub hands: Vec<HandSummary>,
+}
+
+pub struct HandSummary {
+    pub spot: usize,
+    pub cards: String,
+    pub total: u8,
+    pub bet: u32,
+    pub outcome: String,
+}
+
+// Every round played, stored in an SQLite database in the config directory
+// so stats can be worked out over any number of sessions.
+pub struct History {
+    conn: Connection,
+    /// The session being played, or 0 before one is started, which no
+    /// rounds belong to.
+    session_id: i64,
+    /// The player profile whose sessions make up the lifetime stats.
+    profile: String,
+}
+
+impl History {
+    /// Opens the history database, e.g. `~/.config/rustjack/history.db`.
+    /// Without a config directory the history is only kept in memory.
+    pub fn open(profile: &str) -> io::Result<Self> {
+        let conn = match dirs::config_dir().map(|dir| dir.join("rustjack")) {
+            Some(dir) => {
+                fs::create_dir_all(&dir)?;
+                let path = dir.join("history.db");
+                Connection::open(&path).map_err(|err| {
+                    io::Error::other(format!("can't open history {}: {}", path.display(), err))
+                })?
+            }
+            None => Connection::open_in_memory().map_err(io::Error::other)?,
+        };
+        Self::with_connection(conn, profile)
+    }
+
+    /// Opens a history that's only kept in memory, for games that don't
+    /// count towards the stats.
+    pub fn scratch(profile: &str) -> io::Result<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(io::Error::other)?, profile)
+    }
+
+    fn with_connection(conn: Connection, profile: &str) -> io::Result<Self> {
+        Self::create_tables(&conn).map_err(io::Error::other)?;
+        Ok(History {
+            conn,
+            session_id: 0,
+            profile: profile.to_string(),
+        })
+    }
+
+    /// Adds a row for a new session, which the rounds played from now on
+    /// belong to. Daily challenge sessions note the day they were for.
+    pub fn start_session(&mut self, daily: Option<&str>) -> io::Result<()> {
+        self.conn
+            .execute(
+                "INSERT INTO sessions (started_at, profile, daily) VALUES (?1, ?2, ?3)",
+                params![now(), self.profile, daily],
+            )
+            .map_err(io::Error::other)?;
+        self.session_id = self.conn.last_insert_rowid();
+        Ok(())
+    }
+
+    /// Carries on recording rounds into a session started earlier, such as
+    /// one from a saved game.
+    pub fn resume_session(&mut self, session_id: i64) {
+        self.session_id = session_id;
+    }
+
+    pub fn session_id(&self) -> i64 {
+        self.session_id
+    }
+
+    // Creates the tables on first use and brings them up to date
+    fn create_tables(conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch(
+            "CREATE TABLE IF NOT EXISTS sessions (
+                id INTEGER PRIMARY KEY,
+                started_at INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS rounds (
+                id INTEGER PRIMARY KEY,
+                session_id INTEGER NOT NULL REFERENCES sessions(id),
+                played_at INTEGER NOT NULL,
+                dealer_cards TEXT NOT NULL,
+                actions TEXT NOT NULL,
+                net INTEGER NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS hands (
+                round_id INTEGER NOT NULL REFERENCES rounds(id),
+                position INTEGER NOT NULL,
+                spot INTEGER NOT NULL,
+                cards TEXT NOT NULL,
+                bet INTEGER NOT NULL,
+                outcome TEXT NOT NULL
+            );",
+        )?;
+        migrate(conn)
+    }
+
+    /// Stores a finished round along with each of its hands.
+    pub fn record_round(&mut self, round: &RoundRecord) -> rusqlite::Result<()> {
+        let tx = self.conn.transaction()?;
+        tx.execute(
+            "INSERT INTO rounds (session_id, played_at, dealer_cards, actions, net, up_card, dealer_total)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                self.session_id,
+                now(),
+                round.dealer.display_str(),
+                round.actions.join(" "),
+                round.net,
+                round.up_card.map(|c| c.to_string()).unwrap_or_default(),
+                round.dealer.value()
+            ],
+        )?;
+        let round_id = tx.last_insert_rowid();
+
+        let hands = round.hands.iter().zip(round.outcomes);
+        for (position, (player_hand, outcome)) in hands.enumerate() {
+            tx.execute(
+                "INSERT INTO hands (round_id, position, spot, cards, bet, outcome, initial_cards, total)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+                params![
+                    round_id,
+                    position as i64,
+                    player_hand.spot as i64,
+                    player_hand.hand.display_str(),
+                    player_hand.bet,
+                    outcome.label(),
+                    initial_cards(&player_hand.hand),
+                    player_hand.hand.value()
+                ],
+            )?;
+        }
+        tx.commit()
+    }
+
+    /// Keeps the session's bests up to date for the leaderboard, given the
+    /// bankroll it's at now.
+    pub fn record_session(&self, session: &Session, bankroll: u32) -> rusqlite::Result<()> {
+        self.conn.execute(
+            "UPDATE sessions SET peak_bankroll = ?1, best_streak = ?2, net = ?3 WHERE id = ?4",
+            params![
+                session.peak_bankroll,
+                session.streaks.best_win,
+                session.net(bankroll),
+                self.session_id
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the best sessions of every profile, up to `limit` for each
+    /// kind of record, and the best scores in the daily challenge for `date`.
+    pub fn leaderboard(&self, limit: usize, date: &str) -> rusqlite::Result<Leaderboard> {
+        let top = |column: &str| -> rusqlite::Result<Vec<(String, i64)>> {
+            let mut statement = self.conn.prepare(&format!(
+                "SELECT profile, {column} FROM sessions WHERE {column} > 0
+                 ORDER BY {column} DESC, id LIMIT ?1"
+            ))?;
+            let rows = statement.query_map([limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect()
+        };
+
+        let mut statement = self.conn.prepare(
+            "SELECT profile, net FROM sessions WHERE daily = ?1
+             ORDER BY net DESC, id LIMIT ?2",
+        )?;
+        let daily = statement
+            .query_map(params![date, limit as i64], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        Ok(Leaderboard {
+            biggest_bankroll: top("peak_bankroll")?,
+            longest_streak: top("best_streak")?,
+            best_net: top("net")?,
+            daily,
+        })
+    }
+
+    /// Works out the stats for this session or for every session so far.
+    pub fn stats(&self, scope: Scope) -> rusqlite::Result<Stats> {
+        // A null session filter matches every round
+        let session = match scope {
+            Scope::Session => Some(self.session_id),
+            Scope::Lifetime => None,
+        };
+
+        let mut stats = self.conn.query_row(
+            "SELECT COUNT(DISTINCT session_id), COUNT(*), IFNULL(SUM(net), 0),
+                    IFNULL(MAX(net), 0), IFNULL(MIN(net), 0)
+             FROM rounds
+             WHERE (?1 IS NULL OR session_id = ?1)
+               AND session_id IN (SELECT id FROM sessions WHERE profile = ?2)",
+            params![session, self.profile],
+            |row| {
+                Ok(Stats {
+                    sessions: row.get(0)?,
+                    rounds: row.get(1)?,
+                    net: row.get(2)?,
+                    biggest_win: row.get::<_, i64>(3)?.max(0) as u32,
+                    biggest_loss: row.get::<_, i64>(4)?.min(0).unsigned_abs() as u32,
+                    ..Stats::default()
+                })
+            },
+        )?;
+
+        let mut statement = self.conn.prepare(
+            "SELECT hands.outcome, COUNT(*) FROM hands
+             JOIN rounds ON rounds.id = hands.round_id
+             WHERE (?1 IS NULL OR rounds.session_id = ?1)
+               AND rounds.session_id IN (SELECT id FROM sessions WHERE profile = ?2)
+             GROUP BY hands.outcome",
+        )?;
+        let rows = statement.query_map(params![session, self.profile], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, u32>(1)?))
+        })?;
+        for row in rows {
+            let (label, count) = row?;
+            if let Some(outcome) = HandOutcome::from_label(&label) {
+                stats.hands.record(outcome, count);
+            }
+        }
+
+        Ok(stats)
+    }
+
+    /// Returns how many rounds have been played this session.
+    pub fn session_rounds(&self) -> rusqlite::Result<usize> {
+        self.conn.query_row(
+            "SELECT COUNT(*) FROM rounds WHERE session_id = ?1",
+            [self.session_id],
+            |row| Ok(row.get::<_, i64>(0)? as usize),
+        )
+    }
+
+    /// Reads back one of this session's rounds, counting back from the
+    /// latest, which is 0.
+    pub fn session_round(&self, back: usize) -> rusqlite::Result<RoundSummary> {
+        let (round_id, mut round) = self.conn.query_row(
+            "SELECT id, dealer_cards, dealer_total, actions, net FROM rounds
+             WHERE session_id = ?1 ORDER BY id DESC LIMIT 1 OFFSET ?2",
+            params![self.session_id, back as i64],
+            |row| {
+                let round = RoundSummary {
+                    dealer_cards: row.get(1)?,
+                    dealer_total: row.get(2)?,
+                    actions: row.get(3)?,
+                    net: row.get(4)?,
+                    hands: Vec::new(),
+                };
+                Ok((row.get::<_, i64>(0)?, round))
+            },
+        )?;
+
+        let mut statement = self.conn.prepare(
+            "SELECT spot, cards, total, bet, outcome FROM hands
+             WHERE round_id = ?1 ORDER BY position",
+        )?;
+        let hands = statement.query_map([round_id], |row| {
+            Ok(HandSummary {
+                spot: row.get::<_, i64>(0)? as usize,
+                cards: row.get(1)?,
+                total: row.get(2)?,
+                bet: row.get(3)?,
+                outcome: row.get(4)?,
+            })
+        })?;
+        round.hands = hands.collect::<rusqlite::Result<_>>()?;
+
+        Ok(round)
+    }
+
+    /// Reads back up to `limit` of this session's latest rounds, newest
+    /// first, without their hands.
+    pub fn recent_rounds(&self, limit: usize) -> rusqlite::Result<Vec<RoundSummary>> {
+        let mut statement = self.conn.prepare(
+            "SELECT dealer_cards, dealer_total, actions, net FROM rounds
+             WHERE session_id = ?1 ORDER BY id DESC LIMIT ?2",
+        )?;
+        let rounds = statement.query_map(params![self.session_id, limit as i64], |row| {
+            Ok(RoundSummary {
+                dealer_cards: row.get(0)?,
+                dealer_total: row.get(1)?,
+                actions: row.get(2)?,
+                net: row.get(3)?,
+                hands: Vec::new(),
+            })
+        })?;
+        rounds.collect()
+    }
+
+    /// Returns the session's and lifetime stats as pretty-printed JSON. The
+    /// session is left out when none has been started.
+    pub fn export_json(&self) -> io::Result<String> {
+        let session = match self.session_id {
+            0 => None,
+            _ => Some(self.stats(Scope::Session).map_err(io::Error::other)?),
+        };
+        let export = StatsExport {
+            session,
+            lifetime: self.stats(Scope::Lifetime).map_err(io::Error::other)?,
+        };
+        serde_json::to_string_pretty(&export).map_err(io::Error::other)
+    }
+
+    /// Writes every hand played this session to a CSV file, one row per
+    /// hand, and returns how many hands were written.
+    pub fn export_csv(&self, path: &Path) -> io::Result<usize> {
+        let mut statement = self
+            .conn
+            .prepare(
+                "SELECT rounds.id, rounds.played_at, hands.spot, hands.initial_cards,
+                        rounds.up_card, rounds.actions, hands.cards, hands.total,
+                        rounds.dealer_cards, rounds.dealer_total, hands.bet, hands.outcome
+                 FROM hands JOIN rounds ON rounds.id = hands.round_id
+                 WHERE rounds.session_id = ?1
+                 ORDER BY rounds.id, hands.position",
+            )
+            .map_err(io::Error::other)?;
+        let rows = statement
+            .query_map([self.session_id], |row| {
+                let fields = [
+                    row.get::<_, i64>(0)?.to_string(),
+                    row.get::<_, i64>(1)?.to_string(),
+                    (row.get::<_, i64>(2)? + 1).to_string(),
+                    row.get(3)?,
+                    row.get(4)?,
+                    row.get(5)?,
+                    row.get(6)?,
+                    row.get::<_, i64>(7)?.to_string(),
+                    row.get(8)?,
+                    row.get::<_, i64>(9)?.to_string(),
+                    row.get::<_, i64>(10)?.to_string(),
+                    row.get(11)?,
+                ];
+                Ok(fields.map(|f| csv_field(&f)).join(","))
+            })
+            .map_err(io::Error::other)?;
+
+        let mut csv = String::from(
+            "round,played_at,spot,initial_cards,dealer_up_card,actions,final_cards,total,dealer_cards,dealer_total,bet,result\n",
+        );
+        let mut hands = 0;
+        for row in rows {
+            csv.push_str(&row.map_err(io::Error::other)?);
+            csv.push('\n');
+            hands += 1;
+        }
+        fs::write(path, csv)?;
+        Ok(hands)
+    }
+
+    /// Returns a file name for exporting this session, unique to it.
+    pub fn export_name(&self, extension: &str) -> String {
+        format!("rustjack-session-{}.{}", self.session_id, extension)
+    }
+}
+
+// The stats as exported to JSON.
+#[derive(Serialize)]
+struct StatsExport {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    session: Option<Stats>,
+    lifetime: Stats,
+}
+
+// Brings a database made by an older version up to the current schema,
+// tracked with SQLite's user_version.
+fn migrate(conn: &Connection) -> rusqlite::Result<()> {
+    let version: i64 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        // Starting cards and totals, for exporting the hand history
+        conn.execute_batch(
+            "ALTER TABLE rounds ADD COLUMN up_card TEXT NOT NULL DEFAULT '';
+             ALTER TABLE rounds ADD COLUMN dealer_total INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE hands ADD COLUMN initial_cards TEXT NOT NULL DEFAULT '';
+             ALTER TABLE hands ADD COLUMN total INTEGER NOT NULL DEFAULT 0;
+             PRAGMA user_version = 1;",
+        )?;
+    }
+    if version < 2 {
+        // Sessions from before profiles belong to the default one
+        conn.execute_batch(
+            "ALTER TABLE sessions ADD COLUMN profile TEXT NOT NULL DEFAULT 'default';
+             PRAGMA user_version = 2;",
+        )?;
+    }
+    if version < 3 {
+        // Session bests, for the leaderboard
+        conn.execute_batch(
+            "ALTER TABLE sessions ADD COLUMN peak_bankroll INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE sessions ADD COLUMN best_streak INTEGER NOT NULL DEFAULT 0;
+             ALTER TABLE sessions ADD COLUMN net INTEGER NOT NULL DEFAULT 0;
+             PRAGMA user_version = 3;",
+        )?;
+    }
+    if version < 4 {
+        // The day a daily challenge session was played for
+        conn.execute_batch(
+            "ALTER TABLE sessions ADD COLUMN daily TEXT;
+             PRAGMA user_version = 4;",
+        )?;
+    }
+    Ok(())
+}
+
+// The first two cards a hand was played from, which after a split are the
+// card it kept and the one dealt to it.
+fn initial_cards(hand: &Hand) -> String {
+    let cards: Vec<String> = hand.cards().iter().take(2).map(|c| c.to_string()).collect();
+    cards.join(" ")
+}
+
+// Quotes a CSV field if it holds anything that would break the row up.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+// Seconds since the Unix epoch, used to timestamp sessions and rounds.
+fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64)
+}
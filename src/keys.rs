@@ -0,0 +1,388 @@
+use crossterm::event::KeyCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale;
+
+// A key an action is bound to. Letters match in either case.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Key(KeyCode);
+
+impl Key {
+    const fn char(c: char) -> Self {
+        Key(KeyCode::Char(c))
+    }
+
+    pub fn code(&self) -> KeyCode {
+        self.0
+    }
+
+    pub fn matches(&self, code: KeyCode) -> bool {
+        match (self.0, code) {
+            (KeyCode::Char(bound), KeyCode::Char(pressed)) => bound.eq_ignore_ascii_case(&pressed),
+            (bound, pressed) => bound == pressed,
+        }
+    }
+}
+
+impl From<KeyCode> for Key {
+    fn from(code: KeyCode) -> Self {
+        match code {
+            KeyCode::Char(c) => Key::char(c.to_ascii_lowercase()),
+            code => Key(code),
+        }
+    }
+}
+
+// Keys are written the way they're shown in the controls, e.g. "H" or "Enter".
+impl fmt::Display for Key {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Backspace => write!(f, "Bksp"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            code => write!(f, "{:?}", code),
+        }
+    }
+}
+
+// Parses a single character or a key name such as "enter" or "f2".
+impl FromStr for Key {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut chars = s.chars();
+        if let (Some(c), None) = (chars.next(), chars.next()) {
+            return Ok(Key::char(c.to_ascii_lowercase()));
+        }
+        let code = match s.to_ascii_lowercase().as_str() {
+            "space" => KeyCode::Char(' '),
+            "enter" => KeyCode::Enter,
+            "esc" => KeyCode::Esc,
+            "backspace" | "bksp" => KeyCode::Backspace,
+            "tab" => KeyCode::Tab,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            name => match name.strip_prefix('f').and_then(|n| n.parse().ok()) {
+                Some(n @ 1..=12) => KeyCode::F(n),
+                _ => return Err(format!("unknown key '{}'", s)),
+            },
+        };
+        Ok(Key(code))
+    }
+}
+
+// Lets the config file store keys by name.
+impl TryFrom<String> for Key {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Key> for String {
+    fn from(key: Key) -> Self {
+        match key.0 {
+            KeyCode::Char(' ') => String::from("space"),
+            KeyCode::Char(c) => c.to_string(),
+            KeyCode::Backspace => String::from("backspace"),
+            _ => key.to_string().to_lowercase(),
+        }
+    }
+}
+
+// The set of keys the bindings start from, before any the player remapped.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum KeyScheme {
+    /// Actions on their initial letters, arrow keys to move.
+    #[default]
+    Standard,
+    /// hjkl to move as well as the arrows, and vim-like mnemonics for the
+    /// actions, e.g. `i` to take a card in and `o` to stay out.
+    Vim,
+}
+
+impl FromStr for KeyScheme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standard" => Ok(KeyScheme::Standard),
+            "vim" => Ok(KeyScheme::Vim),
+            _ => Err(format!("unknown key scheme '{}', expected standard or vim", s)),
+        }
+    }
+}
+
+// The key each action is bound to: the scheme's keys, with any remapped in
+// the `[keys]` table of the config file. The digits typing a bet aren't
+// remappable, and the arrow keys always move.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KeyBindings {
+    pub hit: Key,
+    pub stand: Key,
+    pub double: Key,
+    pub split: Key,
+    pub surrender: Key,
+    /// Accepts an offer such as insurance or even money.
+    pub yes: Key,
+    /// Declines an offer.
+    pub no: Key,
+    pub deal: Key,
+    pub undo: Key,
+    pub clear: Key,
+    /// Bets the most the table and bankroll allow, or the least if that's
+    /// bet already.
+    pub bet_limit: Key,
+    pub new_round: Key,
+    pub rebet: Key,
+    /// Plays one betting spot from the next round, or two or three.
+    pub one_spot: Key,
+    pub two_spots: Key,
+    pub three_spots: Key,
+    pub lucky_ladies: Key,
+    pub match_the_dealer: Key,
+    pub royal_match: Key,
+    pub buster: Key,
+    pub stats: Key,
+    pub history: Key,
+    /// Opens the full log of what's happened at the table.
+    pub log: Key,
+    /// Shows or hides what's in the discard tray.
+    pub discards: Key,
+    pub export_csv: Key,
+    pub export_json: Key,
+    pub keep_playing: Key,
+    pub cash_out: Key,
+    pub rebuy: Key,
+    pub save: Key,
+    pub load: Key,
+    pub settings: Key,
+    pub help: Key,
+    pub menu: Key,
+    pub quit: Key,
+    pub up: Key,
+    pub down: Key,
+    pub left: Key,
+    pub right: Key,
+}
+
+impl KeyBindings {
+    /// The names of the remappable actions, as used in the `[keys]` table.
+    pub const ACTIONS: [&'static str; 39] = [
+        "hit",
+        "stand",
+        "double",
+        "split",
+        "surrender",
+        "yes",
+        "no",
+        "deal",
+        "undo",
+        "clear",
+        "bet_limit",
+        "new_round",
+        "rebet",
+        "one_spot",
+        "two_spots",
+        "three_spots",
+        "lucky_ladies",
+        "match_the_dealer",
+        "royal_match",
+        "buster",
+        "stats",
+        "history",
+        "log",
+        "discards",
+        "export_csv",
+        "export_json",
+        "keep_playing",
+        "cash_out",
+        "rebuy",
+        "save",
+        "load",
+        "settings",
+        "help",
+        "menu",
+        "quit",
+        "up",
+        "down",
+        "left",
+        "right",
+    ];
+
+    /// Returns a scheme's bindings with the player's remapped keys, given
+    /// by action name, applied on top.
+    pub fn new(scheme: KeyScheme, remapped: &BTreeMap<String, Key>) -> Result<Self, String> {
+        let mut keys = match scheme {
+            KeyScheme::Standard => KeyBindings::default(),
+            KeyScheme::Vim => KeyBindings::vim(),
+        };
+        for (action, &key) in remapped {
+            *keys
+                .key_mut(action)
+                .ok_or_else(|| format!("unknown action '{}' in [keys]", action))? = key;
+        }
+        Ok(keys)
+    }
+
+    fn vim() -> Self {
+        KeyBindings {
+            hit: Key::char('i'),
+            stand: Key::char('o'),
+            split: Key::char('s'),
+            surrender: Key::char('x'),
+            undo: Key::char('u'),
+            rebet: Key::char('.'),
+            up: Key::char('k'),
+            down: Key::char('j'),
+            left: Key::char('h'),
+            right: Key::char('l'),
+            ..KeyBindings::default()
+        }
+    }
+
+    /// Returns the key an action is bound to, by name.
+    pub fn key(&self, action: &str) -> Option<Key> {
+        self.clone().key_mut(action).copied()
+    }
+
+    fn key_mut(&mut self, action: &str) -> Option<&mut Key> {
+        Some(match action {
+            "hit" => &mut self.hit,
+            "stand" => &mut self.stand,
+            "double" => &mut self.double,
+            "split" => &mut self.split,
+            "surrender" => &mut self.surrender,
+            "yes" => &mut self.yes,
+            "no" => &mut self.no,
+            "deal" => &mut self.deal,
+            "undo" => &mut self.undo,
+            "clear" => &mut self.clear,
+            "bet_limit" => &mut self.bet_limit,
+            "new_round" => &mut self.new_round,
+            "rebet" => &mut self.rebet,
+            "one_spot" => &mut self.one_spot,
+            "two_spots" => &mut self.two_spots,
+            "three_spots" => &mut self.three_spots,
+            "lucky_ladies" => &mut self.lucky_ladies,
+            "match_the_dealer" => &mut self.match_the_dealer,
+            "royal_match" => &mut self.royal_match,
+            "buster" => &mut self.buster,
+            "stats" => &mut self.stats,
+            "history" => &mut self.history,
+            "log" => &mut self.log,
+            "discards" => &mut self.discards,
+            "export_csv" => &mut self.export_csv,
+            "export_json" => &mut self.export_json,
+            "keep_playing" => &mut self.keep_playing,
+            "cash_out" => &mut self.cash_out,
+            "rebuy" => &mut self.rebuy,
+            "save" => &mut self.save,
+            "load" => &mut self.load,
+            "settings" => &mut self.settings,
+            "help" => &mut self.help,
+            "menu" => &mut self.menu,
+            "quit" => &mut self.quit,
+            "up" => &mut self.up,
+            "down" => &mut self.down,
+            "left" => &mut self.left,
+            "right" => &mut self.right,
+            _ => return None,
+        })
+    }
+
+    // F1 brings up the help whatever the bindings are
+    pub fn opens_help(&self, code: KeyCode) -> bool {
+        code == KeyCode::F(1) || self.help.matches(code)
+    }
+
+    // Moving works with the arrow keys whatever the bindings are
+    pub fn moves_up(&self, code: KeyCode) -> bool {
+        code == KeyCode::Up || self.up.matches(code)
+    }
+
+    pub fn moves_down(&self, code: KeyCode) -> bool {
+        code == KeyCode::Down || self.down.matches(code)
+    }
+
+    pub fn moves_left(&self, code: KeyCode) -> bool {
+        code == KeyCode::Left || self.left.matches(code)
+    }
+
+    pub fn moves_right(&self, code: KeyCode) -> bool {
+        code == KeyCode::Right || self.right.matches(code)
+    }
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        KeyBindings {
+            hit: Key::char('h'),
+            stand: Key::char('s'),
+            double: Key::char('d'),
+            split: Key::char('p'),
+            surrender: Key::char('u'),
+            yes: Key::char('y'),
+            no: Key::char('n'),
+            deal: Key(KeyCode::Enter),
+            undo: Key(KeyCode::Backspace),
+            clear: Key::char('c'),
+            bet_limit: Key::char('m'),
+            new_round: Key::char('n'),
+            rebet: Key(KeyCode::Enter),
+            one_spot: Key::char('1'),
+            two_spots: Key::char('2'),
+            three_spots: Key::char('3'),
+            lucky_ladies: Key::char('l'),
+            match_the_dealer: Key::char('m'),
+            royal_match: Key::char('r'),
+            buster: Key::char('b'),
+            stats: Key::char('t'),
+            history: Key::char('v'),
+            log: Key::char('g'),
+            discards: Key::char('w'),
+            export_csv: Key::char('e'),
+            export_json: Key::char('j'),
+            keep_playing: Key::char('c'),
+            cash_out: Key::char('x'),
+            rebuy: Key::char('r'),
+            save: Key::char('s'),
+            load: Key::char('l'),
+            settings: Key::char('o'),
+            help: Key::char('?'),
+            menu: Key(KeyCode::Esc),
+            quit: Key::char('q'),
+            up: Key(KeyCode::Up),
+            down: Key(KeyCode::Down),
+            left: Key(KeyCode::Left),
+            right: Key(KeyCode::Right),
+        }
+    }
+}
+
+/// Returns an action's name as it's shown, e.g. "Match the dealer", in
+/// the language picked.
+pub fn label(action: &str) -> String {
+    let mut label = action.replace('_', " ");
+    if let Some(first) = label.get_mut(0..1) {
+        first.make_ascii_uppercase();
+    }
+    // The catalogs have each name as it's written in English here
+    locale::text(&label).to_string()
+}
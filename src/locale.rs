@@ -0,0 +1,217 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::sync::{OnceLock, RwLock};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+// The language everything's written in, picked in the settings. The game
+// is written in English, and the other languages look each English string
+// up in a catalog of translations, so anything not translated yet is left
+// in English rather than missing.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Locale {
+    #[default]
+    #[serde(rename = "en")]
+    English,
+    #[serde(rename = "es")]
+    Spanish,
+    #[serde(rename = "de")]
+    German,
+    #[serde(rename = "pl")]
+    Polish,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::English, Locale::Spanish, Locale::German, Locale::Polish];
+
+    /// Returns the language's name in its own words, as the settings list
+    /// it.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Locale::English => "English",
+            Locale::Spanish => "Español",
+            Locale::German => "Deutsch",
+            Locale::Polish => "Polski",
+        }
+    }
+
+    // How the language writes numbers: what separates the thousands, what
+    // marks the decimals, and whether a currency symbol follows the amount
+    fn numbers(&self) -> (&'static str, &'static str, bool) {
+        match self {
+            Locale::English => (",", ".", false),
+            Locale::Spanish | Locale::German => (".", ",", true),
+            Locale::Polish => (" ", ",", true),
+        }
+    }
+
+    // Returns the language's catalog, each English string with its
+    // translation, read the first time it's needed. English has none.
+    fn catalog(&self) -> Option<&'static HashMap<String, String>> {
+        static CATALOGS: [OnceLock<HashMap<String, String>>; 3] = [const { OnceLock::new() }; 3];
+        let (index, source) = match self {
+            Locale::English => return None,
+            Locale::Spanish => (0, include_str!("../locales/es.toml")),
+            Locale::German => (1, include_str!("../locales/de.toml")),
+            Locale::Polish => (2, include_str!("../locales/pl.toml")),
+        };
+        Some(CATALOGS[index].get_or_init(|| toml::from_str(source).expect("the catalogs are valid TOML")))
+    }
+}
+
+/// How amounts of chips are written. Anything not given follows the
+/// language, as in $1,250 or 1.250 €.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Currency {
+    /// The currency symbol written with amounts, if any.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub symbol: String,
+    /// Whether the symbol goes after the amount rather than before it.
+    pub symbol_after: Option<bool>,
+    /// What separates each three digits, such as "," or ".".
+    pub thousands_separator: Option<String>,
+    /// What marks the decimals, such as "." or ",".
+    pub decimal_separator: Option<String>,
+}
+
+impl Currency {
+    /// The symbols the settings offer, none first.
+    pub const SYMBOLS: [&'static str; 5] = ["", "$", "€", "£", "zł"];
+
+    const NONE: Currency =
+        Currency { symbol: String::new(), symbol_after: None, thousands_separator: None, decimal_separator: None };
+}
+
+// The language picked, by its place in `Locale::ALL`
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+
+// How amounts are written as well
+static CURRENCY: RwLock<Currency> = RwLock::new(Currency::NONE);
+
+/// Writes everything from now on in a language, with amounts written as
+/// `currency` says.
+pub fn set(locale: Locale, currency: &Currency) {
+    let index = Locale::ALL.iter().position(|l| *l == locale).unwrap_or(0);
+    CURRENT.store(index, Ordering::SeqCst);
+    *CURRENCY.write().unwrap_or_else(|err| err.into_inner()) = currency.clone();
+}
+
+/// Returns the language everything's written in.
+pub fn current() -> Locale {
+    Locale::ALL[CURRENT.load(Ordering::SeqCst)]
+}
+
+/// Returns an English string in the language picked, or as it is where
+/// there's no translation for it. `tr!` calls this for string literals.
+pub fn text(english: &str) -> &str {
+    current().catalog().and_then(|catalog| catalog.get(english)).map_or(english, String::as_str)
+}
+
+/// Returns an amount of chips as it's written, such as $1,250 or 1.250 €.
+pub fn money(amount: impl Into<i64>) -> String {
+    let amount = amount.into();
+    let currency = CURRENCY.read().unwrap_or_else(|err| err.into_inner());
+    let (thousands, _, after) = current().numbers();
+    let thousands = currency.thousands_separator.as_deref().unwrap_or(thousands);
+    let digits = amount.unsigned_abs().to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            grouped.push_str(thousands);
+        }
+        grouped.push(digit);
+    }
+    let sign = if amount < 0 { "-" } else { "" };
+    match currency.symbol.as_str() {
+        "" => format!("{}{}", sign, grouped),
+        symbol if currency.symbol_after.unwrap_or(after) => format!("{}{} {}", sign, grouped, symbol),
+        symbol => format!("{}{}{}", sign, symbol, grouped),
+    }
+}
+
+/// Returns a change in chips as it's written, with its sign even when it's
+/// a gain, such as +$50.
+pub fn net(amount: i64) -> String {
+    match amount {
+        1.. => format!("+{}", money(amount)),
+        _ => money(amount),
+    }
+}
+
+// Returns a number written with decimals, marked as the language or the
+// currency has them
+fn decimals(number: &str) -> String {
+    let currency = CURRENCY.read().unwrap_or_else(|err| err.into_inner());
+    let (_, decimal, _) = current().numbers();
+    number.replace('.', currency.decimal_separator.as_deref().unwrap_or(decimal))
+}
+
+/// Returns a number with as many decimals as given, such as 0,32 in a
+/// language that marks decimals with a comma.
+pub fn decimal(number: f64, places: usize) -> String {
+    decimals(&format!("{:.*}", places, number))
+}
+
+/// Fills in the placeholders in a string as `format!` would, with the
+/// arguments given. A translation can take them in another order than the
+/// English does: `{}` is the next argument, and `{1}` the second. The
+/// formats the game uses after a colon, such as `{:+}`, `{:+.1}` or
+/// `{0:.2}`, are kept, and numbers with decimals are marked as the language
+/// has them.
+pub fn format(template: &str, args: &[&dyn fmt::Display]) -> String {
+    let mut text = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut rest = template;
+    while let Some(start) = rest.find(['{', '}']) {
+        text.push_str(&rest[..start]);
+        let brace = &rest[start..];
+        // Doubled braces stand for themselves
+        if brace.starts_with("{{") || brace.starts_with("}}") {
+            text.push_str(&brace[..1]);
+            rest = &brace[2..];
+            continue;
+        }
+        let Some(end) = brace.find('}').filter(|_| brace.starts_with('{')) else {
+            text.push_str(&brace[..1]);
+            rest = &brace[1..];
+            continue;
+        };
+        let (index, spec) = brace[1..end].split_once(':').unwrap_or((&brace[1..end], ""));
+        let index = index.parse().unwrap_or_else(|_| {
+            next += 1;
+            next - 1
+        });
+        if let Some(arg) = args.get(index) {
+            // Writing into a string can't fail
+            let _ = match spec {
+                "+" => write!(text, "{:+}", arg),
+                ".1" => write!(text, "{}", decimals(&format!("{:.1}", arg))),
+                "+.1" => write!(text, "{}", decimals(&format!("{:+.1}", arg))),
+                ".2" => write!(text, "{}", decimals(&format!("{:.2}", arg))),
+                "<2" => write!(text, "{:<2}", arg),
+                _ => write!(text, "{}", arg),
+            };
+        }
+        rest = &brace[end + 1..];
+    }
+    text.push_str(rest);
+    text
+}
+
+/// Returns a string literal in the language picked, as `&str` on its own,
+/// or with its placeholders filled in as a `String` when there are
+/// arguments, as `format!` does.
+macro_rules! tr {
+    ($english:literal) => {
+        $crate::locale::text($english)
+    };
+    ($english:literal, $($arg:expr),+ $(,)?) => {
+        $crate::locale::format(
+            $crate::locale::text($english),
+            &[$(&$arg as &dyn ::std::fmt::Display),+],
+        )
+    };
+}
+
+pub(crate) use tr;
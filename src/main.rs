@@ -1,356 +1,4103 @@
 use crossterm::{
     cursor,
-    event::{self, Event, KeyCode, KeyEvent},
-    execute, queue,
-    terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen, Clear, ClearType, size},
+    event::{Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind},
+    queue,
+    style::{Attribute, Color, Print, SetAttribute, SetBackgroundColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
 };
+use serde::{Deserialize, Serialize};
+use std::cell::RefCell;
+use std::fmt;
 use std::io::{self, Write};
+use std::iter;
+use std::path::Path;
+use std::time::{Duration, Instant};
 use unicode_width::UnicodeWidthStr;
 
+mod animation;
+mod backend;
+mod big_text;
+mod buffer;
+mod card_art;
+mod cast;
+mod chips;
+mod cli;
+mod config;
+mod count;
+mod daily;
+mod discard;
+mod event_log;
 mod game;
-use game::{Deck, Hand};
+mod history;
+mod keys;
+mod locale;
+mod menu;
+mod mouse;
+mod narration;
+mod profile;
+mod replay;
+mod rules;
+mod save;
+mod screen;
+mod session;
+mod settings;
+mod side_bets;
+mod sim;
+mod stats;
+mod theme;
+mod title;
+mod trainer;
+#[cfg(feature = "ratatui")]
+mod tui;
+use animation::{AnimationSpeed, Dealing, Seat, FRAME_TIME};
+use backend::{Backend, Crossterm};
+use buffer::Screen;
+use card_art::{CardBack, CardSize};
+use cast::Cast;
+use chips::{Chip, ChipStack};
+use clap::Parser;
+use cli::{Cli, Command, FrameArgs, PlayArgs, SimArgs, StatsArgs};
+use game::{original_bets_refund, Card, Hand, HandStatus, PlayerHand, Rank, Shoe};
+use history::{History, RoundRecord};
+use keys::KeyBindings;
+use locale::tr;
+use menu::{MainMenu, MenuItem};
+use mouse::{Button, Click};
+use narration::Narrator;
+use profile::ProfileScreen;
+use config::{Config, DEFAULT_PROFILE};
+use count::{CountingSystem, QuizRecord, Rounding, System};
+use daily::Daily;
+use discard::DiscardTray;
+use event_log::EventLog;
+use replay::{Frame, Recorder, Replay};
+use rules::{Payout, Preset, Rules};
+use save::SavedGame;
+use settings::Settings;
+use session::{HandOutcome, Session, SessionLimit};
+use side_bets::{RoundCards, SideBet};
+use sim::Simulator;
+use stats::{Leaderboard, Scope, Stats};
+use theme::Palette;
+use trainer::Trainer;
 
+const STARTING_BANKROLL: u32 = 1000;
+const SIDE_BET_STAKE: u32 = 5;
+// The most a bet can be typed up to, well over any table's limit
+const MAX_TYPED_BET: u32 = 99_999;
+// The save slot the game is kept in as it's played
+const AUTOSAVE: &str = "autosave";
+const SAVE_SLOTS: usize = 3;
+const POPUP_WIDTH: u16 = 50;
+// Cards are drawn as boxes in terminals at least this tall, and as text in
+// shorter ones
+const CARD_ART_HEIGHT: u16 = 30;
+// Terminals smaller than this get the compact table, down to the smallest
+// one it fits in
+const COMFORTABLE_WIDTH: u16 = 60;
+const COMFORTABLE_HEIGHT: u16 = 22;
+const MIN_WIDTH: u16 = 40;
+const MIN_HEIGHT: u16 = 12;
+// Terminals at least this big get the wide table, with bigger cards and a
+// panel either side of it
+const WIDE_WIDTH: u16 = 128;
+const WIDE_HEIGHT: u16 = 36;
+const WIDE_TABLE_WIDTH: u16 = 72;
+const PANEL_WIDTH: u16 = 27;
+// Terminals at least this big get the large print table, if it's picked
+const LARGE_PRINT_WIDTH: u16 = 80;
+const LARGE_PRINT_HEIGHT: u16 = 40;
+// Rounds listed in the wide table's panel
+const RECENT_ROUNDS: usize = 5;
+
+// Columns between hands laid out side by side
+const HAND_GAP: usize = 2;
+
+// How each value is written in the discard tray, from the twos up
+const VALUE_LABELS: [&str; 9] = ["2", "3", "4", "5", "6", "7", "8", "9", "10"];
+
+// Columns of the gauge of how far into the shoe the game is
+const SHOE_GAUGE_WIDTH: usize = 12;
+
+// Lines of the event log shown in the wide table's panel, and in the strip
+// under the other tables where there's room for it
+const LOG_PANEL_LINES: usize = 8;
+const LOG_STRIP_LINES: usize = 3;
+
+// How the table is laid out, going by the room the terminal has
+#[derive(Clone, Copy, PartialEq)]
+enum Layout {
+    /// Cards drawn as boxes.
+    Cards,
+    /// Cards written as text, such as `10♥ K♠`.
+    Text,
+    /// A narrower table with a line for each hand and only the main keys.
+    Compact,
+    /// A wider table with bigger cards, and the session and the shoe in
+    /// panels beside it.
+    Wide,
+    /// A wider table still with the biggest cards, their ranks and the
+    /// totals written in big text.
+    LargePrint,
+}
+
+impl Layout {
+    /// Returns the roomiest layout that fits a terminal, or None if even
+    /// the compact one doesn't. Large print takes the place of the others
+    /// where it fits, and falls back to them where it doesn't.
+    fn fitting(width: u16, height: u16, large_print: bool) -> Option<Layout> {
+        if large_print && width >= LARGE_PRINT_WIDTH && height >= LARGE_PRINT_HEIGHT {
+            Some(Layout::LargePrint)
+        } else if width < MIN_WIDTH || height < MIN_HEIGHT {
+            None
+        } else if width < COMFORTABLE_WIDTH || height < COMFORTABLE_HEIGHT {
+            Some(Layout::Compact)
+        } else if height < CARD_ART_HEIGHT {
+            Some(Layout::Text)
+        } else if width < WIDE_WIDTH || height < WIDE_HEIGHT {
+            Some(Layout::Cards)
+        } else {
+            Some(Layout::Wide)
+        }
+    }
+
+    /// Returns how big cards are drawn, or None if they're written as text.
+    fn card_size(&self) -> Option<CardSize> {
+        match self {
+            Layout::Cards => Some(CardSize::Normal),
+            Layout::Wide => Some(CardSize::Large),
+            Layout::LargePrint => Some(CardSize::Huge),
+            Layout::Text | Layout::Compact => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Serialize, Deserialize)]
 enum GameState {
+    Betting,
+    EvenMoneyOffer,
+    InsuranceOffer,
+    SurrenderOffer,
     PlayerTurn,
     DealerTurn,
     RoundEnd,
+    SessionLimitReached(SessionLimit),
+    Rebuy,
+    Replay,
+    // A game left unfinished last time can be picked back up
+    ResumeOffer,
+    // A practice game asks for the count before the shoe is shuffled
+    CountQuiz,
     GameOver,
 }
 
-struct GameUI {
-    state: GameState,
-    deck: Deck,
-    player_hand: Hand,
-    dealer_hand: Hand,
-    round_result: String,
+// The count asked for at a shuffle in a practice game, and once it's been
+// given, how far off it was.
+struct CountQuiz {
+    count: i32,
+    // The true count it came to, for systems that have one
+    true_count: Option<String>,
+    typed: String,
+    off_by: Option<u32>,
 }
 
-impl GameUI {
-    fn new() -> Self {
-        let mut deck = Deck::new();
-        deck.shuffle();
+// The choices on the pause screen.
+#[derive(Clone, Copy, PartialEq)]
+enum PauseItem {
+    Resume,
+    Settings,
+    SavedGames,
+    Quit,
+}
 
-        let mut player_hand = Hand::new();
-        let mut dealer_hand = Hand::new();
+const PAUSE_ITEMS: [PauseItem; 4] = [PauseItem::Resume, PauseItem::Settings, PauseItem::SavedGames, PauseItem::Quit];
 
-        // Initial deal: 2 cards each, alternating player/dealer
-        for _ in 0..2 {
-            player_hand.add_card(deck.deal().unwrap());
-            dealer_hand.add_card(deck.deal().unwrap());
-        }
+// The pause screen, which hides the table while the player is away. Its
+// saved games list keeps the game in one of a few slots so several
+// long-running bankrolls can be kept, or loads one of them.
+struct PauseMenu {
+    item: usize,
+    // Whether the saved games are shown rather than the choices
+    showing_slots: bool,
+    slots: Vec<Option<SavedGame>>,
+    selected: usize,
+    // What the last save or load did
+    message: String,
+}
 
-        Self {
-            state: GameState::PlayerTurn,
-            deck,
-            player_hand,
-            dealer_hand,
-            round_result: String::new(),
+impl PauseMenu {
+    fn open(profile: &str) -> Self {
+        let mut message = String::new();
+        let slots = (0..SAVE_SLOTS)
+            .map(|slot| {
+                SavedGame::load(profile, &slot_name(slot)).unwrap_or_else(|err| {
+                    message = err.to_string();
+                    None
+                })
+            })
+            .collect();
+        PauseMenu {
+            item: 0,
+            showing_slots: false,
+            slots,
+            selected: 0,
+            message,
         }
     }
 
-    fn reset_round(&mut self) {
-        self.deck = Deck::new();
-        self.deck.shuffle();
-        self.player_hand = Hand::new();
-        self.dealer_hand = Hand::new();
-
-        // Initial deal: 2 cards each, alternating player/dealer
-        for _ in 0..2 {
-            self.player_hand.add_card(self.deck.deal().unwrap());
-            self.dealer_hand.add_card(self.deck.deal().unwrap());
-        }
-
-        self.state = GameState::PlayerTurn;
-        self.round_result = String::new();
+    /// Picks one of the rows shown, returning true if it was already picked.
+    fn select(&mut self, row: usize) -> bool {
+        let selected = if self.showing_slots { &mut self.selected } else { &mut self.item };
+        let again = *selected == row;
+        *selected = row;
+        again
     }
+}
 
-    // Helper to pad a line properly inside the box using Unicode width
-    fn pad_line(&self, content: &str, total_width: usize) -> String {
-        let display_width = UnicodeWidthStr::width(content);
-        let padding = total_width.saturating_sub(display_width);
-        format!("{}{}", content, " ".repeat(padding))
+// Writes what's changed on screen since the last frame, and records it if
+// the game's being cast
+fn write_frame(stdout: &mut impl Write, cast: Option<&mut Cast>, output: &[u8]) -> io::Result<()> {
+    if output.is_empty() {
+        return Ok(());
     }
+    stdout.write_all(output)?;
+    stdout.flush()?;
+    if let Some(cast) = cast {
+        cast.record(output)?;
+    }
+    Ok(())
+}
 
-    fn render(&self) -> io::Result<()> {
-        let mut stdout = io::stdout();
+// The file name a save slot is kept under, counting from 1
+fn slot_name(slot: usize) -> String {
+    format!("slot{}", slot + 1)
+}
 
-        // Clear screen and move cursor to top-left
-        queue!(stdout, Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+// Several hands laid out side by side: the lines they take, the line
+// their cards start on, and where each hand's column starts and how wide
+// it is
+struct HandColumns {
+    lines: Vec<String>,
+    cards_row: usize,
+    columns: Vec<(usize, usize)>,
+}
 
-        let (term_width, term_height) = size()?;
+// A word on how a hand's play ended, to go after its total
+fn status_note(status: HandStatus) -> &'static str {
+    match status {
+        HandStatus::Active => "",
+        HandStatus::Stood => tr!(" Stood"),
+        HandStatus::Busted => tr!(" Bust"),
+        HandStatus::Charlie => tr!(" Charlie"),
+        HandStatus::Blackjack => tr!(" BJ"),
+        HandStatus::Surrendered => tr!(" Surr."),
+    }
+}
 
-        // Main window dimensions
-        let window_width = 60;
-        let window_height = 16;
-        let start_x = (term_width.saturating_sub(window_width)) / 2;
-        let start_y = (term_height.saturating_sub(window_height)) / 2;
+// A box over the table waiting on a key: its title, a message of a line or
+// more, the keys it takes, and the color its border is drawn in.
+struct Popup {
+    title: String,
+    message: String,
+    prompt: String,
+    color: Color,
+}
 
-        // Draw the main window
-        self.draw_main_window(&mut stdout, start_x, start_y, window_width)?;
+struct GameUI {
+    state: GameState,
+    shoe: Shoe,
+    player_hands: Vec<PlayerHand>,
+    active_hand: usize,
+    // Number of betting spots the player plays each round
+    spots: usize,
+    dealer_hand: Hand,
+    round_result: String,
+    // Outcome of something done between rounds, such as an export
+    notice: String,
+    bankroll: u32,
+    // Chips being put down before the deal, and why the bet can't be dealt
+    bet_chips: ChipStack,
+    bet_error: String,
+    // The chip the arrow keys put down or take off, by its place in
+    // `Chip::ALL`, and whether the bet is being typed in digits
+    bet_chip: usize,
+    bet_typing: bool,
+    last_bet: u32,
+    // Bankroll before this round's bets went down, to tell what the round won
+    round_start_bankroll: u32,
+    base_bet: u32,
+    insurance_bet: u32,
+    // Side bets the player has switched on, placed at the start of every round
+    side_bets: Vec<SideBet>,
+    placed_side_bets: Vec<SideBet>,
+    side_bet_results: Vec<(SideBet, Option<Payout>)>,
+    initial_cards: Vec<Card>,
+    // The cards of the rounds played since the shuffle, and whether what's
+    // in the tray is shown in place of the log or the last rounds
+    discards: DiscardTray,
+    show_discards: bool,
+    // The count quiz being asked, and how the quizzes have gone
+    quiz: Option<CountQuiz>,
+    quiz_record: QuizRecord,
+    // Units of the minimum to bet at each count, and how closely the bets
+    // in a practice game have kept to them
+    bet_spread: Vec<u32>,
+    spread_record: QuizRecord,
+    // How each hand was settled and what the player did, for the history
+    round_outcomes: Vec<HandOutcome>,
+    actions: Vec<String>,
+    rules: Rules,
+    session: Session,
+    history: History,
+    // Which stats tab is shown over the table, if any
+    stats_tab: Option<Scope>,
+    // The past round shown over the table, counting back from the latest
+    history_view: Option<usize>,
+    // The pause menu's save slots, if it's open
+    pause_menu: Option<PauseMenu>,
+    // The settings screen, if it's open, and the rules it left for the next shoe
+    settings: Option<Settings>,
+    next_rules: Option<Rules>,
+    // How far the help is scrolled, if it's open
+    help_scroll: Option<usize>,
+    // What's happened at the table, and how far the full log is scrolled
+    // if it's open. Events logged by the key that dealt the cards still
+    // landing wait for them.
+    events: EventLog,
+    events_before: usize,
+    log_scroll: Option<usize>,
+    // Asking whether to really leave the table
+    confirm_quit: bool,
+    // Replay file being written as the game is played, or being played back
+    recorder: Option<Recorder>,
+    replay: Option<Replay>,
+    // Asciicast recording of everything drawn, if the player asked for one
+    cast: Option<Cast>,
+    // Plain text telling of the game for a screen reader, if it's wanted
+    narrator: Option<Narrator>,
+    // The day being played, in the daily challenge
+    daily: Option<String>,
+    // A practice game keeps no history and leaves the profile's chips alone
+    practice: bool,
+    keys: KeyBindings,
+    // Cards being dealt, shown landing one at a time
+    dealing: Option<Dealing>,
+    // The colors of the theme and the card back picked in the settings
+    palette: Palette,
+    card_back: CardBack,
+    // Whether suits are drawn as emoji where the terminal can show them
+    emoji_suits: bool,
+    // Whether ranks and totals are written big where there's room
+    large_print: bool,
+    // Whether the cards and decks left in the shoe are shown, as players
+    // learning to count may rather judge it themselves
+    shoe_left: bool,
+    // The counting system the shoe panel keeps count in, and how its true
+    // count is rounded
+    count_system: System,
+    true_count_rounding: Rounding,
+    // How long the dealer waits before drawing each card, and how quickly
+    // cards are dealt
+    dealer_delay: Duration,
+    animation_speed: AnimationSpeed,
+    // Parts of the last frame drawn that can be clicked
+    buttons: RefCell<Vec<Button>>,
+    // What the last frame left on screen, for the next to draw over, and a
+    // line of it kept to lay rows out in without allocating
+    screen: Screen,
+    row: RefCell<String>,
+    // The terminal's size as of the last frame drawn
+    size: (u16, u16),
+    // The table as ratatui last drew it, while it's the one drawing
+    #[cfg(feature = "ratatui")]
+    tui: Option<tui::Tui>,
+    // Profile the game is autosaved for, and the saved game on offer to resume
+    profile: String,
+    resume: Option<SavedGame>,
+}
 
-        // Draw popup if there's a result
-        if !self.round_result.is_empty() {
-            self.draw_popup(&mut stdout)?;
-        }
+impl GameUI {
+    fn new(rules: Rules, session: Session, history: History, profile: &str, keys: KeyBindings) -> Self {
+        let mut game = Self {
+            state: GameState::PlayerTurn,
+            shoe: Shoe::new(rules.decks, rules.penetration),
+            player_hands: Vec::new(),
+            active_hand: 0,
+            spots: 1,
+            dealer_hand: Hand::new(),
+            round_result: String::new(),
+            notice: String::new(),
+            bankroll: session.starting_bankroll,
+            bet_chips: ChipStack::default(),
+            bet_chip: 1,
+            bet_typing: false,
+            bet_error: String::new(),
+            last_bet: rules.min_bet,
+            round_start_bankroll: session.starting_bankroll,
+            base_bet: 0,
+            insurance_bet: 0,
+            side_bets: Vec::new(),
+            placed_side_bets: Vec::new(),
+            side_bet_results: Vec::new(),
+            initial_cards: Vec::new(),
+            discards: DiscardTray::default(),
+            show_discards: false,
+            quiz: None,
+            quiz_record: QuizRecord::default(),
+            bet_spread: Vec::new(),
+            spread_record: QuizRecord::default(),
+            round_outcomes: Vec::new(),
+            actions: Vec::new(),
+            rules,
+            session,
+            history,
+            stats_tab: None,
+            history_view: None,
+            pause_menu: None,
+            settings: None,
+            next_rules: None,
+            help_scroll: None,
+            events: EventLog::default(),
+            events_before: 0,
+            log_scroll: None,
+            confirm_quit: false,
+            recorder: None,
+            replay: None,
+            cast: None,
+            narrator: None,
+            daily: None,
+            practice: false,
+            keys,
+            dealing: None,
+            palette: Palette::default(),
+            card_back: CardBack::default(),
+            emoji_suits: false,
+            large_print: false,
+            shoe_left: true,
+            count_system: System::default(),
+            true_count_rounding: Rounding::default(),
+            dealer_delay: Duration::ZERO,
+            animation_speed: AnimationSpeed::default(),
+            buttons: RefCell::new(Vec::new()),
+            screen: Screen::new(),
+            row: RefCell::new(String::new()),
+            size: (0, 0),
+            #[cfg(feature = "ratatui")]
+            tui: None,
+            profile: profile.to_string(),
+            resume: None,
+        };
+        game.start_betting();
+        game
+    }
 
-        stdout.flush()?;
+    /// Takes the looks, the counting and the pace of play from a profile's
+    /// config.
+    fn apply_config(&mut self, config: &Config) -> io::Result<()> {
+        self.palette = config.theme.palette()?;
+        self.card_back = config.card_back;
+        self.emoji_suits = config.emoji_suits;
+        self.large_print = config.large_print;
+        self.shoe_left = config.shoe_left();
+        self.count_system = config.count_system;
+        self.quiz_record = config.count_quiz.clone();
+        self.bet_spread = config.bet_spread();
+        self.true_count_rounding = config.true_count_rounding;
+        self.dealer_delay = config.dealer_delay();
+        self.animation_speed = config.animation_speed;
         Ok(())
     }
 
-    fn draw_main_window(&self, stdout: &mut io::Stdout, start_x: u16, start_y: u16, width: u16) -> io::Result<()> {
-        let inner_width = (width - 2) as usize; // Width inside the box borders
-
-        // Draw top border with title
-        queue!(stdout, cursor::MoveTo(start_x, start_y))?;
-        write!(stdout, "┌{}┐\r", "─".repeat(inner_width))?;
+    /// Clears the table and waits for the player to place the next bet,
+    /// starting from the amount they bet last time.
+    fn start_betting(&mut self) {
+        // The last round's cards go in the discard tray
+        let cards = self.player_hands.iter().flat_map(|h| h.hand.cards().iter().copied());
+        self.discards.add(cards.chain(self.dealer_hand.cards().iter().copied()));
+        self.player_hands.clear();
+        self.dealer_hand = Hand::new();
+        self.round_result = String::new();
+        self.notice = String::new();
+        self.bet_chips = ChipStack::from_amount(self.last_bet);
+        self.bet_error = String::new();
+        self.bet_typing = false;
+        // Practice games ask for the count before it's lost to the shuffle
+        self.state = if self.practice && self.shoe.cut_card_reached() {
+            let system = self.count_system.counting();
+            let count = self.shoe.running_count(system);
+            self.quiz = Some(CountQuiz {
+                count,
+                true_count: self.true_count(system, count, self.shoe.remaining()),
+                typed: String::new(),
+                off_by: None,
+            });
+            GameState::CountQuiz
+        } else {
+            GameState::Betting
+        };
+    }
 
-        // Title
-        let title = " ♠ BLACKJACK ♥ ";
-        let title_x = start_x + (width - UnicodeWidthStr::width(title) as u16) / 2;
-        queue!(stdout, cursor::MoveTo(title_x, start_y))?;
-        write!(stdout, "{}\r", title)?;
+    /// Puts the last bet straight back down and deals. If it no longer
+    /// fits the bankroll the bet phase stays open with the reason shown.
+    fn rebet(&mut self) {
+        self.start_betting();
+        if let GameState::Betting = self.state {
+            self.place_bet();
+        }
+    }
 
-        let mut line = start_y + 1;
+    // Takes the count typed in the quiz, a minus first for a count below
+    // nothing, and once it's been answered goes on to the bets
+    fn handle_count_quiz(&mut self, key: KeyCode) {
+        let Some(quiz) = &mut self.quiz else {
+            return;
+        };
+        if quiz.off_by.is_some() {
+            if self.keys.deal.matches(key) {
+                self.quiz = None;
+                self.state = GameState::Betting;
+            }
+            return;
+        }
+        match key {
+            KeyCode::Char(c @ '0'..='9') if quiz.typed.trim_start_matches('-').len() < 3 => quiz.typed.push(c),
+            KeyCode::Char('-') => match quiz.typed.strip_prefix('-') {
+                Some(typed) => quiz.typed = typed.to_string(),
+                None => quiz.typed.insert(0, '-'),
+            },
+            _ if self.keys.undo.matches(key) => {
+                quiz.typed.pop();
+            }
+            _ if self.keys.deal.matches(key) => {
+                let Ok(answer) = quiz.typed.parse::<i32>() else {
+                    return;
+                };
+                let off_by = answer.abs_diff(quiz.count);
+                quiz.off_by = Some(off_by);
+                self.quiz_record.record(off_by);
+                self.save_quiz_record();
+            }
+            _ if self.keys.no.matches(key) => {
+                self.quiz = None;
+                self.state = GameState::Betting;
+            }
+            _ => {}
+        }
+    }
 
-        // Dealer section header
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "├{}┤\r", "─".repeat(inner_width))?;
-        line += 1;
+    // Keeps how the quizzes have gone in the profile's config, read afresh
+    // so settings changed since the game started aren't lost
+    fn save_quiz_record(&mut self) {
+        let saved = Config::load(&self.profile).and_then(|mut config| {
+            config.count_quiz = self.quiz_record.clone();
+            config.save(&self.profile)
+        });
+        if let Err(err) = saved {
+            self.notice = tr!("Quiz score not saved: {}", err);
+        }
+    }
 
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        let dealer_label = "  DEALER";
-        write!(stdout, "│{}│\r", self.pad_line(dealer_label, inner_width))?;
-        line += 1;
+    fn handle_betting(&mut self, key: KeyCode) {
+        let chip = Chip::ALL[self.bet_chip];
+        let typing = match key {
+            // Digits type the bet, from nothing after any other key
+            KeyCode::Char(c @ '0'..='9') => {
+                let typed = if self.bet_typing { self.bet_chips.total() } else { 0 };
+                let digit = c.to_digit(10).unwrap_or(0);
+                self.bet_chips = ChipStack::from_amount((typed * 10 + digit).min(MAX_TYPED_BET));
+                true
+            }
+            _ if self.keys.undo.matches(key) && self.bet_typing => {
+                self.bet_chips = ChipStack::from_amount(self.bet_chips.total() / 10);
+                true
+            }
+            _ if self.keys.undo.matches(key) => {
+                self.bet_chips.pop();
+                false
+            }
+            _ if self.keys.clear.matches(key) => {
+                self.bet_chips.clear();
+                false
+            }
+            _ if self.keys.bet_limit.matches(key) => {
+                let most = self.rules.max_bet.min(self.bankroll / self.spots as u32);
+                let bet = if self.bet_chips.total() == most { self.rules.min_bet } else { most };
+                self.bet_chips = ChipStack::from_amount(bet);
+                false
+            }
+            // The arrows put down or take off a chip, and pick which
+            _ if self.keys.moves_up(key) => {
+                if self.bet_chips.total() + chip.value() <= MAX_TYPED_BET {
+                    self.bet_chips.push(chip);
+                }
+                false
+            }
+            _ if self.keys.moves_down(key) => {
+                if !self.bet_chips.take(chip) {
+                    self.bet_chips = ChipStack::from_amount(self.bet_chips.total().saturating_sub(chip.value()));
+                }
+                false
+            }
+            _ if self.keys.moves_left(key) => {
+                self.bet_chip = self.bet_chip.saturating_sub(1);
+                return;
+            }
+            _ if self.keys.moves_right(key) => {
+                self.bet_chip = (self.bet_chip + 1).min(Chip::ALL.len() - 1);
+                return;
+            }
+            _ if self.keys.deal.matches(key) => return self.place_bet(),
+            _ => return,
+        };
+        self.bet_typing = typing;
 
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "├{}┤\r", "─".repeat(inner_width))?;
-        line += 1;
+        // The bet's checked as it's put down, not only when it's dealt
+        self.bet_error = match self.bet_chips.is_empty() {
+            true => String::new(),
+            false => self.bet_problem().unwrap_or_default(),
+        };
+    }
 
-        // Dealer's cards
-        match self.state {
-            GameState::PlayerTurn => {
-                let cards = self.dealer_hand.display_str();
-                let visible = cards.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
-                let display = format!("  Cards: [??] {}", visible);
+    /// Checks the chips put down against the table limits and the bankroll, and
+    /// deals if it's valid. A refused bet is explained rather than adjusted.
+    fn place_bet(&mut self) {
+        if self.bet_chips.is_empty() {
+            self.bet_error = String::from(tr!("Put down some chips to bet"));
+            return;
+        }
+        match self.bet_problem() {
+            Some(problem) => self.bet_error = problem,
+            None => {
+                self.last_bet = self.bet_chips.total();
+                if self.practice {
+                    let (advised, _) = self.spread_bet();
+                    self.spread_record.record(self.last_bet.abs_diff(advised));
+                }
+                self.reset_round();
+            }
+        }
+    }
 
-                queue!(stdout, cursor::MoveTo(start_x, line))?;
-                write!(stdout, "│{}│\r", self.pad_line(&display, inner_width))?;
-                line += 1;
+    // Returns what the bet spread says to bet on each spot at the count the
+    // shoe's come to, and that count: the true count, or the running count
+    // of an unbalanced system, which is bet by as it runs
+    fn spread_bet(&self) -> (u32, String) {
+        let system = self.count_system.counting();
+        let count = self.shoe.running_count(system);
+        let unseen = self.shoe.remaining();
+        let (by, shown) = match self.true_count(system, count, unseen) {
+            Some(shown) => (count::true_count(count, unseen, self.true_count_rounding), shown),
+            None => (count as f32, format!("{:+}", count)),
+        };
+        let units = count::spread_units(&self.bet_spread, by);
+        ((units * self.rules.min_bet).min(self.rules.max_bet), shown)
+    }
 
-                let value_display = "  Value: ???";
-                queue!(stdout, cursor::MoveTo(start_x, line))?;
-                write!(stdout, "│{}│\r", self.pad_line(value_display, inner_width))?;
-                line += 1;
-            }
-            _ => {
-                let display = format!("  Cards: {}", self.dealer_hand.display_str());
+    // Returns what's said under the bet being put down, if anything: why
+    // it can't be dealt, or in practice games, what the bet spread says and
+    // how closely the bets have kept to it
+    fn bet_notes(&self) -> Vec<String> {
+        if !self.bet_error.is_empty() {
+            return vec![self.bet_error.clone()];
+        }
+        if !self.practice {
+            return Vec::new();
+        }
+        let (bet, count) = self.spread_bet();
+        let mut notes = vec![match self.count_system.counting() {
+            system if system.balanced() => tr!("Spread: {} at a true count of {}", locale::money(bet), count),
+            system => tr!("Spread: {} at a {} count of {}", locale::money(bet), system.name(), count),
+        }];
+        let record = &self.spread_record;
+        if record.answered > 0 {
+            notes.push(tr!("{} of {} bets on it", record.right, record.answered));
+        }
+        notes
+    }
 
-                queue!(stdout, cursor::MoveTo(start_x, line))?;
-                write!(stdout, "│{}│\r", self.pad_line(&display, inner_width))?;
-                line += 1;
+    // Returns why the bet put down can't be dealt, if it can't: it's out of
+    // the table limits or more than the bankroll covers on every spot
+    fn bet_problem(&self) -> Option<String> {
+        let bet = self.bet_chips.total();
+        Some(if bet < self.rules.min_bet {
+            tr!("Minimum bet is {}", locale::money(self.rules.min_bet))
+        } else if bet > self.rules.max_bet {
+            tr!("Maximum bet is {}", locale::money(self.rules.max_bet))
+        } else if bet * self.spots as u32 > self.bankroll {
+            tr!("Not enough chips for {} x {}", self.spots, locale::money(bet))
+        } else {
+            return None;
+        })
+    }
 
-                let value_display = format!("  Value: {}", self.dealer_hand.value());
-                queue!(stdout, cursor::MoveTo(start_x, line))?;
-                write!(stdout, "│{}│\r", self.pad_line(&value_display, inner_width))?;
-                line += 1;
+    fn reset_round(&mut self) {
+        if self.shoe.cut_card_reached() {
+            match self.next_rules.take() {
+                Some(rules) => {
+                    self.shoe = Shoe::new(rules.decks, rules.penetration);
+                    self.rules = rules;
+                }
+                None => self.shoe.reshuffle(),
             }
+            self.discards.clear();
+            self.log(None, String::from(tr!("The shoe is shuffled")));
         }
+        self.dealer_hand = Hand::new();
+        self.round_start_bankroll = self.bankroll;
+        self.actions.clear();
 
+        // Take a bet per spot off the bankroll before dealing
+        let mut player_hands = Vec::with_capacity(self.spots);
+        for spot in 0..self.spots {
+            self.bankroll -= self.last_bet;
+            player_hands.push(PlayerHand::new(self.last_bet, spot));
+        }
+        self.base_bet = player_hands.iter().map(|h| h.bet).sum();
 
-        // Player section header
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "├{}┤\r", "─".repeat(inner_width))?;
-        line += 1;
-
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        let player_label = "  PLAYER";
-        write!(stdout, "│{}│\r", self.pad_line(player_label, inner_width))?;
-        line += 1;
+        // Side bets are only placed if the bankroll still covers them
+        self.placed_side_bets.clear();
+        self.side_bet_results.clear();
+        for &side_bet in &self.side_bets {
+            if self.bankroll >= SIDE_BET_STAKE {
+                self.bankroll -= SIDE_BET_STAKE;
+                self.placed_side_bets.push(side_bet);
+            }
+        }
 
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "├{}┤\r", "─".repeat(inner_width))?;
-        line += 1;
+        // Initial deal: 2 cards each, going round the spots and then the dealer.
+        // Without a hole card the dealer only takes one card for now.
+        for i in 0..2 {
+            for player_hand in &mut player_hands {
+                player_hand.hand.add_card(self.shoe.deal());
+            }
+            if i == 0 || !self.rules.no_hole_card {
+                self.dealer_hand.add_card(self.shoe.deal());
+            }
+        }
 
-        // Player's cards
-        let player_display = format!("  Cards: {}", self.player_hand.display_str());
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "│{}│\r", self.pad_line(&player_display, inner_width))?;
-        line += 1;
+        // Side bets ride on the first spot
+        self.initial_cards = player_hands[0].hand.cards().to_vec();
+        self.player_hands = player_hands;
+        self.active_hand = 0;
+        self.insurance_bet = 0;
+        match self.spots {
+            1 => self.log(None, tr!("You bet {}", locale::money(self.last_bet))),
+            spots => self.log(None, tr!("You bet {} on each of {} spots", locale::money(self.last_bet), spots)),
+        }
+        if !self.placed_side_bets.is_empty() {
+            let names: Vec<&str> = self.placed_side_bets.iter().map(|side_bet| side_bet.name()).collect();
+            self.log(None, tr!("Side bets: {}", names.join(", ")));
+        }
+        for i in 0..self.player_hands.len() {
+            let hand = &self.player_hands[i].hand;
+            self.log(Some(i), tr!("You're dealt {} ({})", hand.display_str(), hand.total()));
+        }
+        if let Some(card) = self.up_card() {
+            self.log(None, tr!("Dealer shows {}", card));
+        }
 
-        let player_value = format!("  Value: {}", self.player_hand.value());
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "│{}│\r", self.pad_line(&player_value, inner_width))?;
-        line += 1;
+        // Offer insurance when the dealer shows an ace and the player can afford it.
+        // Early surrender has to be offered before the dealer peeks as well.
+        let dealer_shows_ace = self.up_card().is_some_and(|c| c.rank == Rank::Ace);
+        let insurance_cost = self.insurance_cost();
+        let all_naturals = self.player_hands.iter().all(|h| h.hand.is_blackjack());
+        self.round_result = String::new();
+        if dealer_shows_ace && self.player_hands.len() == 1 && all_naturals {
+            self.state = GameState::EvenMoneyOffer;
+        } else if self.rules.no_hole_card {
+            self.check_naturals();
+        } else if dealer_shows_ace && insurance_cost > 0 && self.bankroll >= insurance_cost {
+            self.state = GameState::InsuranceOffer;
+        } else if self.rules.early_surrender && self.dealer_peeks() && !all_naturals {
+            self.state = GameState::SurrenderOffer;
+        } else {
+            self.check_naturals();
+        }
+    }
 
-        // Controls section
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "├{}┤\r", "─".repeat(inner_width))?;
-        line += 1;
+    /// Returns the dealer's up-card as it was dealt.
+    fn up_card(&self) -> Option<Card> {
+        self.dealer_hand.up_card(self.rules.no_hole_card)
+    }
 
-        let controls = match self.state {
-            GameState::PlayerTurn => "  [H] Hit  │  [S] Stand  │  [Q] Quit",
-            GameState::RoundEnd => "  [N] New Round  │  [Q] Quit",
-            _ => "  [Q] Quit",
-        };
+    /// The dealer only peeks at the hole card when showing an ace or a
+    /// ten-value card, since those are the only up-cards that can make blackjack.
+    fn dealer_peeks(&self) -> bool {
+        !self.rules.no_hole_card
+            && self.up_card().is_some_and(|c| c.rank.value() >= 10)
+    }
 
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "│{}│\r", self.pad_line(controls, inner_width))?;
-        line += 1;
+    /// Runs the dealer's peek and marks the player's naturals before play
+    /// starts. A dealer natural settles every hand straight away.
+    fn check_naturals(&mut self) {
+        for player_hand in &mut self.player_hands {
+            if player_hand.status == HandStatus::Active && player_hand.hand.is_blackjack() {
+                player_hand.status = HandStatus::Blackjack;
+            }
+        }
 
-        // Bottom border
-        queue!(stdout, cursor::MoveTo(start_x, line))?;
-        write!(stdout, "└{}┘\r", "─".repeat(inner_width))?;
+        if self.dealer_peeks() && self.dealer_hand.is_blackjack() {
+            self.log(None, String::from(tr!("Dealer has blackjack")));
+            self.settle_hands();
+        } else {
+            self.finish_hand();
+        }
+    }
 
-        Ok(())
+    /// Insurance costs half of the main bets.
+    fn insurance_cost(&self) -> u32 {
+        self.player_hands.iter().map(|h| h.bet).sum::<u32>() / 2
     }
 
-    fn draw_popup(&self, stdout: &mut io::Stdout) -> io::Result<()> {
-        let (term_width, term_height) = size()?;
+    /// The hole card stays hidden until the dealer plays, or as it was when
+    /// a replay frame was recorded.
+    fn hides_hole_card(&self) -> bool {
+        if let Some(replay) = &self.replay {
+            return replay.frame().hole_card_hidden;
+        }
+        matches!(
+            self.state,
+            GameState::EvenMoneyOffer
+                | GameState::InsuranceOffer
+                | GameState::SurrenderOffer
+                | GameState::PlayerTurn
+        ) && self.dealer_hand.len() > 1
+    }
 
-        // Popup dimensions
-        let popup_width = 50;
-        let popup_height = 7;
-        let start_x = (term_width.saturating_sub(popup_width)) / 2;
-        let start_y = (term_height.saturating_sub(popup_height)) / 2;
+    /// Returns whether the hole card is drawn face down: while it's hidden,
+    /// and until it's been turned over on screen.
+    fn hole_card_face_down(&self) -> bool {
+        self.hides_hole_card() || self.dealing.as_ref().is_some_and(|d| d.hides_hole_card())
+    }
 
-        // Draw shadow (optional, for depth effect)
-        for i in 0..popup_height {
-            queue!(stdout, cursor::MoveTo(start_x + 1, start_y + i + 1))?;
-            write!(stdout, "{}", " ".repeat(popup_width as usize))?;
+    /// Returns the chips bet on the round being played, which are lost if
+    /// the player leaves before it's settled.
+    fn chips_at_stake(&self) -> u32 {
+        match self.state {
+            GameState::EvenMoneyOffer
+            | GameState::InsuranceOffer
+            | GameState::SurrenderOffer
+            | GameState::PlayerTurn
+            | GameState::DealerTurn => self.round_start_bankroll.saturating_sub(self.bankroll),
+            _ => 0,
         }
+    }
 
-        // Draw popup box
-        queue!(stdout, cursor::MoveTo(start_x, start_y))?;
-        write!(stdout, "┌{}┐\r", "─".repeat(popup_width as usize - 2))?;
+    /// Returns the cards in each hand, to tell which cards a key dealt.
+    fn table_cards(&self) -> Vec<(Seat, Vec<Card>)> {
+        let mut cards: Vec<(Seat, Vec<Card>)> = self
+            .player_hands
+            .iter()
+            .enumerate()
+            .map(|(i, hand)| (Seat::Player(i), hand.hand.cards().to_vec()))
+            .collect();
+        cards.push((Seat::Dealer, self.dealer_hand.cards().to_vec()));
+        cards
+    }
 
-        for i in 1..popup_height - 1 {
-            queue!(stdout, cursor::MoveTo(start_x, start_y + i))?;
-            write!(stdout, "│{}│\r", " ".repeat(popup_width as usize - 2))?;
-        }
+    /// Works out which cards were dealt since the shoe had `remaining` cards
+    /// left and the hands held `before`, and whether the hole card has been
+    /// turned over since it was `hidden`.
+    fn dealt_since(
+        &self,
+        (remaining, before, hidden): (usize, Vec<(Seat, Vec<Card>)>, bool),
+    ) -> Option<Dealing> {
+        let after = self.table_cards();
+        let on_table: usize = after.iter().map(|(_, cards)| cards.len()).sum();
+        let flip = hidden && !self.hides_hole_card() && self.dealer_hand.len() > 1;
+        let before = match remaining.checked_sub(self.shoe.remaining()) {
+            Some(dealt) if dealt < on_table => &before[..],
+            // A new round, or a shuffle, leaves only new cards on the table
+            _ => &[],
+        };
+        Dealing::between(before, &after, flip, self.dealer_delay, self.animation_speed)
+    }
 
-        queue!(stdout, cursor::MoveTo(start_x, start_y + popup_height - 1))?;
-        write!(stdout, "└{}┘\r", "─".repeat(popup_width as usize - 2))?;
+    /// Returns a snapshot of the table for the replay, or None while bets
+    /// are being placed and there are no cards out.
+    fn frame(&self) -> Option<Frame> {
+        let event = match self.state {
+            GameState::Betting
+            | GameState::Replay
+            | GameState::ResumeOffer
+            | GameState::CountQuiz
+            | GameState::GameOver => {
+                return None;
+            }
+            GameState::RoundEnd | GameState::SessionLimitReached(_) | GameState::Rebuy => {
+                self.round_result.lines().next().unwrap_or_default().to_string()
+            }
+            // Actions are kept in English for the history
+            _ => locale::text(self.actions.last().map_or("Deal", String::as_str)).to_string(),
+        };
+        Some(Frame {
+            event,
+            dealer: self.dealer_hand.clone(),
+            hole_card_hidden: self.hides_hole_card(),
+            hands: self.player_hands.clone(),
+            active_hand: self.active_hand,
+            bankroll: self.bankroll,
+        })
+    }
 
-        // Draw title
-        queue!(stdout, cursor::MoveTo(start_x + 2, start_y + 1))?;
-        write!(stdout, "ROUND RESULT\r")?;
+    /// Returns the game as it stands, to be written to a save file.
+    fn saved(&self) -> SavedGame {
+        SavedGame {
+            state: self.state,
+            shoe: self.shoe.clone(),
+            player_hands: self.player_hands.clone(),
+            active_hand: self.active_hand,
+            spots: self.spots,
+            dealer_hand: self.dealer_hand.clone(),
+            round_result: self.round_result.clone(),
+            bankroll: self.bankroll,
+            bet_chips: self.bet_chips.clone(),
+            bet_error: self.bet_error.clone(),
+            last_bet: self.last_bet,
+            round_start_bankroll: self.round_start_bankroll,
+            base_bet: self.base_bet,
+            insurance_bet: self.insurance_bet,
+            side_bets: self.side_bets.clone(),
+            placed_side_bets: self.placed_side_bets.clone(),
+            side_bet_results: self.side_bet_results.clone(),
+            initial_cards: self.initial_cards.clone(),
+            discards: self.discards.clone(),
+            round_outcomes: self.round_outcomes.clone(),
+            actions: self.actions.clone(),
+            rules: self.rules.clone(),
+            session: self.session.clone(),
+            session_id: self.history.session_id(),
+            daily: self.daily.clone(),
+        }
+    }
 
-        // Draw separator
-        queue!(stdout, cursor::MoveTo(start_x, start_y + 2))?;
-        write!(stdout, "├{}┤\r", "─".repeat(popup_width as usize - 2))?;
-
-        // Draw the result message (centered)
-        let result_width = UnicodeWidthStr::width(self.round_result.as_str());
-        let result_x = start_x + ((popup_width as usize - result_width) / 2) as u16;
-        queue!(stdout, cursor::MoveTo(result_x, start_y + 3))?;
-        write!(stdout, "{}\r", self.round_result)?;
-
-        // Draw prompt
-        let prompt = "Press [N] for new round or [Q] to quit";
-        let prompt_width = UnicodeWidthStr::width(prompt);
-        let prompt_x = start_x + ((popup_width as usize - prompt_width) / 2) as u16;
-        queue!(stdout, cursor::MoveTo(prompt_x, start_y + 5))?;
-        write!(stdout, "{}\r", prompt)?;
+    /// Puts the table back the way a saved game left it, carrying on with
+    /// its session in the history.
+    fn restore(&mut self, saved: SavedGame) {
+        self.state = saved.state;
+        self.shoe = saved.shoe;
+        self.player_hands = saved.player_hands;
+        self.active_hand = saved.active_hand;
+        self.spots = saved.spots;
+        self.dealer_hand = saved.dealer_hand;
+        self.round_result = saved.round_result;
+        self.bankroll = saved.bankroll;
+        self.bet_chips = saved.bet_chips;
+        self.bet_error = saved.bet_error;
+        self.last_bet = saved.last_bet;
+        self.round_start_bankroll = saved.round_start_bankroll;
+        self.base_bet = saved.base_bet;
+        self.insurance_bet = saved.insurance_bet;
+        self.side_bets = saved.side_bets;
+        self.placed_side_bets = saved.placed_side_bets;
+        self.side_bet_results = saved.side_bet_results;
+        self.initial_cards = saved.initial_cards;
+        self.discards = saved.discards;
+        self.round_outcomes = saved.round_outcomes;
+        self.actions = saved.actions;
+        self.rules = saved.rules;
+        self.session = saved.session;
+        self.history.resume_session(saved.session_id);
+        self.daily = saved.daily;
+    }
 
-        Ok(())
+    /// Offers to pick up a saved game before anything is played.
+    fn offer_resume(&mut self, saved: SavedGame) {
+        self.resume = Some(saved);
+        self.state = GameState::ResumeOffer;
     }
 
-    fn handle_player_turn(&mut self, key: KeyCode) {
+    fn handle_resume_offer(&mut self, key: KeyCode) {
         match key {
-            KeyCode::Char('h') | KeyCode::Char('H') => {
-                let new_card = self.deck.deal().unwrap();
-                self.player_hand.add_card(new_card);
+            _ if self.keys.yes.matches(key) => {
+                if let Some(saved) = self.resume.take() {
+                    self.restore(saved);
+                }
+            }
+            _ if self.keys.no.matches(key) => {
+                self.resume = None;
+                if let Err(err) = self.history.start_session(self.daily.as_deref()) {
+                    self.notice = tr!("History not recorded: {}", err);
+                }
+                self.start_betting();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_pause_menu(&mut self, key: KeyCode) {
+        let Some(menu) = &mut self.pause_menu else {
+            return;
+        };
+        if !menu.showing_slots {
+            let item = match key {
+                _ if self.keys.moves_up(key) => {
+                    menu.item = menu.item.saturating_sub(1);
+                    return;
+                }
+                _ if self.keys.moves_down(key) => {
+                    menu.item = (menu.item + 1).min(PAUSE_ITEMS.len() - 1);
+                    return;
+                }
+                KeyCode::Enter => PAUSE_ITEMS[menu.item],
+                _ if self.keys.menu.matches(key) => PauseItem::Resume,
+                _ if self.keys.settings.matches(key) => PauseItem::Settings,
+                _ if self.keys.save.matches(key) || self.keys.load.matches(key) => PauseItem::SavedGames,
+                _ => return,
+            };
+            match item {
+                PauseItem::Resume => self.pause_menu = None,
+                PauseItem::Settings => match Config::load(&self.profile) {
+                    Ok(config) => {
+                        self.pause_menu = None;
+                        let mut settings = Settings::new(config, self.keys.clone());
+                        settings.table = Some(self.next_rules.clone().unwrap_or_else(|| self.rules.clone()));
+                        self.settings = Some(settings);
+                    }
+                    Err(err) => menu.message = tr!("Couldn't read the settings: {}", err),
+                },
+                PauseItem::SavedGames => {
+                    menu.showing_slots = true;
+                    menu.message = String::new();
+                }
+                PauseItem::Quit => self.confirm_quit = true,
+            }
+            return;
+        }
 
-                if self.player_hand.value() > 21 {
-                    self.round_result = String::from("BUST! You lose this round.");
-                    self.state = GameState::RoundEnd;
-                } else if self.player_hand.value() == 21 {
-                    self.state = GameState::DealerTurn;
+        match key {
+            _ if self.keys.moves_up(key) => menu.selected = menu.selected.saturating_sub(1),
+            _ if self.keys.moves_down(key) => menu.selected = (menu.selected + 1).min(SAVE_SLOTS - 1),
+            // Reloading a save would let a daily challenge hand be played again
+            _ if (self.keys.save.matches(key) || self.keys.load.matches(key)) && self.daily.is_some() => {
+                menu.message = String::from(tr!("The daily challenge can't be saved or loaded"));
+            }
+            _ if self.keys.save.matches(key) && self.practice => {
+                menu.message = String::from(tr!("Practice games can't be saved"));
+            }
+            _ if self.keys.save.matches(key) => {
+                let slot = menu.selected;
+                let saved = self.saved();
+                let menu = self.pause_menu.as_mut().expect("the pause menu is open");
+                menu.message = match saved.save(&self.profile, &slot_name(slot)) {
+                    Ok(()) => tr!("Saved to slot {}", slot + 1),
+                    Err(err) => tr!("Couldn't save: {}", err),
+                };
+                menu.slots[slot] = Some(saved);
+            }
+            _ if self.keys.load.matches(key) => {
+                if let Some(saved) = menu.slots[menu.selected].take() {
+                    self.pause_menu = None;
+                    self.restore(saved);
+                } else {
+                    menu.message = String::from(tr!("That slot is empty"));
                 }
             }
-            KeyCode::Char('s') | KeyCode::Char('S') => {
-                self.state = GameState::DealerTurn;
+            _ if self.keys.menu.matches(key) => {
+                menu.showing_slots = false;
+                menu.message = String::new();
             }
             _ => {}
         }
     }
 
-    fn resolve_dealer_turn(&mut self) {
-        // Dealer plays
-        while self.dealer_hand.value() < 17 {
-            let new_card = self.deck.deal().unwrap();
-            self.dealer_hand.add_card(new_card);
+    fn handle_settings(&mut self, key: KeyCode) {
+        let Some(settings) = &mut self.settings else {
+            return;
+        };
+        if !settings.handle_input(key) {
+            return;
         }
 
-        // Determine winner
-        let player_score = self.player_hand.value();
-        let dealer_score = self.dealer_hand.value();
+        // Keys, looks and streaks change straight away, but the table
+        // only changes once the shoe runs out, and never in the daily challenge
+        self.keys = settings.key_bindings();
+        locale::set(settings.config.locale, &settings.config.currency);
+        match settings.config.theme.palette() {
+            Ok(palette) => self.palette = palette,
+            Err(err) => self.notice = tr!("Theme not loaded: {}", err),
+        }
+        self.card_back = settings.config.card_back;
+        // Finding out whether the terminal can show emoji writes to the
+        // screen, and the cards all change width, so it's drawn afresh
+        if self.emoji_suits != settings.config.emoji_suits {
+            self.emoji_suits = settings.config.emoji_suits;
+            self.screen.forget();
+        }
+        self.large_print = settings.config.large_print;
+        self.shoe_left = settings.config.shoe_left();
+        self.count_system = settings.config.count_system;
+        self.true_count_rounding = settings.config.true_count_rounding;
+        self.bet_spread = settings.config.bet_spread();
+        self.dealer_delay = settings.config.dealer_delay();
+        self.animation_speed = settings.config.animation_speed;
+        self.session.push_policy = settings.config.push_policy;
+        if self.daily.is_none()
+            && let Some(rules) = settings.changed_rules()
+        {
+            self.next_rules = (rules != self.rules).then_some(rules);
+        }
+        if let Err(err) = settings.config.save(&self.profile) {
+            self.notice = tr!("Settings not saved: {}", err);
+        }
+        self.settings = None;
+    }
 
-        if dealer_score > 21 {
-            self.round_result = String::from("Dealer busts! You win!");
-        } else if player_score > dealer_score {
-            self.round_result = format!("You win! ({} vs {})", player_score, dealer_score);
-        } else if player_score < dealer_score {
-            self.round_result = format!("You lose. ({} vs {})", player_score, dealer_score);
-        } else {
-            self.round_result = format!("Push! It's a tie at {}", player_score);
+    /// Keeps the autosave up to date with the game in progress.
+    fn autosave(&mut self) {
+        if self.replay.is_some() || self.resume.is_some() || self.practice {
+            return;
         }
+        if let Err(err) = self.saved().save(&self.profile, AUTOSAVE) {
+            self.notice = tr!("Autosave failed: {}", err);
+        }
+    }
 
-        self.state = GameState::RoundEnd;
+    /// Switches to playing back a replay from its first frame.
+    fn start_replay(&mut self, replay: Replay) {
+        self.replay = Some(replay);
+        self.state = GameState::Replay;
+        self.show_frame();
     }
 
-    fn handle_input(&mut self, key: KeyCode) -> bool {
-        match key {
-            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => {
-                self.state = GameState::GameOver;
-                return false;
+    /// Lays the table out as it was in the replay's current frame.
+    fn show_frame(&mut self) {
+        let Some(replay) = &self.replay else {
+            return;
+        };
+        let frame = replay.frame();
+        self.dealer_hand = frame.dealer.clone();
+        self.player_hands = frame.hands.clone();
+        self.active_hand = frame.active_hand;
+        self.bankroll = frame.bankroll;
+    }
+
+    // Writes one line of the table between its side borders, padded out to
+    // the width inside them using Unicode width. It's formatted into a line
+    // kept from row to row, so `format_args!` saves building a string.
+    fn write_row(&self, out: &mut impl Write, content: impl fmt::Display, width: usize) -> io::Result<()> {
+        let mut row = self.row.borrow_mut();
+        row.clear();
+        // Writing into a string can't fail
+        let _ = fmt::Write::write_fmt(&mut *row, format_args!("{}", content));
+        let padding = width.saturating_sub(theme::width(&row));
+        self.palette.write(out, "│", self.palette.border)?;
+        write!(out, "{}{:padding$}", row, "")?;
+        self.palette.write(out, "│", self.palette.border)?;
+        write!(out, "\r")
+    }
+
+    // Writes a line of the table's frame `width` wide between its two ends,
+    // such as a divider
+    fn write_border(&self, out: &mut impl Write, (left, right): (char, char), width: usize) -> io::Result<()> {
+        queue!(out, SetForegroundColor(self.palette.border))?;
+        write!(out, "{}{:─<width$}{}\r", left, "", right)?;
+        queue!(out, SetForegroundColor(self.palette.text))
+    }
+
+    // Helper to lay out items such as control hints separated by bars,
+    // wrapping onto further lines when they don't fit the box
+    fn wrap_items<S: AsRef<str>>(&self, items: &[S], total_width: usize) -> Vec<String> {
+        // Lines are given room for their widest characters up front, so they
+        // don't grow an item at a time
+        let line = || String::with_capacity(total_width * 3);
+        let mut lines = Vec::new();
+        let mut current = line();
+
+        for item in items {
+            let item = item.as_ref();
+            let needed = UnicodeWidthStr::width(current.as_str()) + 5 + UnicodeWidthStr::width(item);
+            if !current.is_empty() && needed > total_width {
+                lines.push(std::mem::replace(&mut current, line()));
             }
-            _ => {}
+            current.push_str(if current.is_empty() { "  " } else { "  │  " });
+            current.push_str(item);
         }
 
-        match self.state {
-            GameState::PlayerTurn => {
-                self.handle_player_turn(key);
-                if matches!(self.state, GameState::DealerTurn) {
-                    self.resolve_dealer_turn();
-                }
+        lines.push(current);
+        lines
+    }
+
+    // Helper to write one of several hands on a line of its own, marking
+    // the one in play
+    fn hand_line(&self, i: usize) -> String {
+        let player_hand = &self.player_hands[i];
+        let marker = match self.state {
+            GameState::PlayerTurn if i == self.active_hand => "▶",
+            _ => " ",
+        };
+        let hand = self.shown_hand(Seat::Player(i), &player_hand.hand);
+        tr!(
+            " {} {}: {}  ({})  Bet: {}{}",
+            marker,
+            self.hand_label(i),
+            self.palette.cards(&hand),
+            self.palette.total(&hand),
+            locale::money(player_hand.bet),
+            status_note(player_hand.status)
+        )
+    }
+
+    // Helper to lay several hands out side by side in `width` columns, the
+    // betting spots across the table with each spot's hands next to each
+    // other. Each hand has its cards over its total, its bet and, once the
+    // round's over, how it went, and the one in play is marked. Returns
+    // None if they don't fit, to be written a line each instead.
+    fn hand_columns(&self, width: usize, card_size: Option<CardSize>) -> Option<HandColumns> {
+        // A spot's hands are next to each other, as splitting inserts them.
+        // With one spot its hands are laid out as if each was a spot.
+        let mut spots: Vec<Vec<usize>> = Vec::new();
+        for (i, player_hand) in self.player_hands.iter().enumerate() {
+            match spots.last_mut() {
+                Some(hands) if self.spots > 1 && self.player_hands[hands[0]].spot == player_hand.spot => hands.push(i),
+                _ => spots.push(vec![i]),
             }
-            GameState::RoundEnd => {
-                if let KeyCode::Char('n') | KeyCode::Char('N') = key {
-                    self.reset_round();
+        }
+        let split = spots.iter().any(|hands| hands.len() > 1);
+        let spot_width = (width + HAND_GAP).checked_div(spots.len())?.checked_sub(HAND_GAP)?;
+
+        let playing = |i: usize| matches!(self.state, GameState::PlayerTurn) && i == self.active_hand;
+        let marked = |label: String, active: bool| match active {
+            true => self.palette.paint(&format!("▶ {}", label), self.palette.accent),
+            false => format!("  {}", label),
+        };
+        let pad = |text: &str, width: usize| format!("{}{:pad$}", text, "", pad = width.saturating_sub(theme::width(text)));
+
+        let mut blocks = Vec::with_capacity(spots.len());
+        let mut columns = Vec::with_capacity(self.player_hands.len());
+        for (n, hands) in spots.iter().enumerate() {
+            let column = (spot_width + HAND_GAP).checked_div(hands.len())?.checked_sub(HAND_GAP)?;
+            if card_size.is_some_and(|size| column < size.width()) {
+                return None;
+            }
+            let x = n * (spot_width + HAND_GAP);
+            columns.extend((0..hands.len()).map(|k| (x + k * (column + HAND_GAP), column)));
+
+            // The spot's name, then the hands' own if it's been split
+            let first = hands[0];
+            let mut lines = match self.spots {
+                1 => vec![marked(self.hand_label(first), playing(first))],
+                _ => {
+                    let spot = self.player_hands[first].spot;
+                    vec![marked(tr!("Spot {}", spot + 1), hands.len() == 1 && playing(first))]
                 }
+            };
+            if split {
+                let labels: Vec<String> = match hands.len() {
+                    1 => vec![String::new()],
+                    _ => hands
+                        .iter()
+                        .enumerate()
+                        .map(|(k, &i)| pad(&marked(((b'a' + k as u8) as char).to_string(), playing(i)), column))
+                        .collect(),
+                };
+                lines.push(labels.join(&" ".repeat(HAND_GAP)));
             }
-            _ => {}
+
+            let cells: Vec<Vec<String>> = hands.iter().map(|&i| self.hand_cell(i, card_size, column)).collect();
+            for row in 0..cells[0].len() {
+                let row: Vec<String> = cells.iter().map(|cell| pad(&cell[row], column)).collect();
+                lines.push(row.join(&" ".repeat(HAND_GAP)));
+            }
+            if lines.iter().any(|line| theme::width(line) > spot_width) {
+                return None;
+            }
+            blocks.push(lines);
         }
 
-        true
+        // Each line has a line of every spot, padded to line them up
+        let lines = (0..blocks[0].len())
+            .map(|row| {
+                let cells: Vec<String> = blocks.iter().map(|lines| pad(&lines[row], spot_width)).collect();
+                format!("  {}", cells.join(&" ".repeat(HAND_GAP)))
+            })
+            .collect();
+        let cards_row = if split { 2 } else { 1 };
+        Some(HandColumns { lines, cards_row, columns })
     }
 
-    fn run(&mut self) -> io::Result<()> {
-        enable_raw_mode()?;
-        let mut stdout = io::stdout();
-        execute!(stdout, EnterAlternateScreen)?;
-
-        self.render()?;
+    // Helper for the betting spots laid out across `width` columns while
+    // the bet's placed, each with the bet going on it. None with one spot,
+    // or if they don't fit.
+    fn spot_markers(&self, width: usize) -> Option<String> {
+        if self.spots == 1 {
+            return None;
+        }
+        let spot_width = (width + HAND_GAP) / self.spots - HAND_GAP;
+        let bet = locale::money(self.bet_chips.total());
+        let mut markers = Vec::with_capacity(self.spots);
+        for spot in 0..self.spots {
+            let marker = tr!("○ Spot {}: {}", spot + 1, bet);
+            let pad = spot_width.checked_sub(theme::width(&marker))?;
+            markers.push(format!("{}{:pad$}", marker, ""));
+        }
+        Some(format!("  {}", markers.join(&" ".repeat(HAND_GAP))))
+    }
 
-        loop {
-            if let Event::Key(KeyEvent { code, .. }) = event::read()? {
-                if !self.handle_input(code) {
-                    break;
-                }
-                self.render()?;
+    // Helper for a hand's lines in its column `width` wide: its cards, its
+    // total, its bet and, once the round's over, how it went
+    fn hand_cell(&self, i: usize, card_size: Option<CardSize>, width: usize) -> Vec<String> {
+        let player_hand = &self.player_hands[i];
+        let hand = self.shown_hand(Seat::Player(i), &player_hand.hand);
+        let mut lines = match card_size {
+            Some(size) => {
+                let cards: Vec<Option<&Card>> = hand.cards().iter().map(Some).collect();
+                card_art::row(&cards, width, self.card_back, size, &self.palette)
             }
+            None => vec![format!("  {}", self.palette.cards(&hand))],
+        };
+        lines.push(format!("  {}{}", self.palette.total(&hand), status_note(player_hand.status)));
+        lines.push(tr!("  Bet: {}", locale::money(player_hand.bet)));
+        let settled = matches!(self.state, GameState::RoundEnd | GameState::SessionLimitReached(_) | GameState::Rebuy);
+        if let (true, Some(&outcome)) = (settled, self.round_outcomes.get(i)) {
+            let (mark, net) = match outcome {
+                HandOutcome::Win | HandOutcome::Blackjack | HandOutcome::Charlie => ("✓", 1),
+                HandOutcome::Push => ("=", 0),
+                HandOutcome::Lose | HandOutcome::Bust | HandOutcome::Surrender => ("✗", -1),
+            };
+            lines.push(self.palette.paint(&format!("  {} {}", mark, outcome.name()), self.palette.outcome(net)));
         }
+        lines
+    }
 
-        disable_raw_mode()?;
-        execute!(stdout, LeaveAlternateScreen)?;
-        println!("\nThanks for playing!");
-
-        Ok(())
+    // Helper for the lines a hand's value takes under its cards: one, or
+    // big text beside the label in large print. It's not known while the
+    // hole card's face down.
+    fn value_lines(&self, hand: &Hand, hidden: bool, large_print: bool) -> Vec<String> {
+        let value = match (hidden, large_print) {
+            (true, false) => vec![String::from("???")],
+            (false, false) => vec![self.palette.total(hand)],
+            (true, true) => big_text::lines("???"),
+            (false, true) => self.palette.big_total(hand),
+        };
+        let label = tr!("  Value: ");
+        let labels = iter::once(label.to_string()).chain(iter::repeat(" ".repeat(label.width())));
+        labels.zip(value).map(|(label, value)| format!("{}{}", label, value)).collect()
     }
-}
+
+    // Helper to list what's riding on the table below the hands: the bets
+    // and bankroll, the streak once a round's been played, and side bets
+    fn status_lines(&self) -> Vec<String> {
+        let total_bet: u32 = self.player_hands.iter().map(|h| h.bet).sum();
+        let mut bet_display = tr!("  Bet: {}  │  Bankroll: {}", locale::money(total_bet), locale::money(self.bankroll));
+        if self.spots > 1 {
+            bet_display.push_str(&tr!("  │  Spots: {}", self.spots));
+        }
+        if self.insurance_bet > 0 {
+            bet_display.push_str(&tr!("  │  Insurance: {}", locale::money(self.insurance_bet)));
+        }
+        let mut lines = vec![bet_display];
+
+        // The wide table has them in its shoe panel instead
+        let (width, height) = self.size;
+        if self.shoe_left && Layout::fitting(width, height, self.large_print) != Some(Layout::Wide) {
+            let remaining = self.shoe.remaining();
+            lines.push(tr!("  Cards left: {}  │  Decks left: {:.1}", remaining, remaining as f32 / 52.0));
+        }
+
+        let streaks = &self.session.streaks;
+        if self.session.rounds > 0 {
+            lines.push(tr!(
+                "  Streak: {}  │  Best: W{} L{} P{}",
+                streaks.current_str(),
+                streaks.best_win,
+                streaks.best_loss,
+                streaks.best_push
+            ));
+        }
+
+        if !self.side_bets.is_empty() {
+            let names: Vec<&str> = self.side_bets.iter().map(|s| s.name()).collect();
+            lines.push(tr!("  Side bets ({} each): {}", locale::money(SIDE_BET_STAKE), names.join(", ")));
+        }
+        lines
+    }
+
+    // Helper to list the table's rules and the house edge they give, for
+    // the top of the table
+    fn rules_info(&self) -> Vec<String> {
+        let mut rules_info = self.rules.summary();
+        if let Some(date) = &self.daily {
+            rules_info.insert(0, tr!("Daily {}", date));
+        }
+        if self.practice {
+            rules_info.insert(0, String::from(tr!("Practice")));
+        }
+        rules_info.push(tr!("Edge ≈{:.2}%", self.rules.house_edge()));
+        rules_info
+    }
+
+    // Helper to name a hand in the player section: split hands are numbered,
+    // and with several spots each spot gets a letter per hand it holds
+    fn hand_label(&self, index: usize) -> String {
+        if self.spots == 1 {
+            return tr!("Hand {}", index + 1);
+        }
+
+        let spot = self.player_hands[index].spot;
+        if self.hands_in_spot(spot) == 1 {
+            return tr!("Spot {}", spot + 1);
+        }
+        let position = self.player_hands[..index].iter().filter(|h| h.spot == spot).count();
+        tr!("Spot {}{}", spot + 1, (b'a' + position as u8) as char)
+    }
+
+    // Adds an event to the log, naming the hand it's about if there's more
+    // than one
+    fn log(&mut self, hand: Option<usize>, event: String) {
+        match hand {
+            Some(i) if self.player_hands.len() > 1 => {
+                let event = format!("{}: {}", self.hand_label(i), event);
+                self.events.push(event);
+            }
+            _ => self.events.push(event),
+        }
+    }
+
+    /// Draws the whole screen, table and any popup, into `out`.
+    fn render(&self, out: &mut impl Write) -> io::Result<()> {
+        self.buttons.borrow_mut().clear();
+
+        // Clear screen to the theme's background and move cursor to top-left
+        queue!(out, self.palette.apply(), Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+        // Nothing fits in a terminal smaller than the table, so it waits
+        // to be made bigger
+        let (term_width, term_height) = self.size;
+        let Some(layout) = Layout::fitting(term_width, term_height, self.large_print) else {
+            return self.draw_too_small(out, term_width, term_height);
+        };
+
+        // The settings and the help hide the table
+        if let Some(settings) = &self.settings {
+            *self.buttons.borrow_mut() = settings.draw(out, self.size)?;
+            return Ok(());
+        }
+        if let Some(scroll) = self.help_scroll {
+            return self.draw_scrolling(out, tr!(" HELP "), &self.help_lines(), scroll);
+        }
+        if let Some(scroll) = self.log_scroll {
+            return self.draw_scrolling(out, tr!(" LOG "), &self.log_page(), scroll);
+        }
+
+        // The pause screen hides the table too, so it can be left unattended
+        if let Some(menu) = &self.pause_menu {
+            self.draw_pause_menu(out, menu)?;
+            if self.confirm_quit {
+                self.draw_quit_confirm(out)?;
+            }
+            return Ok(());
+        }
+
+        // Main window dimensions
+        let (window_width, window_height) = match layout {
+            Layout::Cards => (60, 24),
+            Layout::Text => (60, 21),
+            Layout::Compact => (MIN_WIDTH, 10),
+            Layout::Wide => (WIDE_TABLE_WIDTH, 32),
+            Layout::LargePrint => (WIDE_TABLE_WIDTH, 36),
+        };
+        // The event log goes in a strip under the table where there's room
+        let strip_height = LOG_STRIP_LINES as u16 + 2;
+        let strip = self.has_log_strip(layout, window_height);
+        let table_height = if strip { window_height + strip_height } else { window_height };
+        let start_x = (term_width.saturating_sub(window_width)) / 2;
+        let start_y = (term_height.saturating_sub(table_height)) / 2;
+
+        // Draw the main window
+        let below = match layout {
+            Layout::Compact => {
+                self.draw_compact_window(out, start_x, start_y, window_width)?;
+                start_y + window_height
+            }
+            _ => self.draw_main_window(out, start_x, start_y, window_width, layout.card_size())?,
+        };
+        if strip && below + strip_height <= term_height {
+            let (title, lines) = self.strip_panel(window_width as usize - 2);
+            self.draw_panel(out, (start_x, below), window_width, &title, &lines)?;
+        }
+        if layout == Layout::Wide {
+            let (left, right) = (start_x - PANEL_WIDTH - 1, start_x + window_width + 1);
+            let below = self.draw_panel(out, (left, start_y), PANEL_WIDTH, tr!("SESSION"), &self.session_lines())?;
+            let lines = self.log_lines(LOG_PANEL_LINES, PANEL_WIDTH as usize - 2);
+            self.draw_panel(out, (left, below), PANEL_WIDTH, &tr!("LOG [{}]", self.keys.log), &lines)?;
+            let below = self.draw_panel(out, (right, start_y), PANEL_WIDTH, tr!("SHOE"), &self.shoe_lines())?;
+            let (title, lines) = self.tray_panel();
+            self.draw_panel(out, (right, below), PANEL_WIDTH, &title, &lines)?;
+        }
+
+        // Whatever the cards lead to waits until they've all landed
+        if self.dealing.is_some() {
+            return Ok(());
+        }
+
+        // Draw popup if there's a decision to make or a result
+        if let Some(popup) = self.popup() {
+            self.draw_colored_popup(out, &popup)?;
+        }
+
+        Ok(())
+    }
+
+    // Returns the popup over the table, if any: asking whether to leave,
+    // the stats or history, or what the table is waiting on
+    fn popup(&self) -> Option<Popup> {
+        if self.confirm_quit {
+            Some(self.quit_popup())
+        } else if let Some(tab) = self.stats_tab {
+            Some(self.stats_popup(tab))
+        } else if let Some(back) = self.history_view {
+            Some(self.history_popup(back))
+        } else {
+            self.table_popup()
+        }
+    }
+
+    // What the narrator tells of the screen: whatever hides the table, or
+    // else the table, the popup over it and the keys that can be pressed
+    fn narration(&self) -> Vec<String> {
+        if let Some(settings) = &self.settings {
+            let (lines, rows) = settings.lines();
+            return narration::menu(tr!("SETTINGS"), &lines, rows);
+        }
+        if self.help_scroll.is_some() {
+            return narration::menu(tr!("HELP"), &self.help_lines(), 0);
+        }
+        if self.log_scroll.is_some() {
+            return narration::menu(tr!("LOG"), &self.log_page(), 0);
+        }
+        if let Some(menu) = &self.pause_menu
+            && !self.confirm_quit
+        {
+            let (title, lines, rows) = self.pause_lines(menu);
+            return narration::menu(title, &lines, rows);
+        }
+
+        let mut lines = vec![self.rules_info().join(", ")];
+        let dealer_hand = self.shown_hand(Seat::Dealer, &self.dealer_hand);
+        if self.hole_card_face_down() {
+            let shown: Vec<String> = dealer_hand.cards().iter().skip(1).map(narration::card).collect();
+            lines.push(tr!("Dealer: a card face down, {}", shown.join(", ")));
+        } else if !dealer_hand.cards().is_empty() {
+            lines.push(tr!("Dealer: {}", narration::hand(&dealer_hand)));
+        }
+        if let GameState::Betting = self.state {
+            lines.push(tr!("Place your bet: {}", locale::money(self.bet_chips.total())));
+            lines.push(self.bet_notes().join("; "));
+        }
+        for (i, player_hand) in self.player_hands.iter().enumerate() {
+            let status = match player_hand.status {
+                HandStatus::Active if matches!(self.state, GameState::PlayerTurn) && i == self.active_hand => {
+                    tr!(", your turn")
+                }
+                HandStatus::Active => "",
+                HandStatus::Stood => tr!(", stood"),
+                HandStatus::Busted => tr!(", bust"),
+                HandStatus::Charlie => tr!(", Charlie"),
+                HandStatus::Blackjack => tr!(", blackjack"),
+                HandStatus::Surrendered => tr!(", surrendered"),
+            };
+            let hand = self.shown_hand(Seat::Player(i), &player_hand.hand);
+            lines.push(tr!(
+                "{}: {}, bet {}{}",
+                self.hand_label(i),
+                narration::hand(&hand),
+                locale::money(player_hand.bet),
+                status
+            ));
+        }
+        lines.extend(self.status_lines());
+        lines.push(self.notice.clone());
+        match self.popup() {
+            Some(popup) => {
+                lines.push(popup.title);
+                lines.extend(popup.message.lines().map(String::from));
+                lines.push(popup.prompt);
+            }
+            None => lines.push(self.controls(false).join(", ")),
+        }
+        lines.iter().map(|line| narration::plain(line)).filter(|line| !line.is_empty()).collect()
+    }
+
+    fn draw_main_window(
+        &self,
+        out: &mut impl Write,
+        start_x: u16,
+        start_y: u16,
+        width: u16,
+        card_size: Option<CardSize>,
+    ) -> io::Result<u16> {
+        let inner_width = (width - 2) as usize; // Width inside the box borders
+        let card_art = card_size.is_some();
+        let size = card_size.unwrap_or(CardSize::Normal);
+        // Large print has the biggest cards, and totals in big text to match
+        let large_print = card_size == Some(CardSize::Huge);
+
+        // Draw top border with title
+        queue!(out, cursor::MoveTo(start_x, start_y))?;
+        self.write_border(out, ('┌', '┐'), inner_width)?;
+
+        // Title
+        let title = " ♠ BLACKJACK ♥ ";
+        let title_x = start_x + (width - UnicodeWidthStr::width(title) as u16) / 2;
+        queue!(out, cursor::MoveTo(title_x, start_y))?;
+        self.palette.write(out, title, self.palette.accent)?;
+        write!(out, "\r")?;
+
+        let mut line = start_y + 1;
+
+        // Table rules and the house edge they give
+        for rules_line in self.wrap_items(&self.rules_info(), inner_width) {
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &rules_line, inner_width)?;
+            line += 1;
+        }
+
+        // Dealer section header
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('├', '┤'), inner_width)?;
+        line += 1;
+
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        // Right-align the notice and how far into the shoe the game is on
+        // the header line
+        let notice = if self.shoe.cut_card_reached() { tr!("Shuffle coming  ") } else { "" };
+        let gauge = tr!("Shoe {}  ", self.shoe_gauge(SHOE_GAUGE_WIDTH));
+        let label = tr!("  DEALER");
+        let gap = inner_width.saturating_sub(label.width() + notice.width() + gauge.width());
+        self.write_row(out, format_args!("{}{:gap$}{}{}", label, "", notice, gauge), inner_width)?;
+        line += 1;
+
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('├', '┤'), inner_width)?;
+        line += 1;
+
+        // Dealer's cards, with the hole card hidden until the dealer plays.
+        // Cards still being dealt come from the shoe, at the end of this row.
+        let dealer_hand = self.shown_hand(Seat::Dealer, &self.dealer_hand);
+        let shoe = (start_x + width - 3 - size.width() as u16, line);
+        let hidden = self.hole_card_face_down();
+        if card_art {
+            let cards: Vec<Option<&Card>> = dealer_hand
+                .cards()
+                .iter()
+                .enumerate()
+                .map(|(i, card)| (i > 0 || !hidden).then_some(card))
+                .collect();
+            for card_line in card_art::row(&cards, inner_width - 4, self.card_back, size, &self.palette) {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, format_args!("  {}", card_line), inner_width)?;
+                line += 1;
+            }
+            self.draw_moving_card(out, Seat::Dealer, size, (start_x + 3, shoe.1), shoe, inner_width - 4)?;
+
+            if let Some(turning) = self.turning_hole_card(&dealer_hand, size, inner_width - 4) {
+                for (i, card_line) in turning.iter().enumerate() {
+                    queue!(out, cursor::MoveTo(start_x + 3, shoe.1 + i as u16))?;
+                    write!(out, "{}\r", card_line)?;
+                }
+            }
+
+            for value_line in self.value_lines(&dealer_hand, hidden, large_print) {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, &value_line, inner_width)?;
+                line += 1;
+            }
+        } else if hidden {
+            let cards = self.palette.cards(&dealer_hand);
+            let visible = cards.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+            let display = tr!("  Cards: [??] {}", visible);
+
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &display, inner_width)?;
+            line += 1;
+
+            let value_display = tr!("  Value: ???");
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, value_display, inner_width)?;
+            line += 1;
+        } else {
+            let display = tr!("  Cards: {}", self.palette.cards(&dealer_hand));
+
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &display, inner_width)?;
+            line += 1;
+
+            let value_display = tr!("  Value: {}", self.palette.total(&dealer_hand));
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &value_display, inner_width)?;
+            line += 1;
+        }
+
+        // Player section header
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('├', '┤'), inner_width)?;
+        line += 1;
+
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        // Right-align the count on the header line, unless the shoe panel
+        // has it
+        let player_label = tr!("  PLAYER");
+        let count = self.count_note().map(|note| format!("{}  ", note)).unwrap_or_default();
+        let gap = inner_width.saturating_sub(player_label.width() + count.width());
+        self.write_row(out, format_args!("{}{:gap$}{}", player_label, "", count), inner_width)?;
+        line += 1;
+
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('├', '┤'), inner_width)?;
+        line += 1;
+
+        // Player's cards, or the bet being placed before the deal
+        if let GameState::Betting = self.state {
+            let bet_prompt = tr!("  Place your bet: {}", locale::money(self.bet_chips.total()));
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, bet_prompt, inner_width)?;
+            line += 1;
+
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.draw_chips(out, inner_width)?;
+            line += 1;
+
+            let notes = self.wrap_items(&self.bet_notes(), inner_width);
+            for note in &notes {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, note, inner_width)?;
+                line += 1;
+            }
+
+            let markers = self.spot_markers(inner_width - 4);
+            if let Some(markers) = &markers {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, markers, inner_width)?;
+                line += 1;
+            }
+
+            // Keep the table the same height as when the cards are out
+            if card_art {
+                let value_height = if large_print { big_text::HEIGHT } else { 1 };
+                let extra = markers.iter().len() + notes.len() - 1;
+                for _ in 0..(size.height() + value_height - 3).saturating_sub(extra) {
+                    queue!(out, cursor::MoveTo(start_x, line))?;
+                    self.write_row(out, "", inner_width)?;
+                    line += 1;
+                }
+            }
+        } else if let ([player_hand], true) = (self.player_hands.as_slice(), card_art) {
+            let hand = self.shown_hand(Seat::Player(0), &player_hand.hand);
+            let cards: Vec<Option<&Card>> = hand.cards().iter().map(Some).collect();
+            let row_y = line;
+            for card_line in card_art::row(&cards, inner_width - 4, self.card_back, size, &self.palette) {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, format_args!("  {}", card_line), inner_width)?;
+                line += 1;
+            }
+            self.draw_moving_card(out, Seat::Player(0), size, (start_x + 3, row_y), shoe, inner_width - 4)?;
+
+            for value_line in self.value_lines(&hand, false, large_print) {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, &value_line, inner_width)?;
+                line += 1;
+            }
+        } else if let [player_hand] = self.player_hands.as_slice() {
+            let hand = self.shown_hand(Seat::Player(0), &player_hand.hand);
+            let player_display = tr!("  Cards: {}", self.palette.cards(&hand));
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &player_display, inner_width)?;
+            line += 1;
+
+            let player_value = tr!("  Value: {}", self.palette.total(&hand));
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, player_value, inner_width)?;
+            line += 1;
+        } else if let Some(columns) = self.hand_columns(inner_width - 4, card_size) {
+            // With several spots or splits, each hand has a column of its
+            // own where there's room
+            let row_y = line + columns.cards_row as u16;
+            for text in &columns.lines {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, text, inner_width)?;
+                line += 1;
+            }
+            if card_art {
+                for (i, &(x, column)) in columns.columns.iter().enumerate() {
+                    let x = start_x + 3 + x as u16;
+                    self.draw_moving_card(out, Seat::Player(i), size, (x, row_y), shoe, column)?;
+                }
+            }
+        } else {
+            // Otherwise there's a line per hand, marking the one in play
+            for i in 0..self.player_hands.len() {
+                queue!(out, cursor::MoveTo(start_x, line))?;
+                self.write_row(out, self.hand_line(i), inner_width)?;
+                line += 1;
+            }
+        }
+
+        for status_line in self.status_lines() {
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &status_line, inner_width)?;
+            line += 1;
+        }
+
+        // Controls section
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('├', '┤'), inner_width)?;
+        line += 1;
+
+        let controls = self.controls(false);
+
+        for controls_line in self.wrap_items(&controls, inner_width) {
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &controls_line, inner_width)?;
+            self.buttons.borrow_mut().extend(mouse::key_hints(&controls_line, start_x + 1, line));
+            line += 1;
+        }
+
+        // Bottom border
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('└', '┘'), inner_width)?;
+
+        Ok(line + 1)
+    }
+
+    // Draws the table for terminals too small for the full one: a line for
+    // the dealer and each of the player's hands, totals beside the cards
+    fn draw_compact_window(&self, out: &mut impl Write, start_x: u16, start_y: u16, width: u16) -> io::Result<()> {
+        let inner_width = (width - 2) as usize;
+
+        queue!(out, cursor::MoveTo(start_x, start_y))?;
+        self.write_border(out, ('┌', '┐'), inner_width)?;
+        let title = " ♠ BLACKJACK ♥ ";
+        let title_x = start_x + (width - UnicodeWidthStr::width(title) as u16) / 2;
+        queue!(out, cursor::MoveTo(title_x, start_y))?;
+        self.palette.write(out, title, self.palette.accent)?;
+        write!(out, "\r")?;
+
+        let mut line = start_y + 1;
+        for row in self.compact_rows(inner_width) {
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            match row {
+                Some(text) => self.write_row(out, &text, inner_width)?,
+                None => self.write_border(out, ('├', '┤'), inner_width)?,
+            }
+            line += 1;
+        }
+
+        for controls_line in self.wrap_items(&self.controls(true), inner_width) {
+            queue!(out, cursor::MoveTo(start_x, line))?;
+            self.write_row(out, &controls_line, inner_width)?;
+            self.buttons.borrow_mut().extend(mouse::key_hints(&controls_line, start_x + 1, line));
+            line += 1;
+        }
+
+        queue!(out, cursor::MoveTo(start_x, line))?;
+        self.write_border(out, ('└', '┘'), inner_width)
+    }
+
+    // Helper to list the compact table's rows above its key hints: a line
+    // for the dealer and each hand, then the money, None standing for a
+    // divider
+    fn compact_rows(&self, inner_width: usize) -> Vec<Option<String>> {
+        let mut rows: Vec<Option<String>> = Vec::new();
+
+        let dealer_hand = self.shown_hand(Seat::Dealer, &self.dealer_hand);
+        let hidden = self.hole_card_face_down();
+        rows.push(Some(if dealer_hand.cards().is_empty() {
+            String::from(tr!("  Dealer:"))
+        } else if hidden {
+            let cards = self.palette.cards(&dealer_hand);
+            let visible = cards.split_once(' ').map_or(String::new(), |(_, rest)| format!(" {}", rest));
+            tr!("  Dealer: [??]{} (?)", visible)
+        } else {
+            tr!("  Dealer: {} ({})", self.palette.cards(&dealer_hand), self.palette.total(&dealer_hand))
+        }));
+        rows.push(None);
+
+        if let GameState::Betting = self.state {
+            rows.push(Some(tr!("  Place your bet: {}", locale::money(self.bet_chips.total()))));
+            let notes = self.bet_notes();
+            if !notes.is_empty() {
+                rows.extend(self.wrap_items(&notes, inner_width).into_iter().map(Some));
+            }
+        } else if let [player_hand] = self.player_hands.as_slice() {
+            let hand = self.shown_hand(Seat::Player(0), &player_hand.hand);
+            rows.push(Some(tr!("  You: {} ({})", self.palette.cards(&hand), self.palette.total(&hand))));
+        } else {
+            for (i, player_hand) in self.player_hands.iter().enumerate() {
+                let marker = match self.state {
+                    GameState::PlayerTurn if i == self.active_hand => "▶",
+                    _ => " ",
+                };
+                let status = match player_hand.status {
+                    HandStatus::Active | HandStatus::Stood => "",
+                    HandStatus::Busted => tr!(" Bust"),
+                    HandStatus::Charlie => tr!(" Charlie"),
+                    HandStatus::Blackjack => tr!(" BJ"),
+                    HandStatus::Surrendered => tr!(" Surr."),
+                };
+                let hand = self.shown_hand(Seat::Player(i), &player_hand.hand);
+                rows.push(Some(format!(
+                    " {} {}: {} ({}){}",
+                    marker,
+                    self.hand_label(i),
+                    self.palette.cards(&hand),
+                    self.palette.total(&hand),
+                    status
+                )));
+            }
+        }
+
+        let total_bet: u32 = self.player_hands.iter().map(|h| h.bet).sum();
+        let mut money = vec![tr!("Bet: {}", locale::money(total_bet)), tr!("Bank: {}", locale::money(self.bankroll))];
+        if self.insurance_bet > 0 {
+            money.push(tr!("Ins: {}", locale::money(self.insurance_bet)));
+        }
+        money.extend(self.count_items());
+        money.push(tr!("Shoe {}", self.shoe_gauge(SHOE_GAUGE_WIDTH)));
+        if self.shoe.cut_card_reached() {
+            money.push(String::from(tr!("Shuffle coming")));
+        }
+        rows.extend(self.wrap_items(&money, inner_width).into_iter().map(Some));
+        rows.push(None);
+        rows
+    }
+
+    // Helper to draw one of the wide table's side panels: a box with its
+    // title on the top border. Returns the line below it.
+    fn draw_panel(
+        &self,
+        out: &mut impl Write,
+        (x, y): (u16, u16),
+        width: u16,
+        title: &str,
+        lines: &[String],
+    ) -> io::Result<u16> {
+        let inner_width = (width - 2) as usize;
+        queue!(out, cursor::MoveTo(x, y))?;
+        self.write_border(out, ('┌', '┐'), inner_width)?;
+        queue!(out, cursor::MoveTo(x + 2, y))?;
+        queue!(out, SetForegroundColor(self.palette.accent))?;
+        write!(out, " {} \r", title)?;
+        queue!(out, SetForegroundColor(self.palette.text))?;
+
+        let mut line = y + 1;
+        for text in lines {
+            queue!(out, cursor::MoveTo(x, line))?;
+            self.write_row(out, text, inner_width)?;
+            line += 1;
+        }
+        queue!(out, cursor::MoveTo(x, line))?;
+        self.write_border(out, ('└', '┘'), inner_width)?;
+        Ok(line + 1)
+    }
+
+    // Whether the event log is shown in a strip under the table, as it is
+    // when there's room for it below any table but the compact one. The
+    // wide table has it in a panel instead.
+    fn has_log_strip(&self, layout: Layout, window_height: u16) -> bool {
+        let (_, term_height) = self.size;
+        !matches!(layout, Layout::Compact | Layout::Wide) && term_height >= window_height + LOG_STRIP_LINES as u16 + 2
+    }
+
+    // Helper to list the newest events in the log, as many lines of them
+    // as are shown and wrapped to `width`. Events from a key whose cards
+    // are still being dealt wait for them to land.
+    fn log_lines(&self, count: usize, width: usize) -> Vec<String> {
+        let shown = if self.dealing.is_some() { self.events_before } else { self.events.count() };
+        let mut lines = self.events.recent(shown, count, width);
+        if lines.is_empty() {
+            lines.push(String::from(tr!("  Nothing yet")));
+        }
+        lines.resize(count, String::new());
+        lines
+    }
+
+    // Helper to list how the session's going, for the panel left of the
+    // wide table
+    fn session_lines(&self) -> Vec<String> {
+        let session = &self.session;
+        let streaks = &session.streaks;
+        // Chips bet on the round in play aren't lost yet
+        let net = session.net(self.bankroll + self.chips_at_stake());
+        let mut lines = vec![
+            tr!("  Rounds: {}", session.rounds),
+            tr!("  Net: {}", self.palette.paint(&locale::net(net), self.palette.outcome(net))),
+            tr!("  Peak bankroll: {}", locale::money(session.peak_bankroll)),
+            tr!("  Streak: {}", streaks.current_str()),
+            tr!("  Best: W{} L{} P{}", streaks.best_win, streaks.best_loss, streaks.best_push),
+        ];
+        if session.rebuys > 0 {
+            lines.push(tr!("  Rebuys: {}", session.rebuys));
+        }
+        lines
+    }
+
+    // Helper for a gauge `width` columns wide of how far into the shoe the
+    // game is, filled for the cards dealt, with the cut card marked where
+    // it sits
+    fn shoe_gauge(&self, width: usize) -> String {
+        let size = self.shoe.size().max(1);
+        let dealt = (size - self.shoe.remaining()) * width / size;
+        let cut = ((size - self.shoe.cut_card()) * width / size).min(width - 1);
+        (0..width)
+            .map(|i| match i {
+                _ if i == cut => '┃',
+                _ if i < dealt => '█',
+                _ => '░',
+            })
+            .collect()
+    }
+
+    // Helper to work out the count of the cards seen since the shuffle: the
+    // running count, and how many cards and how many aces haven't been seen
+    fn seen_count(&self, system: &dyn CountingSystem) -> (i32, usize, usize) {
+        // The hole card doesn't count until it's been seen
+        let mut count = self.shoe.running_count(system);
+        let mut unseen = self.shoe.remaining();
+        let mut aces_unseen = self.shoe.aces_left();
+        let hidden = self.hole_card_face_down();
+        if let (true, Some(hole_card)) = (hidden, self.dealer_hand.cards().first()) {
+            count -= system.tag(hole_card.rank);
+            unseen += 1;
+            aces_unseen += usize::from(hole_card.rank == Rank::Ace);
+        }
+        (count, unseen, aces_unseen)
+    }
+
+    // Helper to list the counts in short, for the tables without the shoe
+    // panel: the running count, the true count and the aces seen
+    fn count_items(&self) -> Vec<String> {
+        // The counts would give away the answer to a quiz
+        if self.quiz.as_ref().is_some_and(|quiz| quiz.off_by.is_none()) {
+            return vec![String::from(tr!("Count hidden for the quiz"))];
+        }
+        let system = self.count_system.counting();
+        let (count, unseen, aces_unseen) = self.seen_count(system);
+        let mut items = vec![format!("{} {:+}", system.name(), count)];
+        if let Some(true_count) = self.true_count(system, count, unseen) {
+            items.push(tr!("True {}", true_count));
+        }
+        if system.ace_side_count() {
+            let aces = self.shoe.size() / 13;
+            items.push(tr!("Aces {}/{}", aces - aces_unseen, aces));
+        }
+        items
+    }
+
+    // Helper to put the counts on one line, for the player's header on the
+    // tables that don't have them in the shoe panel
+    fn count_note(&self) -> Option<String> {
+        let (width, height) = self.size;
+        (Layout::fitting(width, height, self.large_print) != Some(Layout::Wide))
+            .then(|| self.count_items().join("  │  "))
+    }
+
+    // Helper to list what's left in the shoe and its count, for the first
+    // panel right of the wide table
+    fn shoe_lines(&self) -> Vec<String> {
+        let system = self.count_system.counting();
+        let (count, unseen, aces_unseen) = self.seen_count(system);
+        let mut lines = vec![format!("  {}", self.shoe_gauge(PANEL_WIDTH as usize - 6))];
+        if self.shoe_left {
+            let remaining = self.shoe.remaining();
+            lines.push(tr!("  Cards left: {}", remaining));
+            lines.push(tr!("  Decks left: {:.1}", remaining as f32 / 52.0));
+        }
+        // The counts would give away the answer to a quiz
+        if self.quiz.as_ref().is_some_and(|quiz| quiz.off_by.is_none()) {
+            lines.push(String::from(tr!("  Hidden for the quiz")));
+            return lines;
+        }
+        lines.push(tr!("  {} count: {:+}", system.name(), count));
+        if let Some(true_count) = self.true_count(system, count, unseen) {
+            lines.push(tr!("  True count: {}", true_count));
+        }
+        // Systems that leave the aces out keep a count of them alongside
+        if system.ace_side_count() {
+            let aces = self.shoe.size() / 13;
+            lines.push(tr!("  Aces seen: {} of {}", aces - aces_unseen, aces));
+        }
+        if self.shoe.cut_card_reached() {
+            lines.push(String::from(tr!("  Shuffle coming")));
+        }
+        lines
+    }
+
+    // Helper to write the true count of a running count with `unseen` cards
+    // left, rounded as the player asked. An unbalanced count has none, as
+    // it's bet and played by as it runs.
+    fn true_count(&self, system: &dyn CountingSystem, count: i32, unseen: usize) -> Option<String> {
+        if !system.balanced() {
+            return None;
+        }
+        let true_count = count::true_count(count, unseen, self.true_count_rounding);
+        Some(match self.true_count_rounding {
+            Rounding::Exact => locale::format("{:+.1}", &[&true_count]),
+            _ => format!("{:+}", true_count as i32),
+        })
+    }
+
+    // Helper to list what's in the discard tray, for practicing judging how
+    // much of the shoe is gone: how many cards, and how many of each value
+    // from the aces down, wrapped to fit `width`
+    fn discard_lines(&self, width: usize) -> Vec<String> {
+        let count = self.discards.count();
+        let mut lines = vec![tr!("  {} cards  │  {:.1} decks", count, count as f32 / 52.0)];
+        let by_value = self.discards.by_value();
+        let values =
+            std::iter::once(("A", by_value[9])).chain((2..=10).map(|v| (VALUE_LABELS[v - 2], by_value[v - 2])));
+        let mut current = String::new();
+        for (label, count) in values {
+            let item = format!("  {} {}", label, count);
+            if current.width() + item.width() > width {
+                lines.push(std::mem::take(&mut current));
+            }
+            current.push_str(&item);
+        }
+        lines.push(current);
+        lines
+    }
+
+    // Helper for the panel under the shoe's in the wide table: the discard
+    // tray if it's shown, else the last few rounds
+    fn tray_panel(&self) -> (String, Vec<String>) {
+        match self.show_discards {
+            true => (tr!("DISCARDS [{}]", self.keys.discards), self.discard_lines(PANEL_WIDTH as usize - 4)),
+            false => (String::from(tr!("LAST ROUNDS")), self.recent_lines()),
+        }
+    }
+
+    // Helper for the strip under the table: the discard tray if it's shown,
+    // else the newest events in the log, as many lines as it has
+    fn strip_panel(&self, width: usize) -> (String, Vec<String>) {
+        let mut panel = match self.show_discards {
+            true => (tr!("DISCARDS [{}]", self.keys.discards), self.discard_lines(width)),
+            false => (tr!("LOG [{}]", self.keys.log), self.log_lines(LOG_STRIP_LINES, width)),
+        };
+        panel.1.resize(LOG_STRIP_LINES, String::new());
+        panel
+    }
+
+    // Helper to list the last few rounds, for the panel below the shoe's
+    fn recent_lines(&self) -> Vec<String> {
+        match self.history.recent_rounds(RECENT_ROUNDS) {
+            Ok(rounds) if rounds.is_empty() => vec![String::from(tr!("  None yet"))],
+            Ok(rounds) => rounds
+                .iter()
+                .map(|round| {
+                    let net = self.palette.paint(&locale::net(round.net), self.palette.outcome(round.net));
+                    tr!("  Dealer {:<2}  {}", round.dealer_total, net)
+                })
+                .collect(),
+            Err(_) => vec![String::from(tr!("  Couldn't read them"))],
+        }
+    }
+
+    // Helper to list the key hints for what can be done now. The compact
+    // table leaves out all but the main ones, which still work regardless.
+    fn controls(&self, compact: bool) -> Vec<String> {
+        let keys = &self.keys;
+        match self.state {
+            GameState::PlayerTurn => {
+                let mut controls = vec![tr!("[{}] Hit", keys.hit), tr!("[{}] Stand", keys.stand)];
+                if self.can_double() {
+                    controls.push(tr!("[{}] Double", keys.double));
+                }
+                if self.can_split() {
+                    controls.push(tr!("[{}] Split", keys.split));
+                }
+                if self.can_surrender() {
+                    controls.push(tr!("[{}] Surrender", keys.surrender));
+                }
+                if !compact {
+                    controls.push(tr!("[{}] Stats", keys.stats));
+                }
+                controls.push(tr!("[{}] Help", keys.help));
+                controls.push(tr!("[{}] Menu", keys.menu));
+                controls.push(tr!("[{}] Quit", keys.quit));
+                controls
+            }
+            GameState::Replay => {
+                let replay_step = self.replay.as_ref().map_or(String::new(), |replay| {
+                    let frame = replay.frame();
+                    tr!("Step {}/{}: {}", replay.position + 1, replay.len(), frame.event)
+                });
+                vec![
+                    replay_step,
+                    String::from(tr!("[←/→] Back / Forward")),
+                    tr!("[{}] Quit", keys.quit),
+                ]
+            }
+            GameState::Betting if compact => vec![
+                String::from(tr!("[0-9] Bet")),
+                tr!("[{}] Undo", keys.undo),
+                tr!("[{}] Deal", keys.deal),
+                tr!("[{}] Help", keys.help),
+                tr!("[{}] Menu", keys.menu),
+                tr!("[{}] Quit", keys.quit),
+            ],
+            GameState::Betting => vec![
+                String::from(tr!("[0-9] Type a bet")),
+                tr!("[↑/↓] ±{}", Chip::ALL[self.bet_chip].value()),
+                String::from(tr!("[←/→] Chip")),
+                tr!("[{}] Max/Min", keys.bet_limit),
+                tr!("[{}] Undo", keys.undo),
+                tr!("[{}] Clear", keys.clear),
+                tr!("[{}] Deal", keys.deal),
+                tr!("[{}] Stats", keys.stats),
+                tr!("[{}] Help", keys.help),
+                tr!("[{}] Menu", keys.menu),
+                tr!("[{}] Quit", keys.quit),
+            ],
+            GameState::RoundEnd if compact => vec![
+                tr!("[{}] New Round", keys.new_round),
+                tr!("[{}] Rebet", keys.rebet),
+                tr!("[{}] Help", keys.help),
+                tr!("[{}] Menu", keys.menu),
+                tr!("[{}] Quit", keys.quit),
+            ],
+            GameState::RoundEnd => vec![
+                tr!("[{}] New Round", keys.new_round),
+                tr!("[{}] Rebet & Deal", keys.rebet),
+                tr!("[{}/{}/{}] Spots", keys.one_spot, keys.two_spots, keys.three_spots),
+                tr!("[{}] Lucky Ladies", keys.lucky_ladies),
+                tr!("[{}] Match the Dealer", keys.match_the_dealer),
+                tr!("[{}] Royal Match", keys.royal_match),
+                tr!("[{}] Buster", keys.buster),
+                tr!("[{}] Stats", keys.stats),
+                tr!("[{}] History", keys.history),
+                tr!("[{}] Export CSV", keys.export_csv),
+                tr!("[{}] Help", keys.help),
+                tr!("[{}] Menu", keys.menu),
+                tr!("[{}] Quit", keys.quit),
+            ],
+            _ => vec![tr!("[{}] Quit", keys.quit)],
+        }
+    }
+
+    // Returns the popup with the session's or lifetime stats
+    fn stats_popup(&self, scope: Scope) -> Popup {
+        let prompt = tr!("[Tab] Switch  │  [{}] Export JSON  │  Any key: back", self.keys.export_json);
+        let popup = |title: &str, message| Popup {
+            title: title.to_string(),
+            message,
+            prompt: prompt.clone(),
+            color: self.palette.border,
+        };
+        let stats = match self.history.stats(scope) {
+            Ok(stats) => stats,
+            Err(err) => return popup(tr!("STATS"), tr!("Couldn't read the history:\n{}", err)),
+        };
+
+        let (title, message) = match scope {
+            Scope::Session => (
+                tr!("SESSION STATS"),
+                tr!(
+                    "Hands: {}  │  Rounds: {}\n\
+                     Won: {}  │  Lost: {}  │  Pushed: {}\n\
+                     Blackjacks: {}  │  Busts: {}\n\
+                     Biggest win: {}  │  Biggest loss: {}\n\
+                     Net: {}  │  Rebuys: {}",
+                    stats.hands.hands,
+                    stats.rounds,
+                    stats.hands.wins,
+                    stats.hands.losses,
+                    stats.hands.pushes,
+                    stats.hands.blackjacks,
+                    stats.hands.busts,
+                    locale::money(stats.biggest_win),
+                    locale::money(stats.biggest_loss),
+                    locale::net(stats.net),
+                    self.session.rebuys
+                ),
+            ),
+            Scope::Lifetime => (tr!("LIFETIME STATS"), lifetime_summary(&stats)),
+        };
+        let message = match self.notice.as_str() {
+            "" => message,
+            notice => format!("{}\n{}", message, notice),
+        };
+        popup(title, message)
+    }
+
+    /// The table rules, what everything pays and every key, for the help.
+    fn help_lines(&self) -> Vec<String> {
+        let rules = &self.rules;
+        let mut lines = vec![String::from(tr!("  TABLE RULES"))];
+        for line in self.wrap_items(&rules.summary(), 56) {
+            lines.push(format!("  {}", line));
+        }
+        lines.push(tr!("    House edge ≈{:.2}% with basic strategy", rules.house_edge()));
+        lines.push(String::new());
+
+        lines.push(String::from(tr!("  PAYOUTS")));
+        let mut payouts = vec![
+            (String::from(tr!("Win")), String::from("1:1")),
+            (String::from(tr!("Blackjack")), rules.blackjack_payout.to_string()),
+            (String::from(tr!("Insurance")), String::from("2:1")),
+        ];
+        if rules.late_surrender || rules.early_surrender {
+            payouts.push((String::from(tr!("Surrender")), String::from(tr!("Half the bet back"))));
+        }
+        if let Some(cards) = rules.charlie {
+            payouts.push((tr!("{}-card Charlie", cards), String::from("1:1")));
+        }
+        for side_bet in SideBet::ALL {
+            payouts.push((side_bet.name().to_string(), side_bet.paytable(rules.decks)));
+        }
+        // The names' column is widened for any that don't fit
+        let width = payouts.iter().map(|(name, _)| name.width() + 1).fold(20, usize::max);
+        for (name, pays) in payouts {
+            lines.push(format!("    {}{:pad$}{}", name, "", pays, pad = width - name.width()));
+        }
+        lines.push(tr!("    Side bets are {} chips each", locale::money(SIDE_BET_STAKE)));
+        lines.push(String::new());
+
+        // Two keys to a line
+        lines.push(String::from(tr!("  KEYS")));
+        let labels: Vec<String> = KeyBindings::ACTIONS.iter().map(|action| keys::label(action)).collect();
+        let width = labels.iter().map(|label| label.width() + 1).fold(19, usize::max);
+        let bindings: Vec<String> = KeyBindings::ACTIONS
+            .iter()
+            .zip(&labels)
+            .map(|(action, label)| {
+                let key = self.keys.key(action).map_or(String::new(), |key| key.to_string());
+                format!("{}{:pad$}{:<7}", label, "", key, pad = width - label.width())
+            })
+            .collect();
+        for pair in bindings.chunks(2) {
+            lines.push(format!("    {}", pair.join("")).trim_end().to_string());
+        }
+        let keys = [
+            (tr!("Bet"), tr!("0-9 while betting")),
+            (tr!("Chips"), tr!("↑/↓ ←/→ while betting")),
+        ];
+        for (name, when) in keys {
+            lines.push(format!("    {}{:pad$}{}", name, "", when, pad = width + 1 - name.width()));
+        }
+        lines
+    }
+
+    // Every event in the log, for the screen showing all of it
+    fn log_page(&self) -> Vec<String> {
+        match self.events.count() {
+            0 => vec![String::from(tr!("  Nothing yet"))],
+            _ => self.events.lines(),
+        }
+    }
+
+    // How many lines of a scrolling page such as the help fit on the
+    // screen, leaving room for the border and the hint
+    fn page_height(&self) -> usize {
+        let (_, term_height) = self.size;
+        (term_height as usize).saturating_sub(4).max(1)
+    }
+
+    // Returns where a scrolling page of `lines` is scrolled to after a key,
+    // or None if the key closes it, as any key but scrolling does
+    fn scroll_page(&self, scroll: usize, key: KeyCode, lines: usize) -> Option<usize> {
+        if self.keys.moves_up(key) {
+            Some(scroll.saturating_sub(1))
+        } else if self.keys.moves_down(key) {
+            let last = lines.saturating_sub(self.page_height());
+            Some((scroll + 1).min(last))
+        } else {
+            None
+        }
+    }
+
+    fn draw_scrolling(&self, out: &mut impl Write, title: &str, lines: &[String], scroll: usize) -> io::Result<()> {
+        let visible = self.page_height().min(lines.len());
+        let scroll = scroll.min(lines.len() - visible);
+        let mut shown = lines[scroll..scroll + visible].to_vec();
+        shown.push(String::new());
+        shown.push(if visible < lines.len() {
+            tr!("  [↑/↓] Scroll {}-{} of {}  │  Any key: back", scroll + 1, scroll + visible, lines.len())
+        } else {
+            String::from(tr!("  Any key: back"))
+        });
+        *self.buttons.borrow_mut() = screen::draw_themed_box(out, self.size, title, &shown, 0, &self.palette)?;
+        Ok(())
+    }
+
+    // Returns the popup with a past round of the session, counting back
+    // from the latest
+    fn history_popup(&self, back: usize) -> Popup {
+        let popup = |title: &str, message| Popup {
+            title: title.to_string(),
+            message,
+            prompt: String::from(tr!("[←/→] Older / Newer  │  Any key: back")),
+            color: self.palette.border,
+        };
+        let rounds = match self.history.session_rounds() {
+            Ok(0) => return popup(tr!("HAND HISTORY"), String::from(tr!("No rounds played yet."))),
+            Ok(rounds) => rounds,
+            Err(err) => return popup(tr!("HAND HISTORY"), tr!("Couldn't read the history:\n{}", err)),
+        };
+        let round = match self.history.session_round(back) {
+            Ok(round) => round,
+            Err(err) => return popup(tr!("HAND HISTORY"), tr!("Couldn't read the history:\n{}", err)),
+        };
+
+        let mut message = tr!("Dealer: {} ({})", round.dealer_cards, round.dealer_total);
+        for hand in &round.hands {
+            message.push_str(&tr!(
+                "\nSpot {}: {} ({})  Bet {}  {}",
+                hand.spot + 1,
+                hand.cards,
+                hand.total,
+                locale::money(hand.bet),
+                HandOutcome::from_label(&hand.outcome).map_or(hand.outcome.as_str(), |outcome| outcome.name())
+            ));
+        }
+        if !round.actions.is_empty() {
+            message.push_str(&tr!("\nActions: {}", round.actions));
+        }
+        message.push_str(&tr!("\nNet: {}", locale::net(round.net)));
+
+        popup(&tr!("ROUND {} OF {}", rounds - back, rounds), message)
+    }
+
+    // Returns the popup the table is waiting on, if any: an offer to take
+    // or turn down, a limit reached, or how the round went
+    fn table_popup(&self) -> Option<Popup> {
+        let popup = |title: &str, message, prompt| Popup {
+            title: title.to_string(),
+            message,
+            prompt,
+            color: self.palette.border,
+        };
+        Some(if let Some(saved) = &self.resume {
+            let message = tr!(
+                "A game left unfinished is saved.\nRounds: {}  │  Bankroll: {}  │  Net: {}",
+                saved.session.rounds,
+                locale::money(saved.bankroll),
+                locale::net(saved.session.net(saved.bankroll))
+            );
+            let prompt = tr!("[{}] Resume  │  [{}] Start fresh", self.keys.yes, self.keys.no);
+            popup(tr!("RESUME GAME"), message, prompt)
+        } else if let GameState::EvenMoneyOffer = self.state {
+            let bet = self.player_hands[0].bet;
+            let mut message = tr!("Blackjack vs dealer Ace. Take even money ({})?", locale::money(bet));
+            if self.practice {
+                message.push_str(&format!("\n{}", self.insurance_advice()));
+            }
+            let prompt = tr!("Press [{}] to take it or [{}] to decline", self.keys.yes, self.keys.no);
+            popup(tr!("EVEN MONEY"), message, prompt)
+        } else if let GameState::InsuranceOffer = self.state {
+            let cost = self.insurance_cost();
+            let mut message = tr!(
+                "Dealer shows an Ace. Insure for {}?\nPays {} if the dealer has blackjack.",
+                locale::money(cost),
+                locale::money(cost * 2)
+            );
+            if self.practice {
+                message.push_str(&format!("\n{}", self.insurance_advice()));
+            }
+            let keys = &self.keys;
+            let prompt = if self.rules.early_surrender {
+                tr!("[{}] Insure  │  [{}] Decline  │  [{}] Surrender", keys.yes, keys.no, keys.surrender)
+            } else {
+                tr!("Press [{}] to insure or [{}] to decline", keys.yes, keys.no)
+            };
+            popup(tr!("INSURANCE"), message, prompt)
+        } else if let GameState::SurrenderOffer = self.state {
+            let prompt = tr!("[{}] Surrender  │  [{}] Play on", self.keys.surrender, self.keys.no);
+            popup(tr!("EARLY SURRENDER"), String::from(tr!("Surrender before the dealer peeks?")), prompt)
+        } else if let (GameState::CountQuiz, Some(quiz)) = (self.state, &self.quiz) {
+            let name = self.count_system.counting().name();
+            let Some(off_by) = quiz.off_by else {
+                let typed = if quiz.typed.is_empty() { "_" } else { &quiz.typed };
+                let message = tr!("The shoe's about to be shuffled.\nWhat's the {} count?  {}", name, typed);
+                let prompt = tr!(
+                    "[0-9] Count  │  [-] Minus  │  [{}] Answer  │  [{}] Skip",
+                    self.keys.deal, self.keys.no
+                );
+                return Some(popup(tr!("COUNT QUIZ"), message, prompt));
+            };
+            let mut message = match off_by {
+                0 => tr!("Spot on, the {} count was {:+}.", name, quiz.count),
+                _ => tr!("The {} count was {:+}, you were {} off.", name, quiz.count, off_by),
+            };
+            if let Some(true_count) = &quiz.true_count {
+                message.push_str(&tr!("\nThat's a true count of {}.", true_count));
+            }
+            let record = &self.quiz_record;
+            message.push_str(&tr!(
+                "\nSpot on {} of {} times, {} off on average.",
+                record.right,
+                record.answered,
+                locale::decimal(record.average_miss(), 1)
+            ));
+            if !self.notice.is_empty() {
+                message.push_str(&format!("\n{}", self.notice));
+            }
+            popup(tr!("COUNT QUIZ"), message, tr!("[{}] Place your bet", self.keys.deal))
+        } else if let GameState::SessionLimitReached(limit) = self.state {
+            let message = tr!(
+                "{}\nRounds: {}  │  Net: {}\nBankroll: {}  │  Peak: {}  │  Rebuys: {}",
+                self.round_result,
+                self.session.rounds,
+                locale::net(self.session.net(self.bankroll)),
+                locale::money(self.bankroll),
+                locale::money(self.session.peak_bankroll),
+                self.session.rebuys
+            );
+            let prompt = tr!("[{}] Keep playing  │  [{}] Cash out", self.keys.keep_playing, self.keys.cash_out);
+            popup(limit.name(), message, prompt)
+        } else if let GameState::Rebuy = self.state {
+            let message = tr!(
+                "{}\nNot enough left for the {} minimum.",
+                self.round_result, locale::money(self.rules.min_bet)
+            );
+            let prompt = tr!(
+                "[{}] Rebuy for {}  │  [{}] Quit",
+                self.keys.rebuy, locale::money(self.session.buy_in), self.keys.quit
+            );
+            popup(tr!("OUT OF CHIPS"), message, prompt)
+        } else if !self.round_result.is_empty() {
+            let net = self.bankroll as i64 - self.round_start_bankroll as i64;
+            let mut items = self.payout_breakdown();
+            items.push((String::from(tr!("Net")), net));
+
+            // The amounts are lined up, each padded to the widest
+            let label_width = items.iter().map(|(label, _)| theme::width(label)).max().unwrap_or(0);
+            let amounts: Vec<String> = items.iter().map(|&(_, amount)| locale::net(amount)).collect();
+            let amount_width = amounts.iter().map(|amount| theme::width(amount)).max().unwrap_or(0);
+            let mut message = format!("{}\n", self.round_result);
+            for (i, ((label, _), amount)) in items.iter().zip(&amounts).enumerate() {
+                if i == items.len() - 1 {
+                    message.push_str(&format!("\n{}", "─".repeat(label_width + 2 + amount_width)));
+                }
+                let label_pad = label_width - theme::width(label);
+                let amount_pad = amount_width - theme::width(amount);
+                message.push_str(&format!("\n{}{:label_pad$}  {:amount_pad$}{}", label, "", "", amount));
+            }
+            if !self.notice.is_empty() {
+                message.push_str(&format!("\n{}", self.notice));
+            }
+            let prompt = tr!(
+                "[{}] New bet  │  [{}] Rebet & deal  │  [{}] Quit",
+                self.keys.new_round, self.keys.rebet, self.keys.quit
+            );
+            Popup { title: String::from(tr!("ROUND RESULT")), message, prompt, color: self.palette.outcome(net) }
+        } else {
+            return None;
+        })
+    }
+
+    // Returns what each part of the round's bets won or lost: the main
+    // bets, the extra put down to double, any bets handed back, insurance
+    // and each side bet, leaving out what wasn't bet
+    fn payout_breakdown(&self) -> Vec<(String, i64)> {
+        let (mut main, mut doubles) = (0, 0);
+        for (player_hand, outcome) in self.player_hands.iter().zip(&self.round_outcomes) {
+            let bet = player_hand.bet as i64;
+            let net = match outcome {
+                HandOutcome::Win | HandOutcome::Charlie => bet,
+                // Even money is a blackjack paid 1:1
+                HandOutcome::Blackjack if player_hand.status == HandStatus::Blackjack => {
+                    self.rules.blackjack_payout.winnings(player_hand.bet) as i64
+                }
+                HandOutcome::Blackjack => bet,
+                HandOutcome::Push => 0,
+                HandOutcome::Lose | HandOutcome::Bust => -bet,
+                HandOutcome::Surrender => -(bet - bet / 2),
+            };
+            // A doubled hand has twice the bet it was dealt
+            if player_hand.bet > self.last_bet {
+                main += net / 2;
+                doubles += net - net / 2;
+            } else {
+                main += net;
+            }
+        }
+
+        let mut items = vec![(String::from(tr!("Main bet")), main)];
+        if self.player_hands.iter().any(|h| h.bet > self.last_bet) {
+            items.push((String::from(tr!("Doubles")), doubles));
+        }
+        // Under OBO rules the extra from doubles and splits comes back
+        // when the dealer's blackjack is only found at the end
+        let dealer_blackjack = self.dealer_hand.is_blackjack();
+        if self.rules.no_hole_card && self.rules.original_bets_only && dealer_blackjack {
+            let refund = original_bets_refund(&self.player_hands, self.last_bet);
+            if refund > 0 {
+                items.push((String::from(tr!("Returned")), refund as i64));
+            }
+        }
+        if self.insurance_bet > 0 {
+            let insurance = self.insurance_bet as i64;
+            items.push((String::from(tr!("Insurance")), if dealer_blackjack { insurance * 2 } else { -insurance }));
+        }
+        for (side_bet, odds) in &self.side_bet_results {
+            let net = odds.map_or(-(SIDE_BET_STAKE as i64), |odds| odds.winnings(SIDE_BET_STAKE) as i64);
+            items.push((side_bet.name().to_string(), net));
+        }
+        items
+    }
+
+    // Returns whether insurance is worth taking, for practice games: it
+    // pays 2:1, so it's only worth it when over a third of the cards the
+    // player hasn't seen, the hole card among them, are worth ten
+    fn insurance_advice(&self) -> String {
+        let hole_card = self.dealer_hand.cards().first().filter(|_| self.dealer_hand.len() > 1);
+        let unseen = self.shoe.remaining() + hole_card.iter().len();
+        let tens = self.shoe.tens_left() + hole_card.iter().filter(|card| card.rank.value() == 10).count();
+        let share = tens as f64 * 100.0 / unseen.max(1) as f64;
+        if tens * 3 > unseen {
+            tr!("Correct play: insure. Tens are {:.1}% of the unseen cards, over a third.", share)
+        } else {
+            tr!("Correct play: decline. Tens are {:.1}% of the unseen cards, not over a third.", share)
+        }
+    }
+
+    // Returns the popup asking whether to leave the table
+    fn quit_popup(&self) -> Popup {
+        let message = match self.chips_at_stake() {
+            0 => tr!("Leave the table with {} chips?", locale::money(self.bankroll)),
+            stake => tr!("The {} chips bet this round will be lost.\nLeave the table anyway?", locale::money(stake)),
+        };
+        let prompt = tr!("[{}] Leave  │  [{}] Keep playing", self.keys.yes, self.keys.menu);
+        Popup { title: String::from(tr!("QUIT")), message, prompt, color: self.palette.border }
+    }
+
+    fn draw_quit_confirm(&self, out: &mut impl Write) -> io::Result<()> {
+        let popup = self.quit_popup();
+        self.draw_colored_popup(out, &popup)
+    }
+
+    // Returns the pause screen's title and lines, and how many of them at
+    // the top can be picked
+    fn pause_lines(&self, menu: &PauseMenu) -> (&'static str, Vec<String>, usize) {
+        let mut lines = Vec::new();
+        let hints = if menu.showing_slots {
+            for (slot, saved) in menu.slots.iter().enumerate() {
+                let marker = if slot == menu.selected { "▶" } else { " " };
+                let contents = match saved {
+                    Some(saved) => tr!(
+                        "{} rounds, bankroll {} ({})",
+                        saved.session.rounds,
+                        locale::money(saved.bankroll),
+                        locale::net(saved.session.net(saved.bankroll))
+                    ),
+                    None => String::from(tr!("Empty")),
+                };
+                lines.push(tr!("  {} Slot {}: {}", marker, slot + 1, contents));
+            }
+            tr!(
+                "  [{}] Save  │  [{}] Load  │  [{}] Back",
+                self.keys.save, self.keys.load, self.keys.menu
+            )
+        } else {
+            for (i, item) in PAUSE_ITEMS.iter().enumerate() {
+                let marker = if i == menu.item { "▶" } else { " " };
+                let label = match item {
+                    PauseItem::Resume => tr!("Resume"),
+                    PauseItem::Settings => tr!("Settings"),
+                    PauseItem::SavedGames => tr!("Saved games"),
+                    PauseItem::Quit => tr!("Quit"),
+                };
+                lines.push(format!("  {} {}", marker, label));
+            }
+            tr!("  [↑/↓] Select  │  [Enter] Choose  │  [{}] Resume", self.keys.menu)
+        };
+        let rows = lines.len();
+        lines.push(String::new());
+        lines.push(tr!(
+            "  {} rounds this session, bankroll {} ({})",
+            self.session.rounds,
+            locale::money(self.bankroll),
+            locale::net(self.session.net(self.bankroll))
+        ));
+        lines.push(format!("  {}", menu.message));
+        lines.push(hints);
+
+        let title = if menu.showing_slots { tr!(" SAVED GAMES ") } else { tr!(" PAUSED ") };
+        (title, lines, rows)
+    }
+
+    fn draw_pause_menu(&self, out: &mut impl Write, menu: &PauseMenu) -> io::Result<()> {
+        let (title, lines, rows) = self.pause_lines(menu);
+        *self.buttons.borrow_mut() = screen::draw_themed_box(out, self.size, title, &lines, rows, &self.palette)?;
+        Ok(())
+    }
+
+    // Returns the cards of a hand that have landed, leaving out any still
+    // being dealt
+    fn shown_hand(&self, seat: Seat, hand: &Hand) -> Hand {
+        let shown = self.dealing.as_ref().map_or(hand.len(), |dealing| dealing.shown(seat, hand.len()));
+        let mut cards = Hand::new();
+        for card in &hand.cards()[..shown] {
+            cards.add_card(*card);
+        }
+        cards
+    }
+
+    /// Returns the hole card part way through being turned over, if it is,
+    /// as lines to draw over its back in the dealer's row of cards `width`
+    /// wide. The card overlapping it is left alone.
+    fn turning_hole_card(&self, dealer_hand: &Hand, size: CardSize, width: usize) -> Option<Vec<String>> {
+        let progress = self.dealing.as_ref()?.flipping()?;
+        let hole_card = dealer_hand.cards().first()?;
+        let columns = match dealer_hand.len() {
+            1 => size.width(),
+            count => card_art::offset(count, 1, width, size).min(size.width()),
+        };
+        Some(card_art::flipping(hole_card, progress, columns, self.card_back, size, &self.palette))
+    }
+
+    /// Returns the card being dealt to a hand, if there is one, and where
+    /// it's got to on its way from the shoe to its place in the hand's row
+    /// of cards at `row`. It's None while it's face down.
+    fn moving_card(
+        &self,
+        seat: Seat,
+        size: CardSize,
+        row: (u16, u16),
+        shoe: (u16, u16),
+        width: usize,
+    ) -> Option<(Option<&Card>, (u16, u16))> {
+        let (moving_seat, index, progress) = self.dealing.as_ref()?.moving()?;
+        if moving_seat != seat {
+            return None;
+        }
+
+        let hand = match seat {
+            Seat::Dealer => &self.dealer_hand,
+            Seat::Player(i) => &self.player_hands[i].hand,
+        };
+        let face_down = seat == Seat::Dealer && index == 0 && self.hides_hole_card();
+        let card = (!face_down).then(|| &hand.cards()[index]);
+        let to = (row.0 + card_art::offset(index + 1, index, width, size) as u16, row.1);
+        let along = |from: u16, to: u16| (from as f32 + (to as f32 - from as f32) * progress).round() as u16;
+        Some((card, (along(shoe.0, to.0), along(shoe.1, to.1))))
+    }
+
+    /// Draws the card being dealt to a hand, if there is one, where
+    /// `moving_card` says it's got to.
+    fn draw_moving_card(
+        &self,
+        out: &mut impl Write,
+        seat: Seat,
+        size: CardSize,
+        row: (u16, u16),
+        shoe: (u16, u16),
+        width: usize,
+    ) -> io::Result<()> {
+        let Some((card, (x, y))) = self.moving_card(seat, size, row, shoe, width) else {
+            return Ok(());
+        };
+        for (i, card_line) in card_art::row(&[card], width, self.card_back, size, &self.palette).iter().enumerate() {
+            queue!(out, cursor::MoveTo(x, y + i as u16))?;
+            write!(out, "{}\r", card_line)?;
+        }
+        Ok(())
+    }
+
+    // Helper to lay out the bet's chips as tokens, one per denomination
+    // followed by a count when there's more than one, as many as fit on a
+    // row `inner_width` wide after its indent
+    fn chip_tokens(&self, inner_width: usize) -> Vec<(Chip, String, String)> {
+        let mut width = 2;
+        let mut tokens = Vec::new();
+        for (chip, count) in self.bet_chips.counts() {
+            let token = format!(" {} ", chip.value());
+            let count = if count > 1 { format!("×{} ", count) } else { String::from(" ") };
+            let token_width = token.len() + UnicodeWidthStr::width(count.as_str());
+            if width + token_width > inner_width {
+                break;
+            }
+            width += token_width;
+            tokens.push((chip, token, count));
+        }
+        tokens
+    }
+
+    /// Draws a table row with the bet's chips as coloured tokens.
+    fn draw_chips(&self, out: &mut impl Write, inner_width: usize) -> io::Result<()> {
+        let mut width = 2;
+        self.palette.write(out, "│  ", self.palette.border)?;
+        for (chip, token, count) in self.chip_tokens(inner_width) {
+            width += token.len() + UnicodeWidthStr::width(count.as_str());
+
+            // Without colors the chips stand out in reverse instead
+            if self.palette.chip_colors {
+                let (face, text) = chip.colors();
+                queue!(out, SetBackgroundColor(face), SetForegroundColor(text))?;
+            } else {
+                queue!(out, SetAttribute(Attribute::Reverse))?;
+            }
+            queue!(out, Print(token), SetAttribute(Attribute::Reset), self.palette.apply(), Print(count))?;
+        }
+        let padding = inner_width.saturating_sub(width);
+        write!(out, "{:padding$}", "")?;
+        self.palette.write(out, "│", self.palette.border)?;
+        write!(out, "\r")
+    }
+
+    // Helper to fit a popup's message and prompt inside a width, as longer
+    // translations can need: lines that don't fit are broken between the
+    // things they list, and then between words
+    fn popup_lines(&self, popup: &Popup, width: usize) -> (Vec<String>, Vec<String>) {
+        let fit = |line: &str| -> Vec<String> {
+            if theme::width(line) <= width {
+                return vec![line.to_string()];
+            }
+            let items: Vec<&str> = line.split("  │  ").collect();
+            let mut lines = Vec::new();
+            for line in self.wrap_items(&items, width + 2) {
+                let mut current = String::new();
+                for word in line.trim_start().split(' ') {
+                    if !current.is_empty() && theme::width(&current) + 1 + theme::width(word) > width {
+                        lines.push(std::mem::take(&mut current));
+                    } else if !current.is_empty() {
+                        current.push(' ');
+                    }
+                    current.push_str(word);
+                }
+                lines.push(current);
+            }
+            lines
+        };
+        (popup.message.lines().flat_map(fit).collect(), fit(&popup.prompt))
+    }
+
+    /// Returns where a popup with this many message lines has its top-left
+    /// corner, centred on the screen, and how wide it is: narrower in
+    /// terminals that can only fit the compact table.
+    fn popup_origin(&self, message_lines: usize) -> io::Result<(u16, u16, u16)> {
+        let (term_width, term_height) = self.size;
+        let popup_width = POPUP_WIDTH.min(term_width);
+        let popup_height = 6 + message_lines as u16;
+        Ok((
+            term_width.saturating_sub(popup_width) / 2,
+            term_height.saturating_sub(popup_height) / 2,
+            popup_width,
+        ))
+    }
+
+    // Draws a popup with its border and title in a color, such as the round
+    // result in the color of how the round went
+    fn draw_colored_popup(&self, out: &mut impl Write, popup: &Popup) -> io::Result<()> {
+        // Popup dimensions, growing with the number of message and prompt
+        // lines
+        let width = POPUP_WIDTH.min(self.size.0);
+        let (message_lines, prompt_lines) = self.popup_lines(popup, width as usize - 4);
+        let extra_lines = message_lines.len() + prompt_lines.len() - 1;
+        let popup_height = 6 + extra_lines as u16;
+        let (start_x, start_y, popup_width) = self.popup_origin(extra_lines)?;
+
+        // Draw shadow (optional, for depth effect)
+        for i in 0..popup_height {
+            queue!(out, cursor::MoveTo(start_x + 1, start_y + i + 1))?;
+            write!(out, "{}", " ".repeat(popup_width as usize))?;
+        }
+
+        // Draw popup box
+        queue!(out, SetForegroundColor(popup.color), cursor::MoveTo(start_x, start_y))?;
+        write!(out, "┌{}┐\r", "─".repeat(popup_width as usize - 2))?;
+
+        for i in 1..popup_height - 1 {
+            queue!(out, cursor::MoveTo(start_x, start_y + i))?;
+            write!(out, "│{}│\r", " ".repeat(popup_width as usize - 2))?;
+        }
+
+        queue!(out, cursor::MoveTo(start_x, start_y + popup_height - 1))?;
+        write!(out, "└{}┘\r", "─".repeat(popup_width as usize - 2))?;
+
+        // Draw title
+        queue!(out, cursor::MoveTo(start_x + 2, start_y + 1))?;
+        write!(out, "{}\r", popup.title)?;
+
+        // Draw separator
+        queue!(out, cursor::MoveTo(start_x, start_y + 2))?;
+        write!(out, "├{}┤\r", "─".repeat(popup_width as usize - 2))?;
+        queue!(out, SetForegroundColor(self.palette.text))?;
+
+        // Draw the message (centered)
+        for (i, message_line) in message_lines.iter().enumerate() {
+            let message_width = theme::width(message_line);
+            let message_x = start_x + ((popup_width as usize).saturating_sub(message_width) / 2) as u16;
+            queue!(out, cursor::MoveTo(message_x, start_y + 3 + i as u16))?;
+            write!(out, "{}\r", message_line)?;
+        }
+
+        // Draw prompt, and only the popup's own keys can be clicked while it
+        // covers the table
+        let mut buttons = self.buttons.borrow_mut();
+        buttons.clear();
+        let prompt_y = start_y + popup_height - 1 - prompt_lines.len() as u16;
+        for (i, prompt) in prompt_lines.iter().enumerate() {
+            let prompt_width = UnicodeWidthStr::width(prompt.as_str());
+            let prompt_x = start_x + ((popup_width as usize).saturating_sub(prompt_width) / 2) as u16;
+            queue!(out, cursor::MoveTo(prompt_x, prompt_y + i as u16))?;
+            write!(out, "{}\r", prompt)?;
+            buttons.extend(mouse::key_hints(prompt, prompt_x, prompt_y + i as u16));
+        }
+
+        Ok(())
+    }
+
+    fn handle_player_turn(&mut self, key: KeyCode) {
+        match key {
+            _ if self.keys.hit.matches(key) => {
+                self.actions.push(String::from("Hit"));
+                let new_card = self.shoe.deal();
+                let hand = &mut self.player_hands[self.active_hand];
+                hand.add_card(new_card);
+                let total = hand.hand.total();
+                self.log(Some(self.active_hand), tr!("You hit: {} (total {})", new_card, total));
+            }
+            _ if self.keys.stand.matches(key) => {
+                self.actions.push(String::from("Stand"));
+                let hand = &mut self.player_hands[self.active_hand];
+                hand.status = HandStatus::Stood;
+                let total = hand.hand.total();
+                self.log(Some(self.active_hand), tr!("You stand on {}", total));
+            }
+            _ if self.keys.double.matches(key) && self.can_double() => {
+                self.actions.push(String::from("Double"));
+                let player_hand = &mut self.player_hands[self.active_hand];
+                self.bankroll -= player_hand.bet;
+                player_hand.bet *= 2;
+
+                // Doubling gets exactly one more card, then the hand stands
+                let new_card = self.shoe.deal();
+                player_hand.add_card(new_card);
+                if player_hand.status == HandStatus::Active {
+                    player_hand.status = HandStatus::Stood;
+                }
+                let total = player_hand.hand.total();
+                self.log(Some(self.active_hand), tr!("You double: {} (total {})", new_card, total));
+            }
+            _ if self.keys.surrender.matches(key) && self.can_surrender() => {
+                self.actions.push(String::from("Surrender"));
+                self.surrender(self.active_hand);
+                self.log(Some(self.active_hand), String::from(tr!("You surrender")));
+            }
+            _ if self.keys.split.matches(key) && self.can_split() => {
+                self.actions.push(String::from("Split"));
+                let player_hand = &mut self.player_hands[self.active_hand];
+                self.bankroll -= player_hand.bet;
+
+                let mut split_hand = PlayerHand::new(player_hand.bet, player_hand.spot);
+                split_hand.hand = player_hand.hand.split();
+
+                // Each half of the pair is dealt a second card
+                player_hand.add_card(self.shoe.deal());
+                split_hand.add_card(self.shoe.deal());
+
+                if split_hand.hand.cards()[0].rank == Rank::Ace {
+                    let can_hit = self.rules.hit_split_aces;
+                    let ten_is_blackjack = self.rules.split_aces_blackjack;
+                    player_hand.close_split_ace(can_hit, ten_is_blackjack);
+                    split_hand.close_split_ace(can_hit, ten_is_blackjack);
+                }
+                let split = tr!("You split: {} and {}", player_hand.hand.display_str(), split_hand.hand.display_str());
+                self.player_hands.insert(self.active_hand + 1, split_hand);
+                self.log(None, split);
+            }
+            _ => {}
+        }
+        if self.player_hands[self.active_hand].status == HandStatus::Busted {
+            let total = self.player_hands[self.active_hand].hand.total();
+            self.log(Some(self.active_hand), tr!("You bust with {}", total));
+        }
+
+        // A hand that reaches the Charlie card count without busting wins outright
+        let player_hand = &mut self.player_hands[self.active_hand];
+        if let Some(cards) = self.rules.charlie
+            && player_hand.status != HandStatus::Busted
+            && player_hand.hand.len() >= cards
+        {
+            player_hand.status = HandStatus::Charlie;
+        }
+
+        if self.player_hands[self.active_hand].status != HandStatus::Active {
+            self.finish_hand();
+        }
+    }
+
+    /// Doubling is only allowed on the first two cards, after a split only
+    /// if the table allows it, and if the bankroll can cover a second bet of
+    /// the same size.
+    fn can_double(&self) -> bool {
+        let player_hand = &self.player_hands[self.active_hand];
+        let split = self.hands_in_spot(player_hand.spot) > 1;
+        player_hand.hand.len() == 2
+            && (!split || self.rules.double_after_split)
+            && player_hand.bet > 0
+            && self.bankroll >= player_hand.bet
+    }
+
+    /// Splitting (and re-splitting) is allowed on a pair up to the table's
+    /// hand limit per spot, if the bankroll can cover the bet for the new hand.
+    fn can_split(&self) -> bool {
+        let player_hand = &self.player_hands[self.active_hand];
+        self.hands_in_spot(player_hand.spot) < self.rules.max_hands
+            && player_hand.hand.is_pair()
+            && player_hand.bet > 0
+            && self.bankroll >= player_hand.bet
+    }
+
+    fn hands_in_spot(&self, spot: usize) -> usize {
+        self.player_hands.iter().filter(|h| h.spot == spot).count()
+    }
+
+    /// Surrender is only allowed on the first two cards of an unsplit hand.
+    fn can_surrender(&self) -> bool {
+        // Without a hole card there's no peek, so only early surrender makes sense
+        let allowed = if self.rules.no_hole_card {
+            self.rules.early_surrender
+        } else {
+            self.rules.late_surrender || self.rules.early_surrender
+        };
+        let player_hand = &self.player_hands[self.active_hand];
+        allowed && self.hands_in_spot(player_hand.spot) == 1 && player_hand.hand.len() == 2
+    }
+
+    /// Gives up a hand and gets half its bet back. Late surrender is only
+    /// possible once the dealer's peek has ruled out a blackjack.
+    fn surrender(&mut self, index: usize) {
+        let player_hand = &mut self.player_hands[index];
+        player_hand.status = HandStatus::Surrendered;
+        self.bankroll += player_hand.bet / 2;
+    }
+
+    /// Early surrender gives up every hand that isn't a natural before the
+    /// dealer checks the hole card.
+    fn surrender_early(&mut self) {
+        self.actions.push(String::from("Early surrender"));
+        self.log(None, String::from(tr!("You surrender early")));
+        for i in 0..self.player_hands.len() {
+            if !self.player_hands[i].hand.is_blackjack() {
+                self.surrender(i);
+            }
+        }
+        self.check_naturals();
+    }
+
+    /// Moves play on to the next hand that is still active. Once every hand
+    /// is done, either the dealer plays or, if all hands busted, the round ends.
+    fn finish_hand(&mut self) {
+        if let Some(next) = self
+            .player_hands
+            .iter()
+            .position(|h| h.status == HandStatus::Active)
+        {
+            self.active_hand = next;
+            self.state = GameState::PlayerTurn;
+            return;
+        }
+
+        if self.player_hands.iter().all(|h| h.status == HandStatus::Busted) {
+            self.round_outcomes = vec![HandOutcome::Bust; self.player_hands.len()];
+            self.round_result = if self.player_hands.len() == 1 {
+                String::from(tr!("BUST! You lose this round."))
+            } else {
+                String::from(tr!("BUST! You lose all hands."))
+            };
+            self.end_round();
+        } else if self.player_hands.iter().all(|h| h.status != HandStatus::Stood) {
+            // Nothing is left for the dealer to play against
+            self.settle_hands();
+        } else {
+            self.state = GameState::DealerTurn;
+        }
+    }
+
+    fn handle_insurance_offer(&mut self, key: KeyCode) {
+        match key {
+            _ if self.keys.yes.matches(key) => {
+                self.actions.push(String::from("Insurance"));
+                self.insurance_bet = self.insurance_cost();
+                self.bankroll -= self.insurance_bet;
+                self.log(None, tr!("You take insurance for {}", locale::money(self.insurance_bet)));
+            }
+            _ if self.keys.no.matches(key) => self.log(None, String::from(tr!("You decline insurance"))),
+            // Early surrender is resolved before the dealer checks the hole card
+            _ if self.keys.surrender.matches(key) && self.rules.early_surrender => {
+                self.surrender_early();
+                return;
+            }
+            _ => return,
+        }
+
+        // Insurance pays 2:1 if the dealer's peek turns up a blackjack
+        if self.dealer_hand.is_blackjack() {
+            self.bankroll += self.insurance_bet * 3;
+        }
+        self.check_naturals();
+    }
+
+    fn handle_even_money_offer(&mut self, key: KeyCode) {
+        match key {
+            // Even money settles the blackjack 1:1 before the dealer checks
+            _ if self.keys.yes.matches(key) => {
+                let player_hand = &mut self.player_hands[0];
+                player_hand.status = HandStatus::Stood;
+                self.bankroll += player_hand.bet * 2;
+                self.actions.push(String::from("Even money"));
+                self.round_outcomes = vec![HandOutcome::Blackjack];
+                self.round_result = tr!("Even money! You win {}.", locale::money(player_hand.bet));
+                self.log(None, self.round_result.clone());
+                self.end_round();
+            }
+            _ if self.keys.no.matches(key) => {
+                self.log(None, String::from(tr!("You decline even money")));
+                self.check_naturals();
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_surrender_offer(&mut self, key: KeyCode) {
+        match key {
+            _ if self.keys.surrender.matches(key) => self.surrender_early(),
+            _ if self.keys.no.matches(key) => self.check_naturals(),
+            _ => {}
+        }
+    }
+
+    fn resolve_dealer_turn(&mut self) {
+        if let (false, Some(hole_card)) = (self.rules.no_hole_card, self.dealer_hand.cards().first()) {
+            let event = tr!("Dealer turns over {} (total {})", hole_card, self.dealer_hand.total());
+            self.log(None, event);
+        }
+        self.play_dealer_hand();
+        let event = match self.dealer_hand.value() {
+            22.. => tr!("Dealer busts with {}", self.dealer_hand.value()),
+            total => tr!("Dealer stands on {}", total),
+        };
+        self.log(None, event);
+        self.settle_hands();
+    }
+
+    fn play_dealer_hand(&mut self) {
+        // Dealer plays, hitting soft 17 only under H17 rules
+        while self.dealer_hand.value() < 17
+            || (self.dealer_hand.value() == 17 && self.dealer_hand.is_soft() && self.rules.dealer_hits_soft_17)
+        {
+            let new_card = self.shoe.deal();
+            self.dealer_hand.add_card(new_card);
+            self.log(None, tr!("Dealer draws {} (total {})", new_card, self.dealer_hand.total()));
+        }
+    }
+
+    fn settle_hands(&mut self) {
+        // Without a hole card, naturals wait for the dealer's second card
+        let any_natural = self.player_hands.iter().any(|h| h.status == HandStatus::Blackjack);
+        if self.rules.no_hole_card && any_natural && self.dealer_hand.len() == 1 {
+            let new_card = self.shoe.deal();
+            self.dealer_hand.add_card(new_card);
+            self.log(None, tr!("Dealer draws {} (total {})", new_card, self.dealer_hand.total()));
+        }
+
+        // Determine winner for each hand
+        let dealer_score = self.dealer_hand.value();
+        let dealer_blackjack = self.dealer_hand.is_blackjack();
+        let mut results = Vec::with_capacity(self.player_hands.len());
+
+        // Without a hole card, a dealer blackjack is only found now. Under
+        // OBO rules the extra money from doubles and splits is returned.
+        if self.rules.no_hole_card && self.rules.original_bets_only && dealer_blackjack {
+            self.bankroll += original_bets_refund(&self.player_hands, self.last_bet);
+        }
+
+        for player_hand in &self.player_hands {
+            let player_score = player_hand.hand.value();
+
+            let (outcome, result) = if player_hand.status == HandStatus::Surrendered {
+                (HandOutcome::Surrender, tr!("You surrendered. {} returned.", locale::money(player_hand.bet / 2)))
+            } else if player_hand.status == HandStatus::Blackjack && dealer_blackjack {
+                self.bankroll += player_hand.bet;
+                (HandOutcome::Push, String::from(tr!("Push! Both have blackjack.")))
+            } else if player_hand.status == HandStatus::Blackjack {
+                let winnings = self.rules.blackjack_payout.winnings(player_hand.bet);
+                self.bankroll += player_hand.bet + winnings;
+                (HandOutcome::Blackjack, tr!("Blackjack! You win {}.", locale::money(winnings)))
+            } else if player_hand.status == HandStatus::Charlie {
+                self.bankroll += player_hand.bet * 2;
+                let cards = player_hand.hand.len();
+                (HandOutcome::Charlie, tr!("{}-card Charlie! You win!", cards))
+            } else if player_score > 21 {
+                (HandOutcome::Bust, String::from(tr!("BUST! You lose this round.")))
+            } else if dealer_blackjack {
+                // A dealer natural beats any player 21 made with more cards
+                let result = if self.insurance_bet > 0 {
+                    tr!("Dealer blackjack! Insurance pays {}.", locale::money(self.insurance_bet * 2))
+                } else {
+                    String::from(tr!("Dealer blackjack. You lose."))
+                };
+                (HandOutcome::Lose, result)
+            } else if dealer_score == 22 && self.rules.push_on_dealer_22 {
+                self.bankroll += player_hand.bet;
+                (HandOutcome::Push, String::from(tr!("Dealer 22 pushes.")))
+            } else if dealer_score > 21 {
+                self.bankroll += player_hand.bet * 2;
+                (HandOutcome::Win, String::from(tr!("Dealer busts! You win!")))
+            } else if player_score > dealer_score {
+                self.bankroll += player_hand.bet * 2;
+                (HandOutcome::Win, tr!("You win! ({} vs {})", player_score, dealer_score))
+            } else if player_score < dealer_score {
+                (HandOutcome::Lose, tr!("You lose. ({} vs {})", player_score, dealer_score))
+            } else {
+                self.bankroll += player_hand.bet;
+                (HandOutcome::Push, tr!("Push! It's a tie at {}", player_score))
+            };
+            results.push((outcome, result));
+        }
+        self.round_outcomes = results.iter().map(|&(outcome, _)| outcome).collect();
+        for (i, (_, result)) in results.iter().enumerate() {
+            self.log(Some(i), result.clone());
+        }
+
+        // A single hand keeps the full message, several hands get a summary
+        self.round_result = match results.as_slice() {
+            [(_, result)] => result.clone(),
+            _ => results
+                .iter()
+                .enumerate()
+                .map(|(i, (outcome, _))| tr!("H{}: {}", i + 1, outcome.name()))
+                .collect::<Vec<String>>()
+                .join("  "),
+        };
+
+        self.end_round();
+    }
+
+    /// Closes out the round once the main bet is settled, paying any side
+    /// bets that are still riding.
+    fn end_round(&mut self) {
+        // The Buster bet needs the dealer's hand played out even when the
+        // main bet was settled without it
+        if self.placed_side_bets.contains(&SideBet::Buster) {
+            self.play_dealer_hand();
+        }
+
+        let round = RoundCards {
+            player: &self.initial_cards,
+            dealer: &self.dealer_hand,
+            up_card: self.up_card(),
+            decks: self.rules.decks,
+        };
+        for &side_bet in &self.placed_side_bets {
+            let odds = side_bet.odds(&round);
+            if let Some(odds) = odds {
+                self.bankroll += SIDE_BET_STAKE + odds.winnings(SIDE_BET_STAKE);
+            }
+            self.side_bet_results.push((side_bet, odds));
+        }
+
+        let side_bet_events: Vec<String> = self
+            .side_bet_results
+            .iter()
+            .map(|(side_bet, odds)| match odds {
+                Some(odds) => tr!("{} pays {}", side_bet.name(), locale::money(odds.winnings(SIDE_BET_STAKE))),
+                None => tr!("{} loses", side_bet.name()),
+            })
+            .collect();
+        for event in side_bet_events {
+            self.log(None, event);
+        }
+        let round_net = self.bankroll as i64 - self.round_start_bankroll as i64;
+        self.session.record_round(self.bankroll, round_net);
+        self.log(None, tr!("Round over: {}", locale::net(round_net)));
+        let round = RoundRecord {
+            dealer: &self.dealer_hand,
+            up_card: self.up_card(),
+            hands: &self.player_hands,
+            outcomes: &self.round_outcomes,
+            actions: &self.actions,
+            net: round_net,
+        };
+        let saved = self
+            .history
+            .record_round(&round)
+            .and_then(|()| self.history.record_session(&self.session, self.bankroll));
+        if let Err(err) = saved {
+            self.round_result.push_str(&tr!("\nRound not saved: {}", err));
+        }
+        match self.session.limit_reached(self.bankroll) {
+            Some(limit) => self.state = GameState::SessionLimitReached(limit),
+            None => self.after_round(),
+        }
+    }
+
+    /// Waits for the next round, or offers a rebuy if the bankroll no longer
+    /// covers the table minimum.
+    fn after_round(&mut self) {
+        self.state = if self.bankroll < self.rules.min_bet {
+            GameState::Rebuy
+        } else {
+            GameState::RoundEnd
+        };
+    }
+
+    /// Writes the session's hands to a CSV file in the working directory.
+    fn export_csv(&mut self) {
+        let path = self.history.export_name("csv");
+        self.notice = match self.history.export_csv(Path::new(&path)) {
+            Ok(hands) => tr!("Exported {} hands to {}", hands, path),
+            Err(err) => tr!("Export failed: {}", err),
+        };
+    }
+
+    /// Writes the session and lifetime stats to a JSON file in the working
+    /// directory.
+    fn export_json(&mut self) {
+        let path = self.history.export_name("json");
+        let result = self.history.export_json().and_then(|json| std::fs::write(&path, json));
+        self.notice = match result {
+            Ok(()) => tr!("Exported stats to {}", path),
+            Err(err) => tr!("Export failed: {}", err),
+        };
+    }
+
+    /// Switches a side bet on or off for the following rounds.
+    fn toggle_side_bet(&mut self, side_bet: SideBet) {
+        if let Some(i) = self.side_bets.iter().position(|&s| s == side_bet) {
+            self.side_bets.remove(i);
+        } else {
+            self.side_bets.push(side_bet);
+        }
+    }
+
+    fn handle_input(&mut self, key: KeyCode) -> bool {
+        // The settings take every key, since any of them can be rebound
+        if self.settings.is_some() {
+            self.handle_settings(key);
+            return true;
+        }
+
+        // Any key but scrolling closes the help or the log
+        if let Some(scroll) = self.help_scroll {
+            self.help_scroll = self.scroll_page(scroll, key, self.help_lines().len());
+            return true;
+        }
+        if let Some(scroll) = self.log_scroll {
+            self.log_scroll = self.scroll_page(scroll, key, self.log_page().len());
+            return true;
+        }
+
+        if self.confirm_quit {
+            match key {
+                _ if self.keys.yes.matches(key) || self.keys.quit.matches(key) => {
+                    self.state = GameState::GameOver;
+                    return false;
+                }
+                _ if self.keys.no.matches(key) || self.keys.menu.matches(key) => self.confirm_quit = false,
+                _ => {}
+            }
+            return true;
+        }
+
+        // Nothing's at stake in a replay or before a saved game is resumed,
+        // so those quit straight away
+        let nothing_at_stake = matches!(self.state, GameState::Replay | GameState::ResumeOffer);
+        match key {
+            _ if self.keys.quit.matches(key) && !nothing_at_stake => {
+                self.confirm_quit = true;
+                return true;
+            }
+            _ if (self.keys.quit.matches(key) || self.keys.menu.matches(key)) && nothing_at_stake => {
+                self.state = GameState::GameOver;
+                return false;
+            }
+            _ => {}
+        }
+
+        if let GameState::ResumeOffer = self.state {
+            self.handle_resume_offer(key);
+            return true;
+        }
+
+        if let GameState::Replay = self.state {
+            if let Some(replay) = &mut self.replay {
+                let keys = &self.keys;
+                if keys.moves_left(key) || keys.moves_up(key) {
+                    replay.back();
+                } else if keys.moves_right(key) || keys.moves_down(key) || key == KeyCode::Char(' ') {
+                    replay.forward();
+                }
+            }
+            self.show_frame();
+            return true;
+        }
+
+        // The menu key opens and closes the pause menu
+        if self.pause_menu.is_some() {
+            self.handle_pause_menu(key);
+            return true;
+        }
+        if self.keys.opens_help(key) {
+            self.help_scroll = Some(0);
+            return true;
+        }
+        if self.keys.menu.matches(key) {
+            self.stats_tab = None;
+            self.history_view = None;
+            self.pause_menu = Some(PauseMenu::open(&self.profile));
+            return true;
+        }
+
+        // The stats screen can be opened from anywhere. Tab switches between
+        // this session and lifetime totals, any other key closes it.
+        if let Some(tab) = self.stats_tab {
+            self.stats_tab = match (key, tab) {
+                (KeyCode::Tab, Scope::Session) => Some(Scope::Lifetime),
+                (KeyCode::Tab, Scope::Lifetime) => Some(Scope::Session),
+                _ if self.keys.export_json.matches(key) => {
+                    self.export_json();
+                    Some(tab)
+                }
+                _ => None,
+            };
+            return true;
+        }
+        // So can the hand history, paging between rounds with the arrow keys
+        if let Some(back) = self.history_view {
+            let rounds = self.history.session_rounds().unwrap_or(0);
+            let keys = &self.keys;
+            let older = keys.moves_left(key) || keys.moves_up(key);
+            let newer = keys.moves_right(key) || keys.moves_down(key);
+            self.history_view = match key {
+                _ if older && back + 1 < rounds => Some(back + 1),
+                _ if newer && back > 0 => Some(back - 1),
+                _ if older || newer => Some(back),
+                _ => None,
+            };
+            return true;
+        }
+        if self.keys.history.matches(key) {
+            self.history_view = Some(0);
+            return true;
+        }
+        // The log opens at its newest events
+        if self.keys.log.matches(key) {
+            self.log_scroll = Some(self.log_page().len().saturating_sub(self.page_height()));
+            return true;
+        }
+        if self.keys.stats.matches(key) {
+            self.notice = String::new();
+            self.stats_tab = Some(Scope::Session);
+            return true;
+        }
+        if self.keys.discards.matches(key) {
+            self.show_discards = !self.show_discards;
+            return true;
+        }
+
+        match self.state {
+            GameState::Betting => {
+                self.handle_betting(key);
+            }
+            GameState::CountQuiz => {
+                self.handle_count_quiz(key);
+            }
+            GameState::EvenMoneyOffer => {
+                self.handle_even_money_offer(key);
+            }
+            GameState::InsuranceOffer => {
+                self.handle_insurance_offer(key);
+            }
+            GameState::SurrenderOffer => {
+                self.handle_surrender_offer(key);
+            }
+            GameState::PlayerTurn => {
+                self.handle_player_turn(key);
+                if matches!(self.state, GameState::DealerTurn) {
+                    self.resolve_dealer_turn();
+                }
+            }
+            GameState::SessionLimitReached(limit) => match key {
+                _ if self.keys.keep_playing.matches(key) => {
+                    self.session.clear_limit(limit);
+                    self.after_round();
+                }
+                _ if self.keys.cash_out.matches(key) => {
+                    self.state = GameState::GameOver;
+                    return false;
+                }
+                _ => {}
+            },
+            GameState::Rebuy if self.keys.rebuy.matches(key) => {
+                self.bankroll = self.session.rebuy(self.bankroll);
+                self.start_betting();
+            }
+            GameState::RoundEnd => match key {
+                _ if self.keys.one_spot.matches(key) => self.spots = 1,
+                _ if self.keys.two_spots.matches(key) => self.spots = 2,
+                _ if self.keys.three_spots.matches(key) => self.spots = 3,
+                _ if self.keys.new_round.matches(key) => self.start_betting(),
+                _ if self.keys.rebet.matches(key) => self.rebet(),
+                _ if self.keys.lucky_ladies.matches(key) => self.toggle_side_bet(SideBet::LuckyLadies),
+                _ if self.keys.match_the_dealer.matches(key) => self.toggle_side_bet(SideBet::MatchTheDealer),
+                _ if self.keys.royal_match.matches(key) => self.toggle_side_bet(SideBet::RoyalMatch),
+                _ if self.keys.buster.matches(key) => self.toggle_side_bet(SideBet::Buster),
+                _ if self.keys.export_csv.matches(key) => self.export_csv(),
+                _ => {}
+            },
+            _ => {}
+        }
+
+        true
+    }
+
+    // Helper to ask for a bigger terminal, centered in the one there is
+    fn draw_too_small(&self, out: &mut impl Write, term_width: u16, term_height: u16) -> io::Result<()> {
+        let lines = [
+            String::from(tr!("The terminal is too small.")),
+            tr!("Make it at least {}×{}", MIN_WIDTH, MIN_HEIGHT),
+            tr!("(it's {}×{})", term_width, term_height),
+        ];
+        let start_y = term_height.saturating_sub(lines.len() as u16) / 2;
+        for (i, line) in lines.iter().enumerate() {
+            let x = term_width.saturating_sub(UnicodeWidthStr::width(line.as_str()) as u16) / 2;
+            queue!(out, cursor::MoveTo(x, start_y + i as u16), Print(line))?;
+        }
+        Ok(())
+    }
+
+    /// Draws the screen as it stands for a terminal `width` by `height`,
+    /// returning it as text rather than writing it to the terminal.
+    fn render_text(&mut self, width: u16, height: u16) -> io::Result<String> {
+        self.size = (width, height);
+        self.palette.emoji_suits = self.emoji_suits;
+        let mut frame = Vec::new();
+        self.render(&mut frame)?;
+        Ok(buffer::text(&frame, width, height))
+    }
+
+    // Whether a key does what it's for while cards are being dealt, rather
+    // than only landing them
+    fn interrupts_dealing(&self, code: KeyCode) -> bool {
+        self.keys.quit.matches(code) || self.keys.menu.matches(code) || self.keys.opens_help(code)
+    }
+
+    // Moves the screen on by a frame, returning whether there's anything
+    // new to draw. Only cards being dealt move by themselves.
+    fn tick(&mut self) -> bool {
+        let Some(dealing) = &mut self.dealing else {
+            return false;
+        };
+        if !dealing.advance() {
+            self.dealing = None;
+        }
+        true
+    }
+
+    // Draws the next frame, to the terminal and to the cast file if one is
+    // being recorded. Only what's changed since the last one is written,
+    // unless the terminal's been resized, so nothing flickers.
+    fn draw(&mut self, backend: &mut impl Backend) -> io::Result<()> {
+        self.size = backend.size()?;
+        // Cards being dealt are told once they've all landed
+        if self.narrator.is_some() && self.dealing.is_none() {
+            let lines = self.narration();
+            if let Some(narrator) = &mut self.narrator {
+                narrator.tell(lines, backend)?;
+            }
+        }
+        if self.narrator.as_ref().is_some_and(Narrator::on_terminal) {
+            return Ok(());
+        }
+        self.palette.emoji_suits = self.emoji_suits && backend.wide_emoji();
+        #[cfg(feature = "ratatui")]
+        if self.draw_tui(backend)? {
+            return Ok(());
+        }
+
+        let mut frame = self.screen.take_frame();
+        self.render(&mut frame)?;
+        let (width, height) = self.size;
+        let output = self.screen.update(frame, width, height)?;
+        write_frame(backend, self.cast.as_mut(), output)
+    }
+
+    fn run(&mut self, backend: &mut impl Backend) -> io::Result<()> {
+        backend.enter()?;
+        self.draw(backend)?;
+
+        // The loop ticks at a steady rate whether or not keys are pressed,
+        // so the screen can move on by itself in between
+        let mut next_tick = Instant::now() + FRAME_TIME;
+        let mut interrupted = false;
+        loop {
+            // Being asked to stop from outside leaves the game saved, as
+            // if the terminal had been closed
+            if backend.interrupted() {
+                interrupted = true;
+                break;
+            }
+            let now = Instant::now();
+            if now >= next_tick {
+                // Ticks that have fallen behind are skipped rather than
+                // run back to back
+                next_tick += FRAME_TIME;
+                if next_tick <= now {
+                    next_tick = now + FRAME_TIME;
+                }
+                if self.tick() {
+                    self.draw(backend)?;
+                }
+                continue;
+            }
+            if !backend.poll(next_tick - now)? {
+                continue;
+            }
+
+            // Ctrl+C stops the game the same way as a signal
+            let event = match backend.read() {
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {
+                    interrupted = true;
+                    break;
+                }
+                event => event?,
+            };
+
+            // A click on a key hint counts as pressing that key
+            let code = match event {
+                Event::Key(KeyEvent { code, .. }) => code,
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) => {
+                    let click = mouse::clicked(&self.buttons.borrow(), column, row);
+                    match click {
+                        Some(Click::Key(code)) => code,
+                        // Clicking a pause screen choice that's already
+                        // picked chooses it
+                        Some(Click::Select(row)) => {
+                            let again = match (&mut self.settings, &mut self.pause_menu) {
+                                (Some(settings), _) => {
+                                    settings.select(row);
+                                    false
+                                }
+                                (None, Some(menu)) => menu.select(row) && !menu.showing_slots,
+                                _ => false,
+                            };
+                            if !again {
+                                self.draw(backend)?;
+                                continue;
+                            }
+                            KeyCode::Enter
+                        }
+                        None => continue,
+                    }
+                }
+                // The layout is worked out afresh for the new size, and
+                // the whole screen drawn again
+                Event::Resize(..) => {
+                    self.screen.forget();
+                    #[cfg(feature = "ratatui")]
+                    {
+                        self.tui = None;
+                    }
+                    self.draw(backend)?;
+                    continue;
+                }
+                _ => continue,
+            };
+
+            // A key pressed while cards are being dealt lands them all.
+            // The quit, pause and help keys then go on to do what they're for.
+            if self.dealing.take().is_some() && !self.interrupts_dealing(code) {
+                self.draw(backend)?;
+                continue;
+            }
+
+            let before = (self.shoe.remaining(), self.table_cards(), self.hides_hole_card());
+            self.events_before = self.events.count();
+            if !self.handle_input(code) {
+                break;
+            }
+            if self.replay.is_none() {
+                self.dealing = self.dealt_since(before);
+            }
+            self.autosave();
+            if let Some(frame) = self.frame()
+                && let Some(recorder) = &mut self.recorder
+            {
+                recorder.record(frame)?;
+            }
+            self.draw(backend)?;
+        }
+
+        // A game quit on purpose is over, so there's nothing to resume
+        if self.replay.is_none() && self.resume.is_none() && !self.practice && !interrupted {
+            SavedGame::delete(&self.profile, AUTOSAVE)?;
+        }
+
+        backend.leave()?;
+        // There may be no terminal left to say goodbye to
+        if interrupted {
+            return Ok(());
+        }
+        println!("\n{}", tr!("Thanks for playing!"));
+        if self.replay.is_none() {
+            let net = self.session.net(self.bankroll);
+            println!(
+                "{}",
+                tr!(
+                    "{} rounds played with {} rebuys, leaving with {} ({}).",
+                    self.session.rounds,
+                    self.session.rebuys,
+                    locale::money(self.bankroll),
+                    locale::net(net)
+                )
+            );
+        }
+
+        Ok(())
+    }
+}
+
+// Sums up a profile's stats over every session, a line at a time.
+fn lifetime_summary(stats: &Stats) -> String {
+    tr!(
+        "Sessions: {}  │  Rounds: {}  │  Hands: {}\n\
+         Won: {}  │  Lost: {}  │  Pushed: {}\n\
+         Blackjacks: {}  │  Win rate: {:.1}%\n\
+         Net: {}",
+        stats.sessions,
+        stats.rounds,
+        stats.hands.hands,
+        stats.hands.wins,
+        stats.hands.losses,
+        stats.hands.pushes,
+        stats.hands.blackjacks,
+        stats.win_rate(),
+        locale::net(stats.net)
+    )
+}
+
+// Reads the leaderboard shown on the profile screen.
+fn leaderboard() -> io::Result<Leaderboard> {
+    History::open(DEFAULT_PROFILE)?
+        .leaderboard(5, &Daily::today().date)
+        .map_err(io::Error::other)
+}
 
 fn main() -> io::Result<()> {
-    let mut game = GameUI::new();
-    game.run()
-}
\ No newline at end of file
+    let cli = Cli::parse();
+    backend::restore_on_panic();
+    let result = match cli.command {
+        Some(Command::Play(args)) => play(&mut terminal(&args)?, args),
+        Some(Command::Sim(args)) => simulate(args),
+        Some(Command::Stats(args)) => export_stats(args),
+        Some(Command::Frame(args)) => print_frame(args),
+        None if cli.play.replay.is_some() || narrates_here(&cli.play) => play(&mut terminal(&cli.play)?, cli.play),
+        None => app(&mut terminal(&cli.play)?, cli.play),
+    };
+    // Being asked to stop on a screen with nothing to save just ends
+    match result {
+        Err(err) if err.kind() == io::ErrorKind::Interrupted => Ok(()),
+        result => result,
+    }
+}
+
+// Where the app is, above the state of the table while a game is played.
+enum AppState {
+    Menu,
+    Playing(Start),
+    Done,
+}
+
+// How a game gets going.
+#[derive(Clone, Copy, PartialEq)]
+enum Start {
+    /// Straight from the command line, offering to resume an unfinished game.
+    Offer,
+    New,
+    Continue,
+    Practice,
+}
+
+// Plays rounds with basic strategy and prints what they came to.
+fn simulate(args: SimArgs) -> io::Result<()> {
+    let config = Config::load(&args.profile)?;
+    let rules = args.table.rules(&config);
+    rules.check().map_err(io::Error::other)?;
+    locale::set(config.locale, &config.currency);
+    println!("{}", tr!("Simulating {} rounds: {}", args.rounds, rules.summary().join(", ")));
+    for line in simulation(rules, args.rounds, args.seed) {
+        println!("{}", line);
+    }
+    Ok(())
+}
+
+// Simulates rounds at a table and describes the result.
+fn simulation(rules: Rules, rounds: u64, seed: Option<u64>) -> Vec<String> {
+    let result = Simulator::new(rules.clone(), seed).run(rounds);
+    vec![
+        tr!("Hands played: {}", result.hands),
+        tr!(
+            "Wins: {}  Losses: {}  Pushes: {}",
+            result.wins, result.losses, result.pushes
+        ),
+        tr!("Blackjacks: {}", result.blackjacks),
+        tr!(
+            "House edge: {:.2}% (estimated {:.2}%)",
+            result.house_edge(),
+            rules.house_edge()
+        ),
+    ]
+}
+
+// Writes a profile's stats out as JSON without starting a game.
+fn export_stats(args: StatsArgs) -> io::Result<()> {
+    let json = History::open(&args.profile)?.export_json()?;
+    if args.output == "-" {
+        println!("{}", json);
+        Ok(())
+    } else {
+        std::fs::write(args.output, json)
+    }
+}
+
+// Prints the table as text once the keys given have been pressed, for
+// other programs to show or tests to check. Nothing played is kept.
+fn print_frame(args: FrameArgs) -> io::Result<()> {
+    let config = Config::load(&args.profile)?;
+    locale::set(config.locale, &config.currency);
+    let rules = args.table.rules(&config);
+    rules.check().map_err(io::Error::other)?;
+    let mut history = History::scratch(&args.profile)?;
+    history.start_session(None)?;
+    let session = Session::new(STARTING_BANKROLL, STARTING_BANKROLL, None, None, config.push_policy);
+    let keys = KeyBindings::new(config.key_scheme, &config.keys).map_err(io::Error::other)?;
+    let mut game = GameUI::new(rules, session, history, &args.profile, keys);
+    game.apply_config(&config)?;
+    game.emoji_suits &= !args.ascii;
+    if let Some(seed) = args.seed {
+        game.shoe = Shoe::seeded(game.rules.decks, game.rules.penetration, seed);
+    }
+    // The help scrolls by the height of the frame
+    game.size = (args.width, args.height);
+    for key in args.keys {
+        if !game.handle_input(key.code()) {
+            break;
+        }
+    }
+    let text = game.render_text(args.width, args.height)?;
+    if args.ascii {
+        print!("{}", text.chars().map(backend::ascii).collect::<String>());
+    } else {
+        print!("{}", text);
+    }
+    Ok(())
+}
+
+// Opens the terminal to play in, drawing as the command line asks.
+fn terminal(args: &PlayArgs) -> io::Result<Crossterm> {
+    Crossterm::new(args.inline || narrates_here(args), args.ascii)
+}
+
+// Whether the game's told on the terminal in place of being drawn, which
+// leaves out the title, menus and profile screen and goes straight to the
+// table.
+fn narrates_here(args: &PlayArgs) -> bool {
+    args.narrate.as_deref() == Some("-")
+}
+
+// Picks the profile to play as: the one given on the command line, or one
+// chosen on the profile screen. None means the player quit instead.
+fn pick_profile(backend: &mut impl Backend, args: &PlayArgs) -> io::Result<Option<String>> {
+    match &args.profile {
+        Some(profile) => Ok(Some(profile.clone())),
+        None if args.replay.is_some() || narrates_here(args) => Ok(Some(String::from(DEFAULT_PROFILE))),
+        None => {
+            // The profile screen goes by the default profile's keys and
+            // language
+            let config = Config::load(DEFAULT_PROFILE)?;
+            locale::set(config.locale, &config.currency);
+            let keys = config.key_bindings()?;
+            ProfileScreen::new(leaderboard()?, keys)?.run(backend)
+        }
+    }
+}
+
+// The profile's keys, in the scheme from the command line if one was given.
+fn key_bindings(args: &PlayArgs, config: &Config) -> io::Result<KeyBindings> {
+    let scheme = args.keys.unwrap_or(config.key_scheme);
+    KeyBindings::new(scheme, &config.keys).map_err(io::Error::other)
+}
+
+// Goes straight to the table.
+fn play(backend: &mut impl Backend, args: PlayArgs) -> io::Result<()> {
+    match pick_profile(backend, &args)? {
+        Some(profile) => play_game(backend, &args, &profile, Start::Offer),
+        None => Ok(()),
+    }
+}
+
+// Shows the title, then runs the main menu, going back to it after each
+// game until the player quits.
+fn app(backend: &mut impl Backend, args: PlayArgs) -> io::Result<()> {
+    let config = Config::load(DEFAULT_PROFILE)?;
+    locale::set(config.locale, &config.currency);
+    title::show(backend)?;
+    let Some(profile) = pick_profile(backend, &args)? else {
+        return Ok(());
+    };
+    let mut menu = MainMenu::new(&profile, key_bindings(&args, &Config::load(&profile)?)?);
+    let mut state = AppState::Menu;
+    loop {
+        state = match state {
+            AppState::Menu => {
+                let config = Config::load(&profile)?;
+                locale::set(config.locale, &config.currency);
+                menu.bankroll = config.bankroll;
+                menu.can_continue = SavedGame::load(&profile, AUTOSAVE)?.is_some();
+                match menu.run(backend)? {
+                    MenuItem::NewGame => AppState::Playing(Start::New),
+                    MenuItem::Continue => AppState::Playing(Start::Continue),
+                    MenuItem::Practice => AppState::Playing(Start::Practice),
+                    MenuItem::Trainer => {
+                        let decks = args.table.rules(&config).decks;
+                        Trainer::new(&profile, &config, decks, menu.keys.clone()).run(backend)?;
+                        AppState::Menu
+                    }
+                    MenuItem::Simulation => {
+                        let rules = args.table.rules(&config);
+                        let mut lines = vec![String::from(tr!("Played 100000 rounds with basic strategy"))];
+                        lines.extend(simulation(rules, 100_000, None));
+                        menu.show(tr!("SIMULATION"), lines.iter().map(|l| format!("  {}", l)).collect());
+                        AppState::Menu
+                    }
+                    MenuItem::Settings => {
+                        let mut settings = Settings::new(config, menu.keys.clone());
+                        settings.run(backend, &profile)?;
+                        menu.keys = key_bindings(&args, &settings.config)?;
+                        AppState::Menu
+                    }
+                    MenuItem::Stats => {
+                        let lines = match History::open(&profile)?.stats(Scope::Lifetime) {
+                            Ok(stats) => lifetime_summary(&stats),
+                            Err(err) => tr!("Couldn't read the history:\n{}", err),
+                        };
+                        menu.show(tr!("LIFETIME STATS"), lines.lines().map(|l| format!("  {}", l)).collect());
+                        AppState::Menu
+                    }
+                    MenuItem::Quit => AppState::Done,
+                }
+            }
+            AppState::Playing(start) => {
+                play_game(backend, &args, &profile, start)?;
+                if backend.interrupted() {
+                    AppState::Done
+                } else {
+                    AppState::Menu
+                }
+            }
+            AppState::Done => return Ok(()),
+        };
+    }
+}
+
+fn play_game(backend: &mut impl Backend, args: &PlayArgs, profile: &str, start: Start) -> io::Result<()> {
+    let mut config = Config::load(profile)?;
+    locale::set(config.locale, &config.currency);
+    let practice = start == Start::Practice;
+
+    // Everyone plays the daily challenge at the same table
+    let daily = (args.daily && !practice).then(Daily::today);
+    let rules = if daily.is_some() {
+        Rules::default()
+    } else {
+        args.table.rules(&config)
+    };
+    rules.check().map_err(io::Error::other)?;
+    let push_policy = args.push_streak.unwrap_or(config.push_policy);
+    let keys = key_bindings(args, &config)?;
+
+    // Keep the rules as the custom preset for future sessions
+    if args.save_rules {
+        config.custom_rules = Some(rules.clone());
+        config.preset = Some(Preset::Custom);
+        config.save(profile)?;
+    }
+
+    // Practice rounds don't count towards the stats
+    let mut history = if practice {
+        History::scratch(profile)?
+    } else {
+        History::open(profile)?
+    };
+
+    // A profile that ran out of chips last time starts again from scratch,
+    // and the daily challenge and practice always start from the same bankroll
+    let bankroll = match config.bankroll {
+        Some(bankroll) if bankroll >= rules.min_bet && daily.is_none() && !practice => bankroll,
+        _ => STARTING_BANKROLL,
+    };
+    let session = Session::new(bankroll, STARTING_BANKROLL, args.stop_loss, args.win_goal, push_policy);
+
+    let cast = match &args.cast {
+        Some(path) => {
+            let (width, height) = backend.size()?;
+            Some(Cast::create(Path::new(path), width, height)?)
+        }
+        None => None,
+    };
+    let narrator = match args.narrate.as_deref() {
+        Some("-") => Some(Narrator::terminal()),
+        Some(path) => Some(Narrator::create(Path::new(path))?),
+        None => None,
+    };
+
+    // Playing back a replay doesn't start a session of its own
+    if let Some(path) = &args.replay {
+        let replay = Replay::load(Path::new(path))?;
+        let mut game = GameUI::new(rules, session, history, profile, keys);
+        game.cast = cast;
+        game.narrator = narrator;
+        game.apply_config(&config)?;
+        game.start_replay(replay);
+        return game.run(backend);
+    }
+
+    // A game that was interrupted can be resumed, in which case it carries
+    // on with its own session rather than starting a new one
+    let saved = match start {
+        Start::Offer | Start::Continue => SavedGame::load(profile, AUTOSAVE)?,
+        Start::New | Start::Practice => None,
+    };
+    if saved.is_none() {
+        history.start_session(daily.as_ref().map(|d| d.date.as_str()))?;
+    }
+    let mut game = GameUI::new(rules, session, history, profile, keys);
+    game.cast = cast;
+    game.narrator = narrator;
+    game.practice = practice;
+    game.apply_config(&config)?;
+    if let Some(daily) = daily {
+        game.shoe = Shoe::seeded(game.rules.decks, game.rules.penetration, daily.seed);
+        game.daily = Some(daily.date);
+    } else if let Some(seed) = args.seed {
+        game.shoe = Shoe::seeded(game.rules.decks, game.rules.penetration, seed);
+    }
+    match saved {
+        Some(saved) if start == Start::Continue => game.restore(saved),
+        Some(saved) => game.offer_resume(saved),
+        None => {}
+    }
+    if let Some(path) = &args.record {
+        game.recorder = Some(Recorder::create(Path::new(path))?);
+    }
+    game.run(backend)?;
+
+    // The profile keeps its bankroll for the next session. Daily challenge
+    // and practice chips are separate from it. The config's read afresh so
+    // settings changed during the game aren't lost.
+    if game.daily.is_some() || game.practice {
+        return Ok(());
+    }
+    let mut config = Config::load(profile)?;
+    config.bankroll = Some(game.bankroll);
+    config.save(profile)
+}
@@ -0,0 +1,200 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use std::io;
+
+use crate::backend::Backend;
+use crate::keys::KeyBindings;
+use crate::locale::{self, tr};
+use crate::mouse::{self, Button, Click};
+use crate::screen;
+
+// The choices on the main menu, in the order they're listed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum MenuItem {
+    NewGame,
+    Continue,
+    Practice,
+    Trainer,
+    Simulation,
+    Settings,
+    Stats,
+    Quit,
+}
+
+impl MenuItem {
+    const ALL: [MenuItem; 8] = [
+        MenuItem::NewGame,
+        MenuItem::Continue,
+        MenuItem::Practice,
+        MenuItem::Trainer,
+        MenuItem::Simulation,
+        MenuItem::Settings,
+        MenuItem::Stats,
+        MenuItem::Quit,
+    ];
+
+    fn label(self) -> &'static str {
+        match self {
+            MenuItem::NewGame => tr!("New Game"),
+            MenuItem::Continue => tr!("Continue"),
+            MenuItem::Practice => tr!("Practice"),
+            MenuItem::Trainer => tr!("Trainer"),
+            MenuItem::Simulation => tr!("Simulation"),
+            MenuItem::Settings => tr!("Settings"),
+            MenuItem::Stats => tr!("Stats"),
+            MenuItem::Quit => tr!("Quit"),
+        }
+    }
+
+    fn about(self) -> &'static str {
+        match self {
+            MenuItem::NewGame => tr!("Start a fresh session"),
+            MenuItem::Continue => tr!("Pick up where you left off"),
+            MenuItem::Practice => tr!("Play for free, nothing is kept"),
+            MenuItem::Trainer => tr!("Keep the count as cards flash by"),
+            MenuItem::Simulation => tr!("See what the table rules cost"),
+            MenuItem::Settings => tr!("The rules and keys you play with"),
+            MenuItem::Stats => tr!("Your results over every session"),
+            MenuItem::Quit => "",
+        }
+    }
+}
+
+// The screen a profile starts at, for picking what to do next.
+pub struct MainMenu {
+    profile: String,
+    /// The profile's chips, if it's played before.
+    pub bankroll: Option<u32>,
+    /// Whether there's an unfinished game to continue.
+    pub can_continue: bool,
+    selected: usize,
+    message: String,
+    // Text shown in place of the menu, such as the stats, until any key
+    page: Option<(String, Vec<String>)>,
+    pub keys: KeyBindings,
+    // Parts of the screen that can be clicked, as last drawn
+    buttons: Vec<Button>,
+}
+
+impl MainMenu {
+    pub fn new(profile: &str, keys: KeyBindings) -> Self {
+        MainMenu {
+            profile: profile.to_string(),
+            bankroll: None,
+            can_continue: false,
+            selected: 0,
+            message: String::new(),
+            page: None,
+            keys,
+            buttons: Vec::new(),
+        }
+    }
+
+    /// Shows a page of text over the menu the next time it's run, until
+    /// the player presses a key.
+    pub fn show(&mut self, title: &str, lines: Vec<String>) {
+        self.page = Some((format!(" {} ", title), lines));
+    }
+
+    /// Shows the menu until something is chosen.
+    pub fn run(&mut self, backend: &mut impl Backend) -> io::Result<MenuItem> {
+        backend.enter()?;
+
+        let chosen = loop {
+            self.render(backend)?;
+            let done = match backend.read()? {
+                Event::Key(KeyEvent { code, .. }) => self.handle_input(code),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) => self.handle_click(column, row),
+                _ => None,
+            };
+            if let Some(chosen) = done {
+                break chosen;
+            }
+        };
+
+        backend.leave()?;
+        Ok(chosen)
+    }
+
+    /// Returns the item chosen, if any.
+    fn handle_input(&mut self, key: KeyCode) -> Option<MenuItem> {
+        // Any key goes back from a page
+        if self.page.take().is_some() {
+            return None;
+        }
+
+        self.message = String::new();
+        match key {
+            _ if self.keys.moves_up(key) => self.selected = self.selected.saturating_sub(1),
+            _ if self.keys.moves_down(key) => {
+                self.selected = (self.selected + 1).min(MenuItem::ALL.len() - 1);
+            }
+            KeyCode::Enter => return self.choose(),
+            _ if self.keys.quit.matches(key) || self.keys.menu.matches(key) => return Some(MenuItem::Quit),
+            _ => {}
+        }
+        None
+    }
+
+    /// Clicking an item selects it, and clicking it again chooses it.
+    fn handle_click(&mut self, column: u16, row: u16) -> Option<MenuItem> {
+        if self.page.take().is_some() {
+            return None;
+        }
+        match mouse::clicked(&self.buttons, column, row)? {
+            Click::Key(code) => self.handle_input(code),
+            Click::Select(i) if i == self.selected => self.choose(),
+            Click::Select(i) => {
+                self.selected = i;
+                self.message = String::new();
+                None
+            }
+        }
+    }
+
+    fn choose(&mut self) -> Option<MenuItem> {
+        let item = MenuItem::ALL[self.selected];
+        if item == MenuItem::Continue && !self.can_continue {
+            self.message = String::from(tr!("There's no game to continue"));
+            return None;
+        }
+        Some(item)
+    }
+
+    fn render(&mut self, backend: &mut impl Backend) -> io::Result<()> {
+        let size = backend.size()?;
+        if let Some((title, lines)) = &self.page {
+            let mut lines = lines.clone();
+            lines.push(String::new());
+            lines.push(String::from(tr!("  Press any key to return")));
+            self.buttons = screen::draw_box(backend, size, title, &lines, 0)?;
+            return Ok(());
+        }
+
+        // The items take the first lines
+        let mut lines = Vec::new();
+        let width = MenuItem::ALL.iter().map(|item| item.label().chars().count() + 2).fold(14, usize::max);
+        for (i, item) in MenuItem::ALL.iter().enumerate() {
+            let marker = if i == self.selected { "▶" } else { " " };
+            let about = match item {
+                MenuItem::Continue if !self.can_continue => tr!("Nothing saved"),
+                _ => item.about(),
+            };
+            lines.push(format!("  {} {:<width$}{}", marker, item.label(), about));
+        }
+        lines.push(String::new());
+        let chips = self.bankroll.map_or(String::from(tr!("new profile")), |b| tr!("{} chips", locale::money(b)));
+        lines.push(tr!("  Playing as {} ({})", self.profile, chips));
+        lines.push(format!("  {}", self.message));
+        lines.push(tr!(
+            "  [↑/↓] Select  │  [Enter] Choose  │  [{}] Quit",
+            self.keys.quit
+        ));
+        self.buttons = screen::draw_box(backend, size, tr!(" MAIN MENU "), &lines, MenuItem::ALL.len())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,80 @@
+use crossterm::event::KeyCode;
+use unicode_width::UnicodeWidthChar;
+
+use crate::keys::Key;
+
+// What clicking on part of the screen does.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Click {
+    /// The same as pressing the key.
+    Key(KeyCode),
+    /// Picks an entry in a list, counting from the top.
+    Select(usize),
+}
+
+// A clickable stretch of one screen row.
+#[derive(Debug, Clone, Copy)]
+pub struct Button {
+    pub row: u16,
+    pub start: u16,
+    pub end: u16,
+    pub click: Click,
+}
+
+impl Button {
+    /// Makes a whole row clickable, such as a line in a list.
+    pub fn row(row: u16, start: u16, width: u16, click: Click) -> Self {
+        Button {
+            row,
+            start,
+            end: start + width,
+            click,
+        }
+    }
+}
+
+/// Returns what the buttons do when clicked at a spot, if any is there.
+pub fn clicked(buttons: &[Button], column: u16, row: u16) -> Option<Click> {
+    buttons
+        .iter()
+        .find(|b| b.row == row && (b.start..b.end).contains(&column))
+        .map(|b| b.click)
+}
+
+/// Finds the key hints in a line drawn at `x`, `y`, such as "[H] Hit" in
+/// the controls, and turns each into a button that presses its key. A hint
+/// runs from its bracket to the next hint or │ separator. Hints that don't
+/// name a single key, like "[1-3] Spots", aren't clickable.
+pub fn key_hints(line: &str, x: u16, y: u16) -> Vec<Button> {
+    let mut buttons = Vec::new();
+    // The hint being read: where it started, its key once the bracket
+    // closes, and where its text ends
+    let mut hint: Option<(u16, String, Option<Key>, u16)> = None;
+    let mut column = x;
+
+    for c in line.chars() {
+        let width = c.width().unwrap_or(0) as u16;
+        if c == '[' || c == '│' {
+            if let Some((start, _, Some(key), end)) = hint.take() {
+                buttons.push(Button { row: y, start, end, click: Click::Key(key.code()) });
+            }
+            if c == '[' {
+                hint = Some((column, String::new(), None, column + width));
+            }
+        } else if let Some((_, name, key, end)) = &mut hint {
+            match key {
+                None if c == ']' => *key = name.parse().ok(),
+                None => name.push(c),
+                Some(_) => {}
+            }
+            if c != ' ' {
+                *end = column + width;
+            }
+        }
+        column += width;
+    }
+    if let Some((start, _, Some(key), end)) = hint {
+        buttons.push(Button { row: y, start, end, click: Click::Key(key.code()) });
+    }
+    buttons
+}
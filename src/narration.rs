@@ -0,0 +1,108 @@
+use std::fs::File;
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::game::{Card, Hand};
+use crate::locale::tr;
+
+// Tells the game as it goes in lines of plain text, for a screen reader to
+// read out: the cards on the table, their totals, the result of each round
+// and the keys that can be pressed. Each telling is compared with the last,
+// so only what's changed is told again.
+pub struct Narrator {
+    // The file or pipe it's told to, or None for the terminal itself
+    file: Option<BufWriter<File>>,
+    told: Vec<String>,
+}
+
+impl Narrator {
+    /// Tells the game to a file or named pipe, alongside the table drawn as
+    /// usual.
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Narrator { file: Some(BufWriter::new(File::create(path)?)), told: Vec::new() })
+    }
+
+    /// Tells the game on the terminal, in place of drawing the table.
+    pub fn terminal() -> Self {
+        Narrator { file: None, told: Vec::new() }
+    }
+
+    /// Whether it's telling the game on the terminal.
+    pub fn on_terminal(&self) -> bool {
+        self.file.is_none()
+    }
+
+    /// Tells whichever of the lines weren't told last time, to the file or
+    /// else to `terminal`.
+    pub fn tell(&mut self, lines: Vec<String>, terminal: &mut impl Write) -> io::Result<()> {
+        let mut new = lines.iter().filter(|line| !self.told.contains(line)).peekable();
+        if new.peek().is_some() {
+            match &mut self.file {
+                Some(file) => {
+                    for line in new {
+                        writeln!(file, "{}", line)?;
+                    }
+                    // Flushed every time so a screen reader following it
+                    // hears each line as it happens
+                    file.flush()?;
+                }
+                None => {
+                    // Raw mode needs the carriage return as well
+                    for line in new {
+                        write!(terminal, "{}\r\n", line)?;
+                    }
+                    terminal.flush()?;
+                }
+            }
+        }
+        self.told = lines;
+        Ok(())
+    }
+}
+
+/// Returns text as it's told: without the codes that color it, and with
+/// the separators and selection markers drawn between things turned into
+/// punctuation or left out.
+pub fn plain(text: &str) -> String {
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        plain.push_str(&rest[..start]);
+        // A code runs from the escape to its closing letter
+        let code = &rest[start + 1..];
+        let end = code.find(|c: char| c.is_ascii_alphabetic()).map_or(code.len(), |i| i + 1);
+        rest = &code[end..];
+    }
+    plain.push_str(rest);
+    let words: Vec<&str> = plain
+        .split_whitespace()
+        .filter(|word| !matches!(*word, "▶" | "◀"))
+        .collect();
+    words.join(" ").replace(" │", ",")
+}
+
+/// Returns a box of lines as they're told: its title, then of the rows
+/// that can be picked only the one that's picked, then the rest.
+pub fn menu(title: &str, lines: &[String], rows: usize) -> Vec<String> {
+    let picked = lines[..rows].iter().filter(|line| line.contains('▶'));
+    let told = [title.to_string()].into_iter().chain(picked.cloned()).chain(lines[rows..].iter().cloned());
+    told.map(|line| plain(&line)).filter(|line| !line.is_empty()).collect()
+}
+
+/// A card by name, such as "king of spades".
+pub fn card(card: &Card) -> String {
+    tr!("{} of {}", card.rank.name(), card.suit.name())
+}
+
+/// A hand's cards and what they make, such as "ace of hearts, 6 of clubs:
+/// soft 17".
+pub fn hand(hand: &Hand) -> String {
+    let cards: Vec<String> = hand.cards().iter().map(card).collect();
+    let value = hand.total();
+    let total = match value.total {
+        22.. => tr!("{}, bust", value.total),
+        total if value.soft && total < 21 => tr!("soft {}", total),
+        total => total.to_string(),
+    };
+    format!("{}: {}", cards.join(", "), total)
+}
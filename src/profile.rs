@@ -0,0 +1,198 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use std::io;
+
+use crate::backend::Backend;
+use crate::config::Config;
+use crate::keys::KeyBindings;
+use crate::locale::{self, tr};
+use crate::mouse::{self, Button, Click};
+use crate::screen;
+use crate::stats::Leaderboard;
+
+// The startup screen for picking which player profile to play as, or
+// creating a new one.
+pub struct ProfileScreen {
+    /// Each profile's name and the bankroll it has left, if it's played before.
+    profiles: Vec<(String, Option<u32>)>,
+    selected: usize,
+    // Name being typed for a new profile, and why it was refused
+    new_name: Option<String>,
+    error: String,
+    leaderboard: Leaderboard,
+    show_leaderboard: bool,
+    keys: KeyBindings,
+    // Parts of the screen that can be clicked, as last drawn
+    buttons: Vec<Button>,
+}
+
+impl ProfileScreen {
+    pub fn new(leaderboard: Leaderboard, keys: KeyBindings) -> io::Result<Self> {
+        let mut profiles = Vec::new();
+        for name in Config::profiles()? {
+            let bankroll = Config::load(&name)?.bankroll;
+            profiles.push((name, bankroll));
+        }
+        Ok(ProfileScreen {
+            profiles,
+            selected: 0,
+            new_name: None,
+            error: String::new(),
+            leaderboard,
+            show_leaderboard: false,
+            keys,
+            buttons: Vec::new(),
+        })
+    }
+
+    /// Shows the screen until a profile is picked, returning its name, or
+    /// None if the player quit.
+    pub fn run(&mut self, backend: &mut impl Backend) -> io::Result<Option<String>> {
+        backend.enter()?;
+
+        let chosen = loop {
+            self.render(backend)?;
+            let done = match backend.read()? {
+                Event::Key(KeyEvent { code, .. }) => self.handle_input(code),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) => self.handle_click(column, row),
+                _ => None,
+            };
+            if let Some(chosen) = done {
+                break chosen;
+            }
+        };
+
+        backend.leave()?;
+        Ok(chosen)
+    }
+
+    /// Returns Some once the screen is done: the profile to play, or None to quit.
+    fn handle_input(&mut self, key: KeyCode) -> Option<Option<String>> {
+        if let Some(name) = &mut self.new_name {
+            match key {
+                KeyCode::Char(c) => name.push(c),
+                KeyCode::Backspace => {
+                    name.pop();
+                }
+                KeyCode::Esc => {
+                    self.new_name = None;
+                    self.error = String::new();
+                }
+                KeyCode::Enter => {
+                    let name = name.clone();
+                    if !Config::valid_profile_name(&name) {
+                        self.error = String::from(tr!("Use up to 20 letters, digits, - or _"));
+                    } else if self.profiles.iter().any(|(p, _)| *p == name) {
+                        self.error = tr!("'{}' already exists", name);
+                    } else {
+                        return Some(Some(name));
+                    }
+                }
+                _ => {}
+            }
+            return None;
+        }
+
+        // Any key goes back from the leaderboard
+        if self.show_leaderboard {
+            self.show_leaderboard = false;
+            return None;
+        }
+
+        match key {
+            _ if self.keys.moves_up(key) => self.selected = self.selected.saturating_sub(1),
+            _ if self.keys.moves_down(key) => {
+                self.selected = (self.selected + 1).min(self.profiles.len() - 1);
+            }
+            KeyCode::Enter => return Some(Some(self.profiles[self.selected].0.clone())),
+            KeyCode::Char('n') | KeyCode::Char('N') => self.new_name = Some(String::new()),
+            KeyCode::Char('l') | KeyCode::Char('L') => self.show_leaderboard = true,
+            KeyCode::Char('q') | KeyCode::Char('Q') | KeyCode::Esc => return Some(None),
+            _ => {}
+        }
+        None
+    }
+
+    /// Clicking a profile selects it, and clicking it again plays it.
+    fn handle_click(&mut self, column: u16, row: u16) -> Option<Option<String>> {
+        if self.show_leaderboard {
+            self.show_leaderboard = false;
+            return None;
+        }
+        match mouse::clicked(&self.buttons, column, row)? {
+            Click::Key(code) => self.handle_input(code),
+            Click::Select(i) if i == self.selected && self.new_name.is_none() => {
+                Some(Some(self.profiles[i].0.clone()))
+            }
+            Click::Select(i) => {
+                if self.new_name.is_none() {
+                    self.selected = i;
+                }
+                None
+            }
+        }
+    }
+
+    fn render(&mut self, backend: &mut impl Backend) -> io::Result<()> {
+        let size = backend.size()?;
+        let (title, lines) = if self.show_leaderboard {
+            (tr!(" LEADERBOARD "), self.leaderboard_lines())
+        } else {
+            (tr!(" PLAYER PROFILES "), self.profile_lines())
+        };
+        // The profile list takes the first lines
+        let rows = if self.show_leaderboard { 0 } else { self.profiles.len() };
+        self.buttons = screen::draw_box(backend, size, title, &lines, rows)?;
+        Ok(())
+    }
+
+    fn profile_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        for (i, (name, bankroll)) in self.profiles.iter().enumerate() {
+            let marker = if i == self.selected && self.new_name.is_none() { "▶" } else { " " };
+            let bankroll = bankroll.map_or(String::from(tr!("new")), locale::money);
+            lines.push(format!("  {} {:<24}{:>10}", marker, name, bankroll));
+        }
+        lines.push(String::new());
+        match &self.new_name {
+            Some(name) => {
+                lines.push(tr!("  New profile: {}_", name));
+                lines.push(format!("  {}", self.error));
+                lines.push(String::from(tr!("  [Enter] Create  │  [Esc] Cancel")));
+            }
+            None => {
+                lines.push(String::new());
+                lines.push(String::new());
+                lines.push(String::from(tr!("  [↑/↓] Select  │  [Enter] Play  │  [N] New")));
+                lines.push(String::from(tr!("  [L] Leaderboard  │  [Q] Quit")));
+            }
+        }
+        lines
+    }
+
+    fn leaderboard_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        let tables = [
+            (tr!("Biggest bankroll"), &self.leaderboard.biggest_bankroll, locale::money as fn(i64) -> String),
+            (tr!("Longest win streak"), &self.leaderboard.longest_streak, |streak| streak.to_string()),
+            (tr!("Best session"), &self.leaderboard.best_net, locale::net),
+            (tr!("Today's daily challenge"), &self.leaderboard.daily, locale::net),
+        ];
+        for (heading, entries, record_text) in tables {
+            lines.push(format!("  {}", heading));
+            if entries.is_empty() {
+                lines.push(String::from(tr!("      No sessions yet")));
+            }
+            for (rank, (profile, record)) in entries.iter().enumerate() {
+                lines.push(format!("    {}. {:<24}{:>10}", rank + 1, profile, record_text(*record)));
+            }
+            lines.push(String::new());
+        }
+        lines.push(String::from(tr!("  Press any key to return")));
+        lines
+    }
+}
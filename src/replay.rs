@@ -0,0 +1,89 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::Path;
+
+use crate::game::{Hand, PlayerHand};
+
+// A snapshot of the table after a deal or a decision.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Frame {
+    /// What just happened, e.g. "Hit" or the round's result.
+    pub event: String,
+    pub dealer: Hand,
+    pub hole_card_hidden: bool,
+    pub hands: Vec<PlayerHand>,
+    pub active_hand: usize,
+    pub bankroll: u32,
+}
+
+// Writes frames to a replay file as they happen, one JSON object per line,
+// so the file stays usable if the game is closed mid-round.
+pub struct Recorder {
+    writer: BufWriter<File>,
+    last: Option<Frame>,
+}
+
+impl Recorder {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Recorder {
+            writer: BufWriter::new(File::create(path)?),
+            last: None,
+        })
+    }
+
+    /// Adds a frame to the replay, unless nothing changed since the last one.
+    pub fn record(&mut self, frame: Frame) -> io::Result<()> {
+        if self.last.as_ref() == Some(&frame) {
+            return Ok(());
+        }
+        serde_json::to_writer(&mut self.writer, &frame).map_err(io::Error::other)?;
+        self.writer.write_all(b"\n")?;
+        self.writer.flush()?;
+        self.last = Some(frame);
+        Ok(())
+    }
+}
+
+// A recorded session being stepped through.
+pub struct Replay {
+    frames: Vec<Frame>,
+    pub position: usize,
+}
+
+impl Replay {
+    /// Reads a replay file written by the recorder.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        let contents = fs::read_to_string(path)?;
+        let frames = contents
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(serde_json::from_str)
+            .collect::<Result<Vec<Frame>, _>>()
+            .map_err(|err| {
+                io::Error::other(format!("invalid replay file {}: {}", path.display(), err))
+            })?;
+        if frames.is_empty() {
+            return Err(io::Error::other(format!("replay file {} is empty", path.display())));
+        }
+        Ok(Replay { frames, position: 0 })
+    }
+
+    pub fn frame(&self) -> &Frame {
+        &self.frames[self.position]
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn forward(&mut self) {
+        if self.position + 1 < self.frames.len() {
+            self.position += 1;
+        }
+    }
+
+    pub fn back(&mut self) {
+        self.position = self.position.saturating_sub(1);
+    }
+}
@@ -0,0 +1,354 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::locale::{self, tr};
+
+// The biggest number either side of a payout written in the config or on
+// the command line, well past any a table pays
+const MAX_PAYOUT_TERM: u32 = 100;
+
+// A payout ratio such as 3:2, applied to a bet to get the winnings.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Payout {
+    pub numerator: u32,
+    pub denominator: u32,
+}
+
+impl Payout {
+    pub const THREE_TO_TWO: Payout = Payout::new(3, 2);
+
+    pub const fn new(numerator: u32, denominator: u32) -> Self {
+        Payout {
+            numerator,
+            denominator,
+        }
+    }
+
+    /// Returns the winnings paid on a bet, rounded down to a whole chip.
+    pub fn winnings(&self, bet: u32) -> u32 {
+        // Worked out wide, as a big bet at long odds runs past a u32
+        let winnings = u64::from(bet) * u64::from(self.numerator) / u64::from(self.denominator);
+        winnings.min(u64::from(u32::MAX)) as u32
+    }
+}
+
+impl fmt::Display for Payout {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.numerator, self.denominator)
+    }
+}
+
+// Parses ratios written like "6:5".
+impl FromStr for Payout {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (numerator, denominator) = s
+            .split_once(':')
+            .ok_or_else(|| format!("invalid payout '{}', expected a ratio like 3:2", s))?;
+        let numerator = numerator
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid payout numerator in '{}'", s))?;
+        let denominator: u32 = denominator
+            .trim()
+            .parse()
+            .map_err(|_| format!("invalid payout denominator in '{}'", s))?;
+        if denominator == 0 {
+            return Err(format!("invalid payout '{}', denominator can't be zero", s));
+        }
+        if numerator > MAX_PAYOUT_TERM || denominator > MAX_PAYOUT_TERM {
+            return Err(format!("invalid payout '{}', expected numbers up to {}", s, MAX_PAYOUT_TERM));
+        }
+        Ok(Payout::new(numerator, denominator))
+    }
+}
+
+// Lets the config file store payouts the same way they're written on the
+// command line.
+impl TryFrom<String> for Payout {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Payout> for String {
+    fn from(payout: Payout) -> Self {
+        payout.to_string()
+    }
+}
+
+// Table rules that vary between casinos.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rules {
+    /// Number of decks in the shoe, from 1 to 8.
+    pub decks: usize,
+    /// How far into the shoe the cut card is placed, as a fraction.
+    pub penetration: f32,
+    /// Whether the dealer hits a soft 17 (H17) instead of standing (S17).
+    pub dealer_hits_soft_17: bool,
+    /// European style: the dealer takes no hole card until the player is done.
+    pub no_hole_card: bool,
+    /// With no hole card, a dealer blackjack only takes the original bet
+    /// rather than doubles and splits too.
+    pub original_bets_only: bool,
+    /// Maximum number of hands the player can hold after splitting and re-splitting.
+    pub max_hands: usize,
+    /// Whether hands made by splitting can be doubled.
+    pub double_after_split: bool,
+    /// Surrender after the dealer has checked for blackjack.
+    pub late_surrender: bool,
+    /// Surrender before the dealer checks for blackjack.
+    pub early_surrender: bool,
+    /// Whether split aces can take more than one card each.
+    pub hit_split_aces: bool,
+    /// Whether an ace and a ten after splitting aces pays as blackjack
+    /// rather than counting as an ordinary 21.
+    pub split_aces_blackjack: bool,
+    /// Whether a dealer total of exactly 22 pushes every hand still standing
+    /// instead of busting, as in Free Bet and Blackjack Switch.
+    pub push_on_dealer_22: bool,
+    /// A hand with this many cards that hasn't busted wins automatically.
+    pub charlie: Option<usize>,
+    /// What a player natural pays.
+    pub blackjack_payout: Payout,
+    /// Smallest bet accepted per spot.
+    pub min_bet: u32,
+    /// Largest bet accepted per spot.
+    pub max_bet: u32,
+}
+
+impl Rules {
+    /// Returns short descriptions of the rules for the table header.
+    pub fn summary(&self) -> Vec<String> {
+        let mut parts = vec![
+            match self.decks {
+                1 => String::from(tr!("1 deck")),
+                n => tr!("{} decks", n),
+            },
+            String::from(if self.dealer_hits_soft_17 { tr!("H17") } else { tr!("S17") }),
+            tr!("BJ pays {}", self.blackjack_payout),
+            tr!("Bets {}-{}", locale::money(self.min_bet), locale::money(self.max_bet)),
+        ];
+        match (self.no_hole_card, self.original_bets_only) {
+            (true, true) => parts.push(String::from(tr!("No hole card (OBO)"))),
+            (true, false) => parts.push(String::from(tr!("No hole card"))),
+            (false, _) => {}
+        }
+        match (self.early_surrender, self.late_surrender) {
+            (true, true) => parts.push(String::from(tr!("Early/late surrender"))),
+            (true, false) => parts.push(String::from(tr!("Early surrender"))),
+            (false, true) => parts.push(String::from(tr!("Late surrender"))),
+            (false, false) => {}
+        }
+        parts.push(tr!("Split to {}", self.max_hands));
+        if !self.double_after_split {
+            parts.push(String::from(tr!("No DAS")));
+        }
+        if self.hit_split_aces {
+            parts.push(String::from(tr!("Hit split aces")));
+        }
+        if self.split_aces_blackjack {
+            parts.push(String::from(tr!("Split A+10 is BJ")));
+        }
+        if self.push_on_dealer_22 {
+            parts.push(String::from(tr!("Dealer 22 pushes")));
+        }
+        if let Some(cards) = self.charlie {
+            parts.push(tr!("{}-card Charlie", cards));
+        }
+        parts
+    }
+
+    /// Checks the rules can be dealt with, as those read from the config
+    /// file or given on the command line might not be, returning what's
+    /// wrong with them if not.
+    pub fn check(&self) -> Result<(), String> {
+        if !(1..=8).contains(&self.decks) {
+            return Err(format!("decks is {}, expected 1 to 8", self.decks));
+        }
+        if !(0.1..=1.0).contains(&self.penetration) {
+            return Err(format!("penetration is {}, expected 0.1 to 1.0", self.penetration));
+        }
+        if self.max_hands == 0 {
+            return Err(String::from("max_hands is 0, expected at least 1"));
+        }
+        if let Some(cards) = self.charlie
+            && !(3..=10).contains(&cards)
+        {
+            return Err(format!("charlie is {}, expected 3 to 10", cards));
+        }
+        if self.min_bet == 0 {
+            return Err(String::from("min_bet is 0, expected at least 1"));
+        }
+        if self.min_bet > self.max_bet {
+            return Err(format!("min_bet is {}, above max_bet of {}", self.min_bet, self.max_bet));
+        }
+        Ok(())
+    }
+
+    /// Estimates the house edge in percent for these rules against perfect
+    /// basic strategy. Each rule shifts a base edge by its commonly published
+    /// effect, so the result is an approximation rather than an exact figure.
+    pub fn house_edge(&self) -> f32 {
+        // 8 decks, S17, DAS, split to 4, no surrender, 3:2, dealer peeks
+        let mut edge = 0.43;
+
+        edge += match self.decks {
+            1 => -0.48,
+            2 => -0.19,
+            3 => -0.10,
+            4 => -0.06,
+            5 => -0.04,
+            6 => -0.03,
+            7 => -0.01,
+            _ => 0.0,
+        };
+        if self.dealer_hits_soft_17 {
+            edge += 0.22;
+        }
+        if !self.double_after_split {
+            edge += 0.14;
+        }
+        edge += match self.max_hands {
+            0..=2 => 0.05,
+            3 => 0.01,
+            _ => 0.0,
+        };
+        if self.early_surrender {
+            edge -= 0.39;
+        } else if self.late_surrender && !self.no_hole_card {
+            edge -= 0.08;
+        }
+        if self.no_hole_card && !self.original_bets_only {
+            edge += 0.11;
+        }
+        if self.hit_split_aces {
+            edge -= 0.19;
+        }
+        if self.split_aces_blackjack {
+            edge -= 0.19;
+        }
+        if self.push_on_dealer_22 {
+            edge += 6.91;
+        }
+        edge += match self.charlie {
+            Some(0..=5) => -1.46,
+            Some(6) => -0.16,
+            Some(_) => -0.01,
+            None => 0.0,
+        };
+
+        // A natural comes up in roughly 4.6% of hands
+        let ratio = self.blackjack_payout.numerator as f32 / self.blackjack_payout.denominator as f32;
+        edge += (1.5 - ratio) * 4.6;
+
+        edge
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Rules {
+            decks: 6,
+            penetration: 0.75,
+            dealer_hits_soft_17: false,
+            no_hole_card: false,
+            original_bets_only: false,
+            max_hands: 4,
+            double_after_split: true,
+            late_surrender: true,
+            early_surrender: false,
+            hit_split_aces: false,
+            split_aces_blackjack: false,
+            push_on_dealer_22: false,
+            charlie: None,
+            blackjack_payout: Payout::THREE_TO_TWO,
+            min_bet: 10,
+            max_bet: 500,
+        }
+    }
+}
+
+// Named rule sets matching well-known tables, plus the player's own.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    VegasStrip,
+    Downtown,
+    AtlanticCity,
+    European,
+    Custom,
+}
+
+impl Preset {
+    pub const ALL: [Preset; 5] = [
+        Preset::VegasStrip,
+        Preset::Downtown,
+        Preset::AtlanticCity,
+        Preset::European,
+        Preset::Custom,
+    ];
+
+    /// Returns the name used for the preset on the command line and in the config file.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Preset::VegasStrip => "vegas-strip",
+            Preset::Downtown => "downtown",
+            Preset::AtlanticCity => "atlantic-city",
+            Preset::European => "european",
+            Preset::Custom => "custom",
+        }
+    }
+
+    /// Returns the rules for a built-in preset. The custom preset lives in
+    /// the config file, so it starts out as the default rules here.
+    pub fn rules(&self) -> Rules {
+        let defaults = Rules::default();
+        match self {
+            Preset::VegasStrip => Rules {
+                decks: 4,
+                late_surrender: false,
+                ..defaults
+            },
+            Preset::Downtown => Rules {
+                decks: 2,
+                dealer_hits_soft_17: true,
+                late_surrender: false,
+                ..defaults
+            },
+            Preset::AtlanticCity => Rules {
+                decks: 8,
+                late_surrender: true,
+                ..defaults
+            },
+            Preset::European => Rules {
+                decks: 6,
+                no_hole_card: true,
+                late_surrender: false,
+                max_hands: 2,
+                ..defaults
+            },
+            Preset::Custom => defaults,
+        }
+    }
+}
+
+impl FromStr for Preset {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Preset::ALL
+            .into_iter()
+            .find(|p| p.name() == s)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Preset::ALL.iter().map(|p| p.name()).collect();
+                format!("unknown rules preset '{}', expected one of: {}", s, names.join(", "))
+            })
+    }
+}
@@ -0,0 +1,93 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use crate::GameState;
+use crate::chips::ChipStack;
+use crate::discard::DiscardTray;
+use crate::game::{Card, Hand, PlayerHand, Shoe};
+use crate::rules::{Payout, Rules};
+use crate::session::{HandOutcome, Session};
+use crate::side_bets::SideBet;
+
+// Everything needed to put a game back the way it was, down to the cards
+// left in the shoe and a round that was still being played.
+#[derive(Serialize, Deserialize)]
+pub struct SavedGame {
+    pub state: GameState,
+    pub shoe: Shoe,
+    pub player_hands: Vec<PlayerHand>,
+    pub active_hand: usize,
+    pub spots: usize,
+    pub dealer_hand: Hand,
+    pub round_result: String,
+    pub bankroll: u32,
+    pub bet_chips: ChipStack,
+    pub bet_error: String,
+    pub last_bet: u32,
+    pub round_start_bankroll: u32,
+    pub base_bet: u32,
+    pub insurance_bet: u32,
+    pub side_bets: Vec<SideBet>,
+    pub placed_side_bets: Vec<SideBet>,
+    pub side_bet_results: Vec<(SideBet, Option<Payout>)>,
+    pub initial_cards: Vec<Card>,
+    #[serde(default)]
+    pub discards: DiscardTray,
+    pub round_outcomes: Vec<HandOutcome>,
+    pub actions: Vec<String>,
+    pub rules: Rules,
+    pub session: Session,
+    /// The history session the rounds are recorded under.
+    pub session_id: i64,
+    pub daily: Option<String>,
+}
+
+impl SavedGame {
+    /// Returns where a profile's saved game lives, e.g.
+    /// `~/.config/rustjack/saves/alice-autosave.json`.
+    pub fn path(profile: &str, name: &str) -> Option<PathBuf> {
+        let dir = dirs::config_dir()?.join("rustjack").join("saves");
+        Some(dir.join(format!("{}-{}.json", profile, name)))
+    }
+
+    /// Reads a saved game, or None if there isn't one.
+    pub fn load(profile: &str, name: &str) -> io::Result<Option<Self>> {
+        let Some(path) = Self::path(profile, name) else {
+            return Ok(None);
+        };
+
+        match fs::read_to_string(&path) {
+            Ok(contents) => serde_json::from_str(&contents).map(Some).map_err(|err| {
+                io::Error::other(format!("invalid saved game {}: {}", path.display(), err))
+            }),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Writes the game out, replacing whatever was saved under the name before.
+    pub fn save(&self, profile: &str, name: &str) -> io::Result<()> {
+        let path = Self::path(profile, name)
+            .ok_or_else(|| io::Error::other("no config directory on this platform"))?;
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        // Write to the side and rename, so a save cut short doesn't leave
+        // a broken file in place of the last good one
+        let partial = path.with_extension("json.tmp");
+        fs::write(&partial, serde_json::to_string(self).map_err(io::Error::other)?)?;
+        fs::rename(partial, path)
+    }
+
+    pub fn delete(profile: &str, name: &str) -> io::Result<()> {
+        let Some(path) = Self::path(profile, name) else {
+            return Ok(());
+        };
+        match fs::remove_file(path) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+            _ => Ok(()),
+        }
+    }
+}
@@ -0,0 +1,73 @@
+use crossterm::{
+    cursor, queue,
+    terminal::{Clear, ClearType},
+};
+use std::io::{self, Write};
+use unicode_width::UnicodeWidthStr;
+
+use crate::mouse::{self, Button, Click};
+use crate::theme::{self, Palette};
+
+const BOX_WIDTH: u16 = 60;
+
+/// Clears a screen of `size` and draws the lines in a titled box in the
+/// middle of it, the way the screens outside the table look. The first
+/// `rows` lines can be clicked to select them, and the key hints on any
+/// line to press their keys; both are returned as buttons.
+pub fn draw_box(
+    stdout: &mut impl Write,
+    size: (u16, u16),
+    title: &str,
+    lines: &[String],
+    rows: usize,
+) -> io::Result<Vec<Button>> {
+    draw_themed_box(stdout, size, title, lines, rows, &Palette::default())
+}
+
+/// Draws a box as `draw_box` does, in a theme's colors.
+pub fn draw_themed_box(
+    stdout: &mut impl Write,
+    size: (u16, u16),
+    title: &str,
+    lines: &[String],
+    rows: usize,
+    palette: &Palette,
+) -> io::Result<Vec<Button>> {
+    queue!(stdout, palette.apply(), Clear(ClearType::All), cursor::MoveTo(0, 0))?;
+
+    // Wider for lines that don't fit, as a longer translation might be, as
+    // far as the screen allows
+    let (term_width, term_height) = size;
+    let widest = lines.iter().map(|line| theme::width(line) + 4).max().unwrap_or(0) as u16;
+    let width = BOX_WIDTH.max(widest.min(term_width));
+    let inner_width = (width - 2) as usize;
+    let height = lines.len() as u16 + 2;
+    let start_x = term_width.saturating_sub(width) / 2;
+    let start_y = term_height.saturating_sub(height) / 2;
+
+    queue!(stdout, cursor::MoveTo(start_x, start_y))?;
+    let border = |line: &str| palette.paint(line, palette.border);
+    write!(stdout, "{}\r", border(&format!("┌{}┐", "─".repeat(inner_width))))?;
+    let title_width = UnicodeWidthStr::width(title) as u16;
+    queue!(stdout, cursor::MoveTo(start_x + (width - title_width) / 2, start_y))?;
+    write!(stdout, "{}\r", palette.paint(title, palette.accent))?;
+
+    let mut buttons = Vec::new();
+    for i in 0..rows {
+        buttons.push(Button::row(start_y + 1 + i as u16, start_x, width, Click::Select(i)));
+    }
+
+    let mut line = start_y + 1;
+    for content in lines {
+        let padding = inner_width.saturating_sub(theme::width(content));
+        queue!(stdout, cursor::MoveTo(start_x, line))?;
+        write!(stdout, "{}{}{}{}\r", border("│"), content, " ".repeat(padding), border("│"))?;
+        buttons.extend(mouse::key_hints(content, start_x + 1, line));
+        line += 1;
+    }
+
+    queue!(stdout, cursor::MoveTo(start_x, line))?;
+    write!(stdout, "{}\r", border(&format!("└{}┘", "─".repeat(inner_width))))?;
+    stdout.flush()?;
+    Ok(buttons)
+}
@@ -0,0 +1,260 @@
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+use crate::locale::tr;
+
+// Limits that end a session once the bankroll has moved far enough from
+// where it started.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SessionLimit {
+    StopLoss,
+    WinGoal,
+}
+
+impl SessionLimit {
+    /// Returns the limit's name as shown in the popup title.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SessionLimit::StopLoss => tr!("STOP-LOSS REACHED"),
+            SessionLimit::WinGoal => tr!("WIN GOAL REACHED"),
+        }
+    }
+}
+
+// How a round went overall, judged by whether the bankroll went up or down.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Push,
+}
+
+impl Outcome {
+    /// Classifies a round by how much it changed the bankroll.
+    pub fn from_net(net: i64) -> Self {
+        match net {
+            1.. => Outcome::Win,
+            0 => Outcome::Push,
+            _ => Outcome::Loss,
+        }
+    }
+
+    /// Returns the letter used for the outcome in streaks, e.g. W3.
+    pub fn letter(&self) -> &'static str {
+        match self {
+            Outcome::Win => tr!("W"),
+            Outcome::Loss => tr!("L"),
+            Outcome::Push => tr!("P"),
+        }
+    }
+}
+
+// How a single hand was settled.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum HandOutcome {
+    Win,
+    Lose,
+    Push,
+    Blackjack,
+    Charlie,
+    Bust,
+    Surrender,
+}
+
+impl HandOutcome {
+    pub const ALL: [HandOutcome; 7] = [
+        HandOutcome::Win,
+        HandOutcome::Lose,
+        HandOutcome::Push,
+        HandOutcome::Blackjack,
+        HandOutcome::Charlie,
+        HandOutcome::Bust,
+        HandOutcome::Surrender,
+    ];
+
+    /// Returns the short label used when summing up several hands.
+    pub fn label(&self) -> &'static str {
+        match self {
+            HandOutcome::Win => "Win",
+            HandOutcome::Lose => "Lose",
+            HandOutcome::Push => "Push",
+            HandOutcome::Blackjack => "Blackjack",
+            HandOutcome::Charlie => "Charlie",
+            HandOutcome::Bust => "Bust",
+            HandOutcome::Surrender => "Surrender",
+        }
+    }
+
+    /// Returns the outcome's name as shown, in the language picked; the
+    /// label is what's stored.
+    pub fn name(&self) -> &'static str {
+        match self {
+            HandOutcome::Win => tr!("Win"),
+            HandOutcome::Lose => tr!("Lose"),
+            HandOutcome::Push => tr!("Push"),
+            HandOutcome::Blackjack => tr!("Blackjack"),
+            HandOutcome::Charlie => tr!("Charlie"),
+            HandOutcome::Bust => tr!("Bust"),
+            HandOutcome::Surrender => tr!("Surrender"),
+        }
+    }
+
+    /// Looks an outcome up by its label, as stored in the history.
+    pub fn from_label(label: &str) -> Option<Self> {
+        HandOutcome::ALL.into_iter().find(|o| o.label() == label)
+    }
+}
+
+// What a push does to a running win or loss streak.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PushPolicy {
+    /// The streak carries on past the push.
+    #[default]
+    Keep,
+    /// The push ends the streak.
+    Reset,
+}
+
+impl FromStr for PushPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "keep" => Ok(PushPolicy::Keep),
+            "reset" => Ok(PushPolicy::Reset),
+            _ => Err(format!("unknown push policy '{}', expected keep or reset", s)),
+        }
+    }
+}
+
+// Current and longest runs of the same outcome. Pushes are counted in a
+// run of their own, and only touch the win or loss streak under the reset
+// policy.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Streaks {
+    /// The win or loss streak in progress, if any.
+    pub current: Option<(Outcome, u32)>,
+    pub pushes: u32,
+    pub best_win: u32,
+    pub best_loss: u32,
+    pub best_push: u32,
+}
+
+impl Streaks {
+    pub fn record(&mut self, outcome: Outcome, policy: PushPolicy) {
+        if outcome == Outcome::Push {
+            self.pushes += 1;
+            self.best_push = self.best_push.max(self.pushes);
+            if policy == PushPolicy::Reset {
+                self.current = None;
+            }
+            return;
+        }
+
+        self.pushes = 0;
+        let length = match self.current {
+            Some((current, length)) if current == outcome => length + 1,
+            _ => 1,
+        };
+        self.current = Some((outcome, length));
+        let best = match outcome {
+            Outcome::Win => &mut self.best_win,
+            _ => &mut self.best_loss,
+        };
+        *best = (*best).max(length);
+    }
+
+    /// Returns the streak in progress as shown at the table, e.g. W3, or a
+    /// run of pushes if that's what the last rounds were.
+    pub fn current_str(&self) -> String {
+        if self.pushes > 0 {
+            return format!("{}{}", Outcome::Push.letter(), self.pushes);
+        }
+        match self.current {
+            Some((outcome, length)) => format!("{}{}", outcome.letter(), length),
+            None => String::from("-"),
+        }
+    }
+}
+
+// Running totals for the rounds played since the game was started.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Bankroll the player sat down with.
+    pub starting_bankroll: u32,
+    /// Chips bought with each rebuy.
+    pub buy_in: u32,
+    pub rebuys: u32,
+    pub rounds: u32,
+    /// Highest the bankroll has been after a round.
+    pub peak_bankroll: u32,
+    /// Loss that ends the session, if the player set one.
+    pub stop_loss: Option<u32>,
+    /// Profit that ends the session, if the player set one.
+    pub win_goal: Option<u32>,
+    pub streaks: Streaks,
+    pub push_policy: PushPolicy,
+}
+
+impl Session {
+    pub fn new(
+        starting_bankroll: u32,
+        buy_in: u32,
+        stop_loss: Option<u32>,
+        win_goal: Option<u32>,
+        push_policy: PushPolicy,
+    ) -> Self {
+        Session {
+            starting_bankroll,
+            buy_in,
+            rebuys: 0,
+            rounds: 0,
+            peak_bankroll: starting_bankroll,
+            stop_loss,
+            win_goal,
+            streaks: Streaks::default(),
+            push_policy,
+        }
+    }
+
+    /// Counts a finished round, given the bankroll it left the player with
+    /// and how much it won or lost overall.
+    pub fn record_round(&mut self, bankroll: u32, round_net: i64) {
+        self.rounds += 1;
+        self.streaks.record(Outcome::from_net(round_net), self.push_policy);
+        self.peak_bankroll = self.peak_bankroll.max(bankroll);
+    }
+
+    /// Buys another stack of chips after going broke, returning the new bankroll.
+    pub fn rebuy(&mut self, bankroll: u32) -> u32 {
+        self.rebuys += 1;
+        bankroll + self.buy_in
+    }
+
+    /// Returns how far the bankroll is up or down on what it started at
+    /// plus any rebuys.
+    pub fn net(&self, bankroll: u32) -> i64 {
+        bankroll as i64 - self.starting_bankroll as i64 - self.buy_in as i64 * self.rebuys as i64
+    }
+
+    /// Returns the limit the bankroll has reached, if any.
+    pub fn limit_reached(&self, bankroll: u32) -> Option<SessionLimit> {
+        let net = self.net(bankroll);
+        if self.stop_loss.is_some_and(|loss| net <= -(loss as i64)) {
+            Some(SessionLimit::StopLoss)
+        } else if self.win_goal.is_some_and(|goal| net >= goal as i64) {
+            Some(SessionLimit::WinGoal)
+        } else {
+            None
+        }
+    }
+
+    /// Drops a limit the player chose to play on past.
+    pub fn clear_limit(&mut self, limit: SessionLimit) {
+        match limit {
+            SessionLimit::StopLoss => self.stop_loss = None,
+            SessionLimit::WinGoal => self.win_goal = None,
+        }
+    }
+}
@@ -0,0 +1,451 @@
+use crossterm::event::{Event, KeyCode, KeyEvent, MouseButton, MouseEvent, MouseEventKind};
+use std::io::{self, Write};
+
+use crate::animation::AnimationSpeed;
+use crate::backend::Backend;
+use crate::card_art::CardBack;
+use crate::config::Config;
+use crate::count::{Rounding, System};
+use crate::keys::{label, Key, KeyBindings, KeyScheme};
+use crate::locale::{self, tr, Currency, Locale};
+use crate::mouse::{self, Button, Click};
+use crate::rules::{Payout, Preset, Rules};
+use crate::screen;
+use crate::session::PushPolicy;
+use crate::theme::Theme;
+
+// How many settings are listed at once; the rest are scrolled to.
+const VISIBLE_ROWS: usize = 12;
+
+// Columns the settings' names take at least, more in languages with
+// longer names
+const NAME_WIDTH: usize = 26;
+
+const PAYOUTS: [Payout; 4] = [Payout::THREE_TO_TWO, Payout::new(6, 5), Payout::new(1, 1), Payout::new(2, 1)];
+
+// A setting that can be changed, one per line.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Row {
+    Preset,
+    Decks,
+    Penetration,
+    Soft17,
+    Payout,
+    DoubleAfterSplit,
+    Surrender,
+    MinBet,
+    MaxBet,
+    PushStreak,
+    Language,
+    Currency,
+    Theme,
+    CardBack,
+    EmojiSuits,
+    LargePrint,
+    ShoeLeft,
+    CountSystem,
+    TrueCount,
+    TrainerSpeed,
+    DealerDelay,
+    AnimationSpeed,
+    KeyScheme,
+    /// The key for one of the actions in `KeyBindings::ACTIONS`.
+    Key(usize),
+}
+
+// The settings screen, which changes a profile's config while the game is
+// running rather than by editing the file. Table rules changed here go
+// into the custom preset.
+pub struct Settings {
+    /// The config as changed so far.
+    pub config: Config,
+    /// The rules of the table the game's at, which the rules start from
+    /// until they're changed here.
+    pub table: Option<Rules>,
+    // Whether a preset's been picked or a rule changed
+    rules_changed: bool,
+    rows: Vec<Row>,
+    // The themes to pick from, the player's own theme files included
+    themes: Vec<Theme>,
+    selected: usize,
+    // First row shown, once there are more than fit
+    scroll: usize,
+    // Waiting for the key to bind the selected action to
+    rebinding: bool,
+    message: String,
+    // The keys the screen itself is driven with, as they were when opened
+    keys: KeyBindings,
+    buttons: Vec<Button>,
+}
+
+impl Settings {
+    pub fn new(config: Config, keys: KeyBindings) -> Self {
+        let mut rows = vec![
+            Row::Preset,
+            Row::Decks,
+            Row::Penetration,
+            Row::Soft17,
+            Row::Payout,
+            Row::DoubleAfterSplit,
+            Row::Surrender,
+            Row::MinBet,
+            Row::MaxBet,
+            Row::PushStreak,
+            Row::Language,
+            Row::Currency,
+            Row::Theme,
+            Row::CardBack,
+            Row::EmojiSuits,
+            Row::LargePrint,
+            Row::ShoeLeft,
+            Row::CountSystem,
+            Row::TrueCount,
+            Row::TrainerSpeed,
+            Row::DealerDelay,
+            Row::AnimationSpeed,
+            Row::KeyScheme,
+        ];
+        rows.extend((0..KeyBindings::ACTIONS.len()).map(Row::Key));
+        Settings {
+            config,
+            table: None,
+            rules_changed: false,
+            rows,
+            themes: Theme::available().unwrap_or_else(|_| Theme::ALL.to_vec()),
+            selected: 0,
+            scroll: 0,
+            rebinding: false,
+            message: String::new(),
+            keys,
+            buttons: Vec::new(),
+        }
+    }
+
+    /// Shows the settings on a screen of their own until the player is done,
+    /// then saves them to the profile's config.
+    pub fn run(&mut self, backend: &mut impl Backend, profile: &str) -> io::Result<()> {
+        backend.enter()?;
+
+        loop {
+            let size = backend.size()?;
+            self.buttons = self.draw(backend, size)?;
+            let done = match backend.read()? {
+                Event::Key(KeyEvent { code, .. }) => self.handle_input(code),
+                Event::Mouse(MouseEvent {
+                    kind: MouseEventKind::Down(MouseButton::Left),
+                    column,
+                    row,
+                    ..
+                }) => match mouse::clicked(&self.buttons, column, row) {
+                    Some(Click::Key(code)) => self.handle_input(code),
+                    Some(Click::Select(i)) => {
+                        self.select(i);
+                        false
+                    }
+                    None => false,
+                },
+                _ => false,
+            };
+            if done {
+                break;
+            }
+        }
+
+        backend.leave()?;
+        self.config.save(profile)
+    }
+
+    /// The rules a new shoe would be dealt with.
+    pub fn rules(&self) -> Rules {
+        match &self.table {
+            Some(table) if !self.rules_changed => table.clone(),
+            _ => self.config.preset.map(|p| self.config.preset_rules(p)).unwrap_or_default(),
+        }
+    }
+
+    /// The rules as they've been changed, if they have been.
+    pub fn changed_rules(&self) -> Option<Rules> {
+        self.rules_changed.then(|| self.rules())
+    }
+
+    /// The key bindings as they've been changed.
+    pub fn key_bindings(&self) -> KeyBindings {
+        self.config.key_bindings().unwrap_or_else(|_| self.keys.clone())
+    }
+
+    /// Selects one of the rows shown, counting from the top of the list.
+    pub fn select(&mut self, shown: usize) {
+        if !self.rebinding {
+            self.selected = (self.scroll + shown).min(self.rows.len() - 1);
+        }
+    }
+
+    /// Returns true once the player is done with the settings.
+    pub fn handle_input(&mut self, key: KeyCode) -> bool {
+        if self.rebinding {
+            self.rebinding = false;
+            if key == KeyCode::Esc {
+                self.message = String::new();
+            } else if let Row::Key(action) = self.rows[self.selected] {
+                let action = KeyBindings::ACTIONS[action];
+                self.config.keys.insert(action.to_string(), Key::from(key));
+                self.message = tr!("{} is now on {}", label(action), Key::from(key));
+            }
+            return false;
+        }
+
+        self.message = String::new();
+        match key {
+            _ if self.keys.moves_up(key) => self.selected = self.selected.saturating_sub(1),
+            _ if self.keys.moves_down(key) => self.selected = (self.selected + 1).min(self.rows.len() - 1),
+            _ if self.keys.moves_left(key) => self.change(false),
+            _ if self.keys.moves_right(key) => self.change(true),
+            KeyCode::Enter => match self.rows[self.selected] {
+                Row::Key(action) => {
+                    self.rebinding = true;
+                    self.message = tr!("Press the new key for {}, or Esc", label(KeyBindings::ACTIONS[action]));
+                }
+                _ => self.change(true),
+            },
+            KeyCode::Backspace => {
+                if let Row::Key(action) = self.rows[self.selected] {
+                    self.config.keys.remove(KeyBindings::ACTIONS[action]);
+                }
+            }
+            _ if self.keys.menu.matches(key) || self.keys.quit.matches(key) => return true,
+            _ => {}
+        }
+
+        // Keep the selected row in view
+        if self.selected < self.scroll {
+            self.scroll = self.selected;
+        } else if self.selected >= self.scroll + VISIBLE_ROWS {
+            self.scroll = self.selected + 1 - VISIBLE_ROWS;
+        }
+        false
+    }
+
+    // Steps the selected setting to its next or previous value
+    fn change(&mut self, forward: bool) {
+        let step = |value: u32, by: u32, min: u32, max: u32| {
+            if forward {
+                (value + by).min(max)
+            } else {
+                value.saturating_sub(by).max(min)
+            }
+        };
+        match self.rows[self.selected] {
+            Row::Preset => {
+                let current = Preset::ALL.iter().position(|p| Some(*p) == self.config.preset);
+                let next = match (current, forward) {
+                    (None, _) => 0,
+                    (Some(i), true) => (i + 1) % Preset::ALL.len(),
+                    (Some(i), false) => (i + Preset::ALL.len() - 1) % Preset::ALL.len(),
+                };
+                self.config.preset = Some(Preset::ALL[next]);
+                self.rules_changed = true;
+            }
+            Row::Decks => self.edit_rules(|r| r.decks = step(r.decks as u32, 1, 1, 8) as usize),
+            Row::Penetration => self.edit_rules(|r| {
+                let percent = (r.penetration * 100.0).round() as u32;
+                r.penetration = step(percent, 5, 10, 100) as f32 / 100.0;
+            }),
+            Row::Soft17 => self.edit_rules(|r| r.dealer_hits_soft_17 = !r.dealer_hits_soft_17),
+            Row::Payout => self.edit_rules(|r| {
+                let i = PAYOUTS.iter().position(|p| *p == r.blackjack_payout).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + PAYOUTS.len() - 1 };
+                r.blackjack_payout = PAYOUTS[next % PAYOUTS.len()];
+            }),
+            Row::DoubleAfterSplit => self.edit_rules(|r| r.double_after_split = !r.double_after_split),
+            Row::Surrender => self.edit_rules(|r| r.late_surrender = !r.late_surrender),
+            Row::MinBet => self.edit_rules(|r| r.min_bet = step(r.min_bet, 5, 5, r.max_bet)),
+            Row::MaxBet => self.edit_rules(|r| r.max_bet = step(r.max_bet, 50, r.min_bet, 10_000)),
+            Row::PushStreak => {
+                self.config.push_policy = match self.config.push_policy {
+                    PushPolicy::Keep => PushPolicy::Reset,
+                    PushPolicy::Reset => PushPolicy::Keep,
+                };
+            }
+            Row::Language => {
+                let i = Locale::ALL.iter().position(|l| *l == self.config.locale).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + Locale::ALL.len() - 1 };
+                self.config.locale = Locale::ALL[next % Locale::ALL.len()];
+            }
+            Row::Currency => {
+                let symbols = Currency::SYMBOLS;
+                let i = symbols.iter().position(|s| *s == self.config.currency.symbol).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + symbols.len() - 1 };
+                self.config.currency.symbol = symbols[next % symbols.len()].to_string();
+            }
+            Row::Theme => {
+                let count = self.themes.len();
+                let i = self.themes.iter().position(|t| *t == self.config.theme).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + count - 1 };
+                self.config.theme = self.themes[next % count].clone();
+                if let Err(err) = self.config.theme.palette() {
+                    self.message = tr!("Couldn't load the theme: {}", err);
+                }
+            }
+            Row::CardBack => {
+                let i = CardBack::ALL.iter().position(|b| *b == self.config.card_back).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + CardBack::ALL.len() - 1 };
+                self.config.card_back = CardBack::ALL[next % CardBack::ALL.len()];
+            }
+            Row::EmojiSuits => self.config.emoji_suits = !self.config.emoji_suits,
+            Row::LargePrint => self.config.large_print = !self.config.large_print,
+            Row::ShoeLeft => self.config.shoe_left = Some(!self.config.shoe_left()),
+            Row::CountSystem => {
+                let i = System::ALL.iter().position(|s| *s == self.config.count_system).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + System::ALL.len() - 1 };
+                self.config.count_system = System::ALL[next % System::ALL.len()];
+            }
+            Row::TrueCount => {
+                let i = Rounding::ALL.iter().position(|r| *r == self.config.true_count_rounding).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + Rounding::ALL.len() - 1 };
+                self.config.true_count_rounding = Rounding::ALL[next % Rounding::ALL.len()];
+            }
+            Row::TrainerSpeed => {
+                let speed = self.config.trainer_speed().as_millis() as u32;
+                self.config.trainer_speed = Some(step(speed, 100, 100, 3000) as u64);
+            }
+            Row::DealerDelay => {
+                let delay = self.config.dealer_delay().as_millis() as u32;
+                self.config.dealer_delay = Some(step(delay, 100, 0, 2000) as u64);
+            }
+            Row::AnimationSpeed => {
+                let speeds = AnimationSpeed::ALL;
+                let i = speeds.iter().position(|s| *s == self.config.animation_speed).unwrap_or(0);
+                let next = if forward { i + 1 } else { i + speeds.len() - 1 };
+                self.config.animation_speed = speeds[next % speeds.len()];
+            }
+            Row::KeyScheme => {
+                self.config.key_scheme = match self.config.key_scheme {
+                    KeyScheme::Standard => KeyScheme::Vim,
+                    KeyScheme::Vim => KeyScheme::Standard,
+                };
+            }
+            Row::Key(_) => {}
+        }
+    }
+
+    // Changes a rule, which makes the table a custom one
+    fn edit_rules(&mut self, edit: impl FnOnce(&mut Rules)) {
+        let mut rules = self.rules();
+        edit(&mut rules);
+        self.config.custom_rules = Some(rules);
+        self.config.preset = Some(Preset::Custom);
+        self.rules_changed = true;
+    }
+
+    fn value(&self, row: Row, rules: &Rules, keys: &KeyBindings) -> String {
+        let on_off = |on: bool| String::from(if on { tr!("On") } else { tr!("Off") });
+        match row {
+            Row::Preset => self.config.preset.map_or("standard", |p| p.name()).to_string(),
+            Row::Decks => rules.decks.to_string(),
+            Row::Penetration => format!("{:.0}%", rules.penetration * 100.0),
+            Row::Soft17 => String::from(if rules.dealer_hits_soft_17 { tr!("Hits") } else { tr!("Stands") }),
+            Row::Payout => rules.blackjack_payout.to_string(),
+            Row::DoubleAfterSplit => on_off(rules.double_after_split),
+            Row::Surrender => on_off(rules.late_surrender),
+            Row::MinBet => locale::money(rules.min_bet),
+            Row::MaxBet => locale::money(rules.max_bet),
+            Row::PushStreak => String::from(match self.config.push_policy {
+                PushPolicy::Keep => tr!("Keeps streaks"),
+                PushPolicy::Reset => tr!("Ends streaks"),
+            }),
+            Row::Language => self.config.locale.name().to_string(),
+            Row::Currency => match self.config.currency.symbol.as_str() {
+                "" => String::from(tr!("None")),
+                symbol => symbol.to_string(),
+            },
+            Row::Theme => self.config.theme.name().to_string(),
+            Row::CardBack => self.config.card_back.name().to_string(),
+            Row::EmojiSuits => on_off(self.config.emoji_suits),
+            Row::LargePrint => on_off(self.config.large_print),
+            Row::ShoeLeft => on_off(self.config.shoe_left()),
+            Row::CountSystem => self.config.count_system.counting().name().to_string(),
+            Row::TrueCount => String::from(match self.config.true_count_rounding {
+                Rounding::Floor => tr!("Round down"),
+                Rounding::Truncate => tr!("Round towards zero"),
+                Rounding::Nearest => tr!("Round to nearest"),
+                Rounding::Exact => tr!("Don't round"),
+            }),
+            Row::TrainerSpeed => format!("{}s", locale::decimal(self.config.trainer_speed().as_secs_f64(), 1)),
+            Row::DealerDelay => format!("{}s", locale::decimal(self.config.dealer_delay().as_secs_f64(), 1)),
+            Row::AnimationSpeed => self.config.animation_speed.name().to_string(),
+            Row::KeyScheme => String::from(match self.config.key_scheme {
+                KeyScheme::Standard => tr!("Standard"),
+                KeyScheme::Vim => "Vim",
+            }),
+            Row::Key(action) => keys
+                .key(KeyBindings::ACTIONS[action])
+                .map_or(String::new(), |key| key.to_string()),
+        }
+    }
+
+    /// Returns the lines the settings box shows, and how many of them at
+    /// the top are settings that can be picked.
+    pub fn lines(&self) -> (Vec<String>, usize) {
+        let rules = self.rules();
+        let keys = self.key_bindings();
+        let mut lines = Vec::new();
+        let end = (self.scroll + VISIBLE_ROWS).min(self.rows.len());
+        let mut names = Vec::new();
+        for &row in &self.rows[self.scroll..end] {
+            names.push(match row {
+                Row::Preset => String::from(tr!("Rules preset")),
+                Row::Decks => String::from(tr!("Decks")),
+                Row::Penetration => String::from(tr!("Penetration")),
+                Row::Soft17 => String::from(tr!("Dealer on soft 17")),
+                Row::Payout => String::from(tr!("Blackjack pays")),
+                Row::DoubleAfterSplit => String::from(tr!("Double after split")),
+                Row::Surrender => String::from(tr!("Late surrender")),
+                Row::MinBet => String::from(tr!("Minimum bet")),
+                Row::MaxBet => String::from(tr!("Maximum bet")),
+                Row::PushStreak => String::from(tr!("A push")),
+                Row::Language => String::from(tr!("Language")),
+                Row::Currency => String::from(tr!("Currency")),
+                Row::Theme => String::from(tr!("Theme")),
+                Row::CardBack => String::from(tr!("Card back")),
+                Row::EmojiSuits => String::from(tr!("Emoji suits")),
+                Row::LargePrint => String::from(tr!("Large print")),
+                Row::ShoeLeft => String::from(tr!("Cards left in the shoe")),
+                Row::CountSystem => String::from(tr!("Counting system")),
+                Row::TrueCount => String::from(tr!("True count")),
+                Row::TrainerSpeed => String::from(tr!("Trainer shows each card")),
+                Row::DealerDelay => String::from(tr!("Dealer waits to draw")),
+                Row::AnimationSpeed => String::from(tr!("Animations")),
+                Row::KeyScheme => String::from(tr!("Key scheme")),
+                Row::Key(action) => tr!("{} key", label(KeyBindings::ACTIONS[action])),
+            });
+        }
+        let width = names.iter().map(|name| name.chars().count() + 2).fold(NAME_WIDTH, usize::max);
+        for (i, name) in (self.scroll..end).zip(names) {
+            let row = self.rows[i];
+            let marker = if i == self.selected { "▶" } else { " " };
+            let value = self.value(row, &rules, &keys);
+            let value = if i == self.selected && !matches!(row, Row::Key(_)) {
+                format!("◀ {} ▶", value)
+            } else {
+                value
+            };
+            lines.push(format!("  {} {:<width$}{}", marker, name, value));
+        }
+        let shown = lines.len();
+        lines.push(tr!("  {}-{} of {}", self.scroll + 1, end, self.rows.len()));
+        lines.push(tr!("  House edge ≈{:.2}%", rules.house_edge()));
+        lines.push(format!("  {}", self.message));
+        lines.push(String::from(tr!("  Table changes apply from the next shoe")));
+        lines.push(String::from(tr!("  [↑/↓] Select  │  [←/→] Change  │  [Enter] Rebind key")));
+        lines.push(tr!("  [Bksp] Reset key  │  [{}] Done", self.keys.menu));
+        (lines, shown)
+    }
+
+    /// Draws the settings in a box of their own on a screen of `size`,
+    /// returning what can be clicked.
+    pub fn draw(&self, out: &mut impl Write, size: (u16, u16)) -> io::Result<Vec<Button>> {
+        let (lines, shown) = self.lines();
+        let palette = self.config.theme.palette().unwrap_or_default();
+        screen::draw_themed_box(out, size, tr!(" SETTINGS "), &lines, shown, &palette)
+    }
+}
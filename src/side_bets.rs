@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+
+use crate::game::{Card, Hand, Rank, Suit};
+use crate::locale::tr;
+use crate::rules::Payout;
+
+// Optional wagers placed alongside the main bet.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum SideBet {
+    LuckyLadies,
+    MatchTheDealer,
+    RoyalMatch,
+    Buster,
+}
+
+// Everything a side bet can be settled against once the round is over.
+pub struct RoundCards<'a> {
+    /// The player's first two cards, before any split.
+    pub player: &'a [Card],
+    pub dealer: &'a Hand,
+    /// The dealer's up-card as it was dealt, before the dealer drew.
+    pub up_card: Option<Card>,
+    pub decks: usize,
+}
+
+impl SideBet {
+    pub const ALL: [SideBet; 4] = [
+        SideBet::LuckyLadies,
+        SideBet::MatchTheDealer,
+        SideBet::RoyalMatch,
+        SideBet::Buster,
+    ];
+
+    /// Returns the side bet's name as shown at the table.
+    pub fn name(&self) -> &'static str {
+        match self {
+            SideBet::LuckyLadies => tr!("Lucky Ladies"),
+            SideBet::MatchTheDealer => tr!("Match the Dealer"),
+            SideBet::RoyalMatch => tr!("Royal Match"),
+            SideBet::Buster => tr!("Buster"),
+        }
+    }
+
+    /// Sums up what the side bet pays with this many decks in the shoe.
+    pub fn paytable(&self, decks: usize) -> String {
+        match self {
+            SideBet::LuckyLadies => String::from(tr!("4:1 for 20, up to 1000:1")),
+            SideBet::MatchTheDealer => {
+                let (suited, unsuited) = match_odds(decks);
+                tr!("{}:1 per match, {}:1 suited", unsuited, suited)
+            }
+            SideBet::RoyalMatch if decks == 1 => String::from(tr!("3:1 suited, 25:1 for K-Q")),
+            SideBet::RoyalMatch => String::from(tr!("5:2 suited, 25:1 for K-Q")),
+            SideBet::Buster => String::from(tr!("2:1 on a dealer bust, up to 250:1")),
+        }
+    }
+
+    /// Returns the odds a winning side bet pays, or None if it lost.
+    pub fn odds(&self, round: &RoundCards) -> Option<Payout> {
+        match self {
+            SideBet::LuckyLadies => lucky_ladies(round).map(|odds| Payout::new(odds, 1)),
+            SideBet::MatchTheDealer => match_the_dealer(round).map(|odds| Payout::new(odds, 1)),
+            SideBet::RoyalMatch => royal_match(round),
+            SideBet::Buster => buster(round).map(|odds| Payout::new(odds, 1)),
+        }
+    }
+}
+
+// Pays on a first-two-card total of 20, with the top tier reserved for
+// a pair of queens of hearts against a dealer blackjack
+fn lucky_ladies(round: &RoundCards) -> Option<u32> {
+    let [first, second] = round.player else {
+        return None;
+    };
+    if first.rank.value() + second.rank.value() != 20 {
+        return None;
+    }
+
+    let queen_of_hearts = |c: &Card| c.rank == Rank::Queen && c.suit == Suit::Hearts;
+    let suited = first.suit == second.suit;
+    let matched = suited && first.rank == second.rank;
+
+    if queen_of_hearts(first) && queen_of_hearts(second) {
+        Some(if round.dealer.is_blackjack() { 1000 } else { 125 })
+    } else if matched {
+        Some(19)
+    } else if suited {
+        Some(9)
+    } else {
+        Some(4)
+    }
+}
+
+// Pays for each of the player's first two cards that matches the dealer's
+// up-card in rank, more for a suited match. Suited matches get rarer as
+// decks are removed, so the paytable shifts with the number of decks.
+fn match_the_dealer(round: &RoundCards) -> Option<u32> {
+    let up_card = round.up_card?;
+    let (suited_odds, unsuited_odds) = match_odds(round.decks);
+
+    let odds: u32 = round
+        .player
+        .iter()
+        .filter(|c| c.rank == up_card.rank)
+        .map(|c| if c.suit == up_card.suit { suited_odds } else { unsuited_odds })
+        .sum();
+
+    (odds > 0).then_some(odds)
+}
+
+// The odds for a suited and an unsuited match
+fn match_odds(decks: usize) -> (u32, u32) {
+    match decks {
+        8.. => (12, 3),
+        6..=7 => (11, 4),
+        4..=5 => (10, 5),
+        _ => (9, 6),
+    }
+}
+
+// Pays on suited first two cards, with a jackpot for a suited King and Queen
+fn royal_match(round: &RoundCards) -> Option<Payout> {
+    let [first, second] = round.player else {
+        return None;
+    };
+    if first.suit != second.suit {
+        return None;
+    }
+
+    let royal = matches!(
+        (first.rank, second.rank),
+        (Rank::King, Rank::Queen) | (Rank::Queen, Rank::King)
+    );
+    if royal {
+        Some(Payout::new(25, 1))
+    } else if round.decks == 1 {
+        Some(Payout::new(3, 1))
+    } else {
+        Some(Payout::new(5, 2))
+    }
+}
+
+// Pays when the dealer busts, more the more cards it took them to get there
+fn buster(round: &RoundCards) -> Option<u32> {
+    if round.dealer.value() <= 21 {
+        return None;
+    }
+
+    match round.dealer.len() {
+        0..=4 => Some(2),
+        5 => Some(4),
+        6 => Some(12),
+        7 => Some(50),
+        _ => Some(250),
+    }
+}
@@ -0,0 +1,251 @@
+use crate::game::{original_bets_refund, Hand, HandStatus, PlayerHand, Rank, Shoe};
+use crate::rules::Rules;
+
+// Every round is played for the same stake, large enough that a 3:2 or 6:5
+// blackjack pays a whole number of chips.
+const STAKE: u32 = 100;
+
+// A decision the simulated player makes on a hand.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Play {
+    Hit,
+    Stand,
+    Double,
+    Split,
+    Surrender,
+}
+
+// What a run of simulated rounds came to.
+#[derive(Debug, Default)]
+pub struct SimResult {
+    pub rounds: u64,
+    pub hands: u64,
+    pub wins: u64,
+    pub losses: u64,
+    pub pushes: u64,
+    pub blackjacks: u64,
+    /// Total won or lost, in chips.
+    pub net: i64,
+}
+
+impl SimResult {
+    /// Returns the house edge the rounds worked out to, in percent of the
+    /// starting bet.
+    pub fn house_edge(&self) -> f64 {
+        if self.rounds == 0 {
+            return 0.0;
+        }
+        -(self.net as f64) / (self.rounds as f64 * STAKE as f64) * 100.0
+    }
+}
+
+// Plays rounds at a table without anyone watching, using basic strategy,
+// to see what a set of rules actually costs.
+pub struct Simulator {
+    rules: Rules,
+    shoe: Shoe,
+}
+
+impl Simulator {
+    /// Sets up a table at the given rules, shuffling the same way every time
+    /// if a seed is given.
+    pub fn new(rules: Rules, seed: Option<u64>) -> Self {
+        let shoe = match seed {
+            Some(seed) => Shoe::seeded(rules.decks, rules.penetration, seed),
+            None => Shoe::new(rules.decks, rules.penetration),
+        };
+        Simulator { rules, shoe }
+    }
+
+    pub fn run(&mut self, rounds: u64) -> SimResult {
+        let mut result = SimResult::default();
+        for _ in 0..rounds {
+            self.play_round(&mut result);
+        }
+        result
+    }
+
+    fn play_round(&mut self, result: &mut SimResult) {
+        if self.shoe.cut_card_reached() {
+            self.shoe.reshuffle();
+        }
+        result.rounds += 1;
+
+        let mut hands = vec![PlayerHand::new(STAKE, 0)];
+        let mut dealer = Hand::new();
+        for i in 0..2 {
+            hands[0].hand.add_card(self.shoe.deal());
+            if i == 0 || !self.rules.no_hole_card {
+                dealer.add_card(self.shoe.deal());
+            }
+        }
+        let up_card = dealer.up_card(self.rules.no_hole_card).map_or(0, |c| c.rank.value());
+
+        if hands[0].hand.is_blackjack() {
+            hands[0].status = HandStatus::Blackjack;
+        }
+        // The dealer peeks under a ten or an ace, settling the round at once
+        let peeks = !self.rules.no_hole_card && up_card >= 10;
+        if !(peeks && dealer.is_blackjack()) {
+            self.play_hands(&mut hands, up_card);
+        }
+
+        let needs_dealer = hands.iter().any(|h| {
+            matches!(h.status, HandStatus::Stood | HandStatus::Active)
+                || (self.rules.no_hole_card && h.status == HandStatus::Blackjack)
+        });
+        if needs_dealer {
+            while dealer.value() < 17
+                || (dealer.value() == 17 && dealer.is_soft() && self.rules.dealer_hits_soft_17)
+            {
+                dealer.add_card(self.shoe.deal());
+            }
+        }
+
+        self.settle(&hands, &dealer, result);
+    }
+
+    fn play_hands(&mut self, hands: &mut Vec<PlayerHand>, up_card: u8) {
+        let mut index = 0;
+        while index < hands.len() {
+            while hands[index].status == HandStatus::Active {
+                let split_hand = hands.len() > 1;
+                let can_double = hands[index].hand.len() == 2
+                    && (!split_hand || self.rules.double_after_split);
+                let can_split = hands[index].hand.is_pair() && hands.len() < self.rules.max_hands;
+                let can_surrender = self.rules.late_surrender
+                    && !self.rules.no_hole_card
+                    && !split_hand
+                    && hands[index].hand.len() == 2;
+
+                let hand = &hands[index].hand;
+                let play = basic_strategy(hand, up_card, can_double, can_split, can_surrender, &self.rules);
+                match play {
+                    Play::Hit => hands[index].add_card(self.shoe.deal()),
+                    Play::Stand => hands[index].status = HandStatus::Stood,
+                    Play::Double => {
+                        hands[index].bet *= 2;
+                        hands[index].add_card(self.shoe.deal());
+                        if hands[index].status == HandStatus::Active {
+                            hands[index].status = HandStatus::Stood;
+                        }
+                    }
+                    Play::Split => {
+                        let aces = hands[index].hand.cards()[0].rank == Rank::Ace;
+                        let mut new_hand = PlayerHand::new(hands[index].bet, 0);
+                        new_hand.hand = hands[index].hand.split();
+                        hands[index].add_card(self.shoe.deal());
+                        new_hand.add_card(self.shoe.deal());
+                        if aces {
+                            let hit = self.rules.hit_split_aces;
+                            let blackjack = self.rules.split_aces_blackjack;
+                            hands[index].close_split_ace(hit, blackjack);
+                            new_hand.close_split_ace(hit, blackjack);
+                        }
+                        hands.insert(index + 1, new_hand);
+                    }
+                    Play::Surrender => hands[index].status = HandStatus::Surrendered,
+                }
+
+                if let Some(cards) = self.rules.charlie
+                    && hands[index].status != HandStatus::Busted
+                    && hands[index].hand.len() >= cards
+                {
+                    hands[index].status = HandStatus::Charlie;
+                }
+            }
+            index += 1;
+        }
+    }
+
+    fn settle(&self, hands: &[PlayerHand], dealer: &Hand, result: &mut SimResult) {
+        let dealer_score = dealer.value();
+        let dealer_blackjack = dealer.is_blackjack();
+
+        // Without a hole card, OBO rules give back what was added to the
+        // original bet when the dealer turns out to have blackjack
+        if self.rules.no_hole_card && self.rules.original_bets_only && dealer_blackjack {
+            result.net += original_bets_refund(hands, STAKE) as i64;
+        }
+
+        for player_hand in hands {
+            result.hands += 1;
+            let bet = player_hand.bet as i64;
+            let player_score = player_hand.hand.value();
+            let net = match player_hand.status {
+                HandStatus::Surrendered => -(bet / 2),
+                HandStatus::Blackjack if dealer_blackjack => 0,
+                HandStatus::Blackjack => {
+                    result.blackjacks += 1;
+                    self.rules.blackjack_payout.winnings(player_hand.bet) as i64
+                }
+                HandStatus::Charlie => bet,
+                _ if player_score > 21 || dealer_blackjack => -bet,
+                _ if dealer_score == 22 && self.rules.push_on_dealer_22 => 0,
+                _ if dealer_score > 21 || player_score > dealer_score => bet,
+                _ if player_score < dealer_score => -bet,
+                _ => 0,
+            };
+            match net {
+                1.. => result.wins += 1,
+                0 => result.pushes += 1,
+                _ => result.losses += 1,
+            }
+            result.net += net;
+        }
+    }
+}
+
+/// Picks a play from the standard multi-deck basic strategy chart, falling
+/// back to hitting or standing when doubling, splitting or surrendering
+/// isn't allowed.
+fn basic_strategy(
+    hand: &Hand,
+    up_card: u8,
+    can_double: bool,
+    can_split: bool,
+    can_surrender: bool,
+    rules: &Rules,
+) -> Play {
+    if can_split {
+        let pair = hand.cards()[0].rank.value();
+        let split = match pair {
+            11 | 8 => true,
+            9 => !matches!(up_card, 7 | 10 | 11),
+            7 => up_card <= 7,
+            6 => up_card <= 6 && (rules.double_after_split || up_card >= 3),
+            4 => rules.double_after_split && matches!(up_card, 5 | 6),
+            3 | 2 => up_card <= 7 && (rules.double_after_split || up_card >= 4),
+            _ => false,
+        };
+        if split {
+            return Play::Split;
+        }
+    }
+
+    let total = hand.value();
+    if hand.is_soft() {
+        return match total {
+            19.. => Play::Stand,
+            18 if (3..=6).contains(&up_card) && can_double => Play::Double,
+            18 if up_card <= 8 => Play::Stand,
+            17 if (3..=6).contains(&up_card) && can_double => Play::Double,
+            15 | 16 if (4..=6).contains(&up_card) && can_double => Play::Double,
+            13 | 14 if (5..=6).contains(&up_card) && can_double => Play::Double,
+            _ => Play::Hit,
+        };
+    }
+
+    if can_surrender && (total == 16 && up_card >= 9 || total == 15 && up_card == 10) {
+        return Play::Surrender;
+    }
+    match total {
+        17.. => Play::Stand,
+        13..=16 if up_card <= 6 => Play::Stand,
+        12 if (4..=6).contains(&up_card) => Play::Stand,
+        11 if can_double => Play::Double,
+        10 if up_card <= 9 && can_double => Play::Double,
+        9 if (3..=6).contains(&up_card) && can_double => Play::Double,
+        _ => Play::Hit,
+    }
+}
@@ -0,0 +1,76 @@
+use serde::Serialize;
+
+use crate::session::HandOutcome;
+
+// Which rounds a set of stats covers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Scope {
+    Session,
+    Lifetime,
+}
+
+// Counts of how hands were settled.
+#[derive(Debug, Default, Serialize)]
+pub struct HandStats {
+    pub hands: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub pushes: u32,
+    pub blackjacks: u32,
+    pub busts: u32,
+}
+
+impl HandStats {
+    /// Counts a number of hands settled the same way. Blackjacks and
+    /// Charlies are wins too, busts and surrenders are losses.
+    pub fn record(&mut self, outcome: HandOutcome, count: u32) {
+        self.hands += count;
+        match outcome {
+            HandOutcome::Win | HandOutcome::Charlie => self.wins += count,
+            HandOutcome::Blackjack => {
+                self.wins += count;
+                self.blackjacks += count;
+            }
+            HandOutcome::Push => self.pushes += count,
+            HandOutcome::Lose | HandOutcome::Surrender => self.losses += count,
+            HandOutcome::Bust => {
+                self.losses += count;
+                self.busts += count;
+            }
+        }
+    }
+}
+
+// Totals over the rounds in a scope, as read back from the history.
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub sessions: u32,
+    pub rounds: u32,
+    /// Chips won or lost over all the rounds.
+    pub net: i64,
+    /// Most won and lost in a single round.
+    pub biggest_win: u32,
+    pub biggest_loss: u32,
+    pub hands: HandStats,
+}
+
+impl Stats {
+    /// Returns the share of hands won, as a percentage.
+    pub fn win_rate(&self) -> f64 {
+        if self.hands.hands == 0 {
+            return 0.0;
+        }
+        self.hands.wins as f64 * 100.0 / self.hands.hands as f64
+    }
+}
+
+// The best sessions across every profile, each as the profile's name and
+// the record it set.
+#[derive(Debug, Default)]
+pub struct Leaderboard {
+    pub biggest_bankroll: Vec<(String, i64)>,
+    pub longest_streak: Vec<(String, i64)>,
+    pub best_net: Vec<(String, i64)>,
+    /// Scores in today's daily challenge.
+    pub daily: Vec<(String, i64)>,
+}
@@ -0,0 +1,398 @@
+use crossterm::{
+    Command, queue,
+    style::{Color, Colors, Print, SetColors, SetForegroundColor},
+};
+use serde::{Deserialize, Deserializer, Serialize, de};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+use std::str::FromStr;
+use unicode_width::UnicodeWidthStr;
+
+use crate::big_text;
+use crate::game::{Card, Hand, Suit};
+
+// The looks for the table picked in the settings: the built-in ones, or
+// one of the player's own from a theme file.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Theme {
+    /// A green felt table with gold edges.
+    Classic,
+    /// The terminal's own colors, with the cards and results in color.
+    #[default]
+    Dark,
+    /// Dark text on a white table.
+    Light,
+    /// No colors at all.
+    Monochrome,
+    /// Blue and orange in place of green and red, for deuteranopia.
+    Deuteranopia,
+    /// Blue and yellow in place of green and red, with the red suits bright
+    /// enough to tell from the black ones, for protanopia.
+    Protanopia,
+    /// Bright colors on black, as far apart as they go.
+    HighContrast,
+    /// A theme file in the themes directory, by its name without `.toml`.
+    File(String),
+}
+
+impl Theme {
+    pub const ALL: [Theme; 7] = [
+        Theme::Classic,
+        Theme::Dark,
+        Theme::Light,
+        Theme::Monochrome,
+        Theme::Deuteranopia,
+        Theme::Protanopia,
+        Theme::HighContrast,
+    ];
+
+    /// Returns the name used for the theme in the config file.
+    pub fn name(&self) -> &str {
+        match self {
+            Theme::Classic => "classic",
+            Theme::Dark => "dark",
+            Theme::Light => "light",
+            Theme::Monochrome => "monochrome",
+            Theme::Deuteranopia => "deuteranopia",
+            Theme::Protanopia => "protanopia",
+            Theme::HighContrast => "high-contrast",
+            Theme::File(name) => name,
+        }
+    }
+
+    /// Returns where theme files are kept, e.g.
+    /// `~/.config/rustjack/themes/solarized.toml`.
+    pub fn dir() -> Option<PathBuf> {
+        Some(dirs::config_dir()?.join("rustjack").join("themes"))
+    }
+
+    /// Returns every theme there is to pick, the built-in ones first and
+    /// then the theme files by name.
+    pub fn available() -> io::Result<Vec<Theme>> {
+        let mut files = Vec::new();
+        if let Some(dir) = Self::dir() {
+            match fs::read_dir(dir) {
+                Ok(entries) => {
+                    for entry in entries {
+                        let path = entry?.path();
+                        if path.extension().is_some_and(|ext| ext == "toml")
+                            && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+                            && !Theme::ALL.iter().any(|theme| theme.name() == name)
+                        {
+                            files.push(name.to_string());
+                        }
+                    }
+                }
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => return Err(err),
+            }
+        }
+        files.sort();
+        let mut themes = Theme::ALL.to_vec();
+        themes.extend(files.into_iter().map(Theme::File));
+        Ok(themes)
+    }
+
+    /// Returns the colors the theme draws with, reading them from its file
+    /// for the player's own themes.
+    pub fn palette(&self) -> io::Result<Palette> {
+        Ok(match self {
+            Theme::Classic => Palette {
+                background: Color::DarkGreen,
+                text: Color::White,
+                border: Color::DarkYellow,
+                accent: Color::Yellow,
+                red_suit: Color::Red,
+                black_suit: Color::Black,
+                win: Color::Yellow,
+                loss: Color::Red,
+                push: Color::Cyan,
+                chip_colors: true,
+                emoji_suits: false,
+            },
+            Theme::Dark => Palette::default(),
+            Theme::Light => Palette {
+                background: Color::White,
+                text: Color::Black,
+                border: Color::DarkGrey,
+                accent: Color::DarkBlue,
+                red_suit: Color::DarkRed,
+                black_suit: Color::Black,
+                win: Color::DarkGreen,
+                loss: Color::DarkRed,
+                push: Color::DarkYellow,
+                chip_colors: true,
+                emoji_suits: false,
+            },
+            Theme::Monochrome => Palette {
+                red_suit: Color::Reset,
+                black_suit: Color::Reset,
+                win: Color::Reset,
+                loss: Color::Reset,
+                push: Color::Reset,
+                chip_colors: false,
+                ..Palette::default()
+            },
+            // The colors are from the terminal's 256-color palette, near
+            // Okabe and Ito's set that reads the same to most color vision
+            Theme::Deuteranopia => Palette {
+                red_suit: Color::AnsiValue(208),
+                win: Color::AnsiValue(75),
+                loss: Color::AnsiValue(208),
+                push: Color::Grey,
+                ..Palette::default()
+            },
+            Theme::Protanopia => Palette {
+                red_suit: Color::AnsiValue(214),
+                win: Color::AnsiValue(33),
+                loss: Color::AnsiValue(220),
+                push: Color::Grey,
+                ..Palette::default()
+            },
+            Theme::HighContrast => Palette {
+                background: Color::Black,
+                text: Color::White,
+                border: Color::White,
+                accent: Color::Yellow,
+                red_suit: Color::Magenta,
+                black_suit: Color::White,
+                win: Color::Cyan,
+                loss: Color::Magenta,
+                push: Color::White,
+                chip_colors: false,
+                emoji_suits: false,
+            },
+            Theme::File(name) => {
+                let path = Self::dir()
+                    .ok_or_else(|| io::Error::other("no config directory on this platform"))?
+                    .join(format!("{}.toml", name));
+                let contents = fs::read_to_string(&path).map_err(|err| {
+                    io::Error::new(err.kind(), format!("can't read theme file {}: {}", path.display(), err))
+                })?;
+                toml::from_str(&contents).map_err(|err| {
+                    io::Error::other(format!("invalid theme file {}: {}", path.display(), err))
+                })?
+            }
+        })
+    }
+}
+
+impl fmt::Display for Theme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+// Any name that isn't a built-in theme is taken to be a theme file's.
+impl FromStr for Theme {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if let Some(theme) = Theme::ALL.iter().find(|theme| theme.name() == s) {
+            return Ok(theme.clone());
+        }
+        if s.is_empty() || s.contains(['/', '\\', '.']) {
+            return Err(format!("invalid theme name '{}'", s));
+        }
+        Ok(Theme::File(s.to_string()))
+    }
+}
+
+// Lets the config file store themes by name.
+impl TryFrom<String> for Theme {
+    type Error = String;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl From<Theme> for String {
+    fn from(theme: Theme) -> Self {
+        theme.name().to_string()
+    }
+}
+
+// The colors a theme draws each part of the screen in. Theme files name
+// the colors they change, leaving the rest as in the dark theme.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Palette {
+    #[serde(deserialize_with = "color")]
+    pub background: Color,
+    #[serde(deserialize_with = "color")]
+    pub text: Color,
+    /// The lines boxes are drawn with.
+    #[serde(deserialize_with = "color")]
+    pub border: Color,
+    /// Titles.
+    #[serde(deserialize_with = "color")]
+    pub accent: Color,
+    #[serde(deserialize_with = "color")]
+    pub red_suit: Color,
+    #[serde(deserialize_with = "color")]
+    pub black_suit: Color,
+    /// Rounds that won chips, and hands that make 21.
+    #[serde(deserialize_with = "color")]
+    pub win: Color,
+    /// Rounds that lost chips, and busted hands.
+    #[serde(deserialize_with = "color")]
+    pub loss: Color,
+    /// Rounds that broke even.
+    #[serde(deserialize_with = "color")]
+    pub push: Color,
+    /// Whether chips are drawn in their own colors, or in reverse.
+    pub chip_colors: bool,
+    /// Whether suits are drawn as emoji. It's picked in the settings rather
+    /// than by the theme.
+    #[serde(skip)]
+    pub emoji_suits: bool,
+}
+
+impl Default for Palette {
+    fn default() -> Self {
+        Palette {
+            background: Color::Reset,
+            text: Color::Reset,
+            border: Color::Reset,
+            accent: Color::Reset,
+            red_suit: Color::Red,
+            black_suit: Color::White,
+            win: Color::Green,
+            loss: Color::Red,
+            push: Color::Yellow,
+            chip_colors: true,
+            emoji_suits: false,
+        }
+    }
+}
+
+impl Palette {
+    /// Switches to the theme's text and background, ready to clear the
+    /// screen with them.
+    pub fn apply(&self) -> SetColors {
+        SetColors(Colors::new(self.text, self.background))
+    }
+
+    /// Hearts and diamonds are red, clubs and spades black.
+    pub fn suit(&self, suit: Suit) -> Color {
+        match suit {
+            Suit::Hearts | Suit::Diamonds => self.red_suit,
+            Suit::Clubs | Suit::Spades => self.black_suit,
+        }
+    }
+
+    /// Returns the text wrapped in the codes that draw it in a color and
+    /// then go back to the theme's text color.
+    pub fn paint(&self, text: &str, color: Color) -> String {
+        let mut painted = String::new();
+        // Writing into a string can't fail
+        let _ = SetForegroundColor(color).write_ansi(&mut painted);
+        painted.push_str(text);
+        let _ = SetForegroundColor(self.text).write_ansi(&mut painted);
+        painted
+    }
+
+    /// Writes the text in a color as `paint` does, straight to `out`
+    /// rather than into a string of its own.
+    pub fn write(&self, out: &mut impl io::Write, text: &str, color: Color) -> io::Result<()> {
+        queue!(out, SetForegroundColor(color), Print(text), SetForegroundColor(self.text))
+    }
+
+    /// A suit's symbol, or its emoji if those are picked.
+    pub fn suit_symbol(&self, suit: Suit) -> &'static str {
+        if self.emoji_suits { suit.emoji() } else { suit.symbol() }
+    }
+
+    /// A card as text, such as "10♥", in its suit's color.
+    pub fn card(&self, card: &Card) -> String {
+        let text = format!("{}{}", card.rank.label(), self.suit_symbol(card.suit));
+        self.paint(&text, self.suit(card.suit))
+    }
+
+    /// The cards in a hand, in their suits' colors.
+    pub fn cards(&self, hand: &Hand) -> String {
+        hand.cards().iter().map(|card| self.card(card)).collect::<Vec<String>>().join(" ")
+    }
+
+    /// A hand's value, in the win color when it makes 21 and the loss color
+    /// once it's bust.
+    pub fn total(&self, hand: &Hand) -> String {
+        let value = hand.total();
+        match value.total {
+            21 => self.paint(&value.to_string(), self.win),
+            22.. => self.paint(&value.to_string(), self.loss),
+            _ => value.to_string(),
+        }
+    }
+
+    /// A hand's value in big text, a line per line, colored as `total`
+    /// colors it.
+    pub fn big_total(&self, hand: &Hand) -> Vec<String> {
+        let value = hand.total();
+        let lines = big_text::lines(&value.to_string());
+        match value.total {
+            21 => lines.iter().map(|line| self.paint(line, self.win)).collect(),
+            22.. => lines.iter().map(|line| self.paint(line, self.loss)).collect(),
+            _ => lines,
+        }
+    }
+
+    /// The color for a round that won chips, lost them or broke even.
+    pub fn outcome(&self, net: i64) -> Color {
+        match net {
+            1.. => self.win,
+            0 => self.push,
+            _ => self.loss,
+        }
+    }
+}
+
+/// Parses a color in a theme file: a name such as "dark_red", "default" for
+/// the terminal's own, a hex code like "#1e90ff", or a number from the
+/// terminal's 256-color palette.
+fn parse_color(s: &str) -> Result<Color, String> {
+    let invalid = || format!("invalid color '{}'", s);
+    if s == "default" {
+        return Ok(Color::Reset);
+    }
+    if let Some(hex) = s.strip_prefix('#') {
+        let channel = |i: usize| {
+            hex.get(i..i + 2)
+                .and_then(|c| u8::from_str_radix(c, 16).ok())
+                .ok_or_else(invalid)
+        };
+        if hex.len() != 6 {
+            return Err(invalid());
+        }
+        return Ok(Color::Rgb { r: channel(0)?, g: channel(2)?, b: channel(4)? });
+    }
+    if let Ok(value) = s.parse() {
+        return Ok(Color::AnsiValue(value));
+    }
+    Color::try_from(s).map_err(|()| invalid())
+}
+
+fn color<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_color(&s).map_err(de::Error::custom)
+}
+
+/// Returns the columns text takes up on screen, leaving out the codes that
+/// color it. The text between codes is measured a run at a time, so that a
+/// suit and the selector after it that makes it an emoji count as one.
+pub fn width(text: &str) -> usize {
+    let mut width = 0;
+    let mut rest = text;
+    while let Some(start) = rest.find('\x1b') {
+        width += rest[..start].width();
+        // A code runs from the escape to its closing letter
+        let code = &rest[start + 1..];
+        let end = code.find(|c: char| c.is_ascii_alphabetic()).map_or(code.len(), |i| i + 1);
+        rest = &code[end..];
+    }
+    width + rest.width()
+}
@@ -0,0 +1,71 @@
+use crossterm::{
+    cursor,
+    event::{Event, MouseEventKind},
+    queue,
+    style::{Color, Print, ResetColor, SetForegroundColor},
+    terminal::{Clear, ClearType},
+};
+use std::io;
+use unicode_width::UnicodeWidthStr;
+
+use crate::backend::Backend;
+use crate::locale::tr;
+
+const LOGO: [&str; 5] = [
+    r" ____   _   _  ____   _____      _     _      ____  _  __",
+    r"|  _ \ | | | |/ ___| |_   _|    | |   / \    / ___|| |/ /",
+    r"| |_) || | | |\___ \   | |   _  | |  / _ \  | |    | ' /",
+    r"|  _ < | |_| | ___) |  | |  | |_| | / ___ \ | |___ | . \",
+    r"|_| \_\ \___/ |____/   |_|   \___/ /_/   \_\ \____||_|\_\",
+];
+
+/// Shows the logo and version when the game starts, until a key is
+/// pressed or the screen is clicked.
+pub fn show(backend: &mut impl Backend) -> io::Result<()> {
+    backend.enter()?;
+
+    loop {
+        draw(backend)?;
+        match backend.read()? {
+            Event::Key(_) => break,
+            Event::Mouse(mouse) if matches!(mouse.kind, MouseEventKind::Down(_)) => break,
+            _ => {}
+        }
+    }
+
+    backend.leave()
+}
+
+fn draw(backend: &mut impl Backend) -> io::Result<()> {
+    queue!(backend, Clear(ClearType::All))?;
+
+    let (term_width, term_height) = backend.size()?;
+    let logo_width = LOGO.iter().map(|line| line.len()).max().unwrap_or(0) as u16;
+    let logo_x = term_width.saturating_sub(logo_width) / 2;
+    let start_y = term_height.saturating_sub(LOGO.len() as u16 + 7) / 2;
+
+    queue!(backend, SetForegroundColor(Color::Red))?;
+    for (i, line) in LOGO.iter().enumerate() {
+        queue!(backend, cursor::MoveTo(logo_x, start_y + i as u16), Print(line))?;
+    }
+    queue!(backend, ResetColor)?;
+
+    // The suits, with the red ones in red, then the version under them
+    let suits = [("♠", Color::White), ("♥", Color::Red), ("♣", Color::White), ("♦", Color::Red)];
+    let suits_x = term_width.saturating_sub(7) / 2;
+    queue!(backend, cursor::MoveTo(suits_x, start_y + LOGO.len() as u16 + 1))?;
+    for (suit, color) in suits {
+        queue!(backend, SetForegroundColor(color), Print(suit), Print(" "))?;
+    }
+    queue!(backend, ResetColor)?;
+
+    let lines = [
+        (2, tr!("Blackjack in the terminal  │  v{}", env!("CARGO_PKG_VERSION"))),
+        (5, String::from(tr!("Press any key to start"))),
+    ];
+    for (offset, text) in lines {
+        let x = term_width.saturating_sub(UnicodeWidthStr::width(text.as_str()) as u16) / 2;
+        queue!(backend, cursor::MoveTo(x, start_y + LOGO.len() as u16 + offset), Print(text))?;
+    }
+    backend.flush()
+}
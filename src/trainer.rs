@@ -0,0 +1,237 @@
+use crossterm::event::{Event, KeyCode, KeyEvent};
+use std::io;
+use std::time::{Duration, Instant};
+
+use crate::backend::Backend;
+use crate::card_art::{self, CardBack, CardSize};
+use crate::config::Config;
+use crate::count::{QuizRecord, System};
+use crate::game::{Card, Shoe};
+use crate::keys::KeyBindings;
+use crate::locale::{self, tr};
+use crate::screen;
+use crate::theme::Palette;
+
+// Cards shown between checks on the count
+const CARDS_PER_CHECK: usize = 20;
+// How the cards are drawn, big enough to be read at a glance
+const CARD_SIZE: CardSize = CardSize::Large;
+// Columns the box's lines are laid out in
+const WIDTH: usize = 58;
+
+// A check on the count: what's been typed, and once it's been answered,
+// how far off it was.
+struct Check {
+    typed: String,
+    off_by: Option<u32>,
+}
+
+// The counting trainer, a drill with no betting: cards from a shoe are
+// shown one at a time, each for a while, and every so often the player is
+// asked what the count has come to.
+pub struct Trainer {
+    profile: String,
+    shoe: Shoe,
+    system: System,
+    // How long each card's shown
+    speed: Duration,
+    palette: Palette,
+    card_back: CardBack,
+    keys: KeyBindings,
+    // The card showing, and how many have been since the last check
+    card: Option<Card>,
+    shown: usize,
+    check: Option<Check>,
+    paused: bool,
+    // Anything to tell the player, such as that the shoe's been shuffled
+    message: String,
+    // How the checks have gone this time, and every time
+    session: QuizRecord,
+    record: QuizRecord,
+}
+
+impl Trainer {
+    pub fn new(profile: &str, config: &Config, decks: usize, keys: KeyBindings) -> Self {
+        Trainer {
+            profile: profile.to_string(),
+            shoe: Shoe::new(decks, 1.0),
+            system: config.count_system,
+            speed: config.trainer_speed(),
+            palette: config.theme.palette().unwrap_or_default(),
+            card_back: config.card_back,
+            keys,
+            card: None,
+            shown: 0,
+            check: None,
+            paused: false,
+            message: String::new(),
+            session: QuizRecord::default(),
+            record: config.count_drill.clone(),
+        }
+    }
+
+    /// Shows cards until the player stops.
+    pub fn run(&mut self, backend: &mut impl Backend) -> io::Result<()> {
+        backend.enter()?;
+
+        let mut next_card = Instant::now();
+        loop {
+            let now = Instant::now();
+            if self.flashing() && now >= next_card {
+                self.next_card();
+                next_card = now + self.speed;
+            }
+            let size = backend.size()?;
+            screen::draw_themed_box(backend, size, tr!(" COUNTING TRAINER "), &self.lines(), 0, &self.palette)?;
+
+            // Between cards only waits as long as the card's left to show
+            if self.flashing() && !backend.poll(next_card.saturating_duration_since(Instant::now()))? {
+                continue;
+            }
+            let was_flashing = self.flashing();
+            if let Event::Key(KeyEvent { code, .. }) = backend.read()?
+                && self.handle_input(code)
+            {
+                break;
+            }
+            // Going back to the cards shows the next one straight away
+            if !was_flashing && self.flashing() {
+                next_card = Instant::now();
+            }
+        }
+
+        backend.leave()
+    }
+
+    // Whether cards are being shown, rather than paused or checking the count
+    fn flashing(&self) -> bool {
+        !self.paused && self.check.is_none()
+    }
+
+    // Shows the next card, or checks the count once enough have been shown
+    // or the shoe's run out
+    fn next_card(&mut self) {
+        if self.shown == CARDS_PER_CHECK || self.shoe.remaining() == 0 {
+            self.card = None;
+            self.check = Some(Check { typed: String::new(), off_by: None });
+            self.message = String::new();
+            return;
+        }
+        self.card = Some(self.shoe.deal());
+        self.shown += 1;
+    }
+
+    /// Returns true once the player is done.
+    fn handle_input(&mut self, key: KeyCode) -> bool {
+        if self.keys.menu.matches(key) || self.keys.quit.matches(key) {
+            return true;
+        }
+        let Some(check) = &mut self.check else {
+            if key == KeyCode::Char(' ') {
+                self.paused = !self.paused;
+            }
+            return false;
+        };
+        if check.off_by.is_some() {
+            if self.keys.deal.matches(key) {
+                self.check = None;
+                self.shown = 0;
+                if self.shoe.cut_card_reached() || self.shoe.remaining() < CARDS_PER_CHECK {
+                    self.shoe.reshuffle();
+                    self.message = String::from(tr!("The shoe's been shuffled, so the count starts again."));
+                }
+            }
+            return false;
+        }
+        match key {
+            KeyCode::Char(c @ '0'..='9') if check.typed.trim_start_matches('-').len() < 3 => check.typed.push(c),
+            KeyCode::Char('-') => match check.typed.strip_prefix('-') {
+                Some(typed) => check.typed = typed.to_string(),
+                None => check.typed.insert(0, '-'),
+            },
+            _ if self.keys.undo.matches(key) => {
+                check.typed.pop();
+            }
+            _ if self.keys.deal.matches(key) => {
+                let Ok(answer) = check.typed.parse::<i32>() else {
+                    return false;
+                };
+                let off_by = answer.abs_diff(self.shoe.running_count(self.system.counting()));
+                check.off_by = Some(off_by);
+                self.session.record(off_by);
+                self.record.record(off_by);
+                self.save_record();
+            }
+            _ => {}
+        }
+        false
+    }
+
+    // Keeps how the checks have gone in the profile's config
+    fn save_record(&mut self) {
+        let saved = Config::load(&self.profile).and_then(|mut config| {
+            config.count_drill = self.record.clone();
+            config.save(&self.profile)
+        });
+        if let Err(err) = saved {
+            self.message = tr!("Score not saved: {}", err);
+        }
+    }
+
+    // The lines the box shows: the card in the middle, or the check in
+    // its place, with how the checks have gone under it
+    fn lines(&self) -> Vec<String> {
+        let name = self.system.counting().name();
+        let decks = match self.shoe.size() / 52 {
+            1 => String::from(tr!("1 deck")),
+            n => tr!("{} decks", n),
+        };
+        let mut lines = vec![
+            tr!("  Keep the {} count of every card shown.", name),
+            tr!("  {}  │  {}s a card", decks, locale::decimal(self.speed.as_secs_f64(), 1)),
+            String::new(),
+        ];
+
+        let cards: Vec<Option<&Card>> = self.card.iter().map(Some).collect();
+        let margin = " ".repeat((WIDTH - CARD_SIZE.width()) / 2);
+        for line in card_art::row(&cards, CARD_SIZE.width(), self.card_back, CARD_SIZE, &self.palette) {
+            lines.push(format!("{}{}", margin, line));
+        }
+        lines.push(String::new());
+
+        let count = self.shoe.running_count(self.system.counting());
+        match &self.check {
+            None if self.paused => lines.push(String::from(tr!("  Paused"))),
+            None => lines.push(tr!("  Card {} of {} before the count's checked", self.shown, CARDS_PER_CHECK)),
+            Some(Check { typed, off_by: None }) => {
+                let typed = if typed.is_empty() { "_" } else { typed };
+                lines.push(tr!("  What's the {} count?  {}", name, typed));
+            }
+            Some(Check { off_by: Some(0), .. }) => lines.push(tr!("  Spot on, the {} count was {:+}.", name, count)),
+            Some(Check { off_by: Some(off_by), .. }) => {
+                lines.push(tr!("  The {} count was {:+}, you were {} off.", name, count, off_by));
+            }
+        }
+        lines.push(format!("  {}", self.message));
+        for (label, record) in [(tr!("This time"), &self.session), (tr!("Every time"), &self.record)] {
+            lines.push(tr!(
+                "  {}: spot on {} of {}, {} off on average",
+                label,
+                record.right,
+                record.answered,
+                locale::decimal(record.average_miss(), 1)
+            ));
+        }
+        lines.push(String::new());
+
+        let keys = &self.keys;
+        lines.push(match &self.check {
+            None => tr!("  [Space] Pause  │  [{}] Stop", keys.menu),
+            Some(Check { off_by: None, .. }) => {
+                tr!("  [0-9] Count  │  [-] Minus  │  [{}] Answer  │  [{}] Stop", keys.deal, keys.menu)
+            }
+            Some(_) => tr!("  [{}] Carry on  │  [{}] Stop", keys.deal, keys.menu),
+        });
+        lines
+    }
+}
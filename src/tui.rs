@@ -0,0 +1,480 @@
+use crossterm::{
+    queue,
+    style::ResetColor,
+    terminal::{self, ClearType},
+};
+use ratatui::{
+    Frame, Terminal,
+    backend::{CrosstermBackend, FromCrossterm},
+    layout::{Constraint, Flex, Layout as Split, Rect},
+    style::{Color, Modifier, Style},
+    symbols::merge::MergeStrategy,
+    text::{Line, Span},
+    widgets::{Block, Clear, Paragraph},
+};
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::mem;
+use std::rc::Rc;
+use unicode_width::UnicodeWidthStr;
+
+use crate::animation::Seat;
+use crate::backend::Backend;
+use crate::big_text;
+use crate::buffer;
+use crate::card_art::{self, CardSize};
+use crate::game::Card;
+use crate::locale::{self, tr};
+use crate::mouse;
+use crate::{
+    GameState, GameUI, Layout, Popup, LOG_PANEL_LINES, LOG_STRIP_LINES, MIN_WIDTH, PANEL_WIDTH, POPUP_WIDTH,
+    SHOE_GAUGE_WIDTH, WIDE_TABLE_WIDTH,
+};
+
+/// The ratatui terminal the table is drawn with, writing into memory so
+/// that each frame goes to the screen and any recording of the game alike.
+pub struct Tui {
+    terminal: Terminal<CrosstermBackend<Output>>,
+    output: Output,
+    // The size of the terminal it was made for
+    size: (u16, u16),
+}
+
+impl Tui {
+    fn new(size: (u16, u16)) -> io::Result<Self> {
+        let mut output = Output::default();
+        // Whatever's on screen was drawn without it, so it starts from a
+        // blank screen. Its own clearing asks the terminal where the cursor
+        // is, which not every terminal answers.
+        queue!(output, ResetColor, terminal::Clear(ClearType::All))?;
+        let terminal = Terminal::new(CrosstermBackend::new(output.clone()))?;
+        Ok(Tui { terminal, output, size })
+    }
+}
+
+// What the terminal's written since it was last taken, shared with the
+// backend writing it
+#[derive(Clone, Default)]
+struct Output(Rc<RefCell<Vec<u8>>>);
+
+impl Write for Output {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.borrow_mut().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+// The parts of the table from top to bottom, each in a box sharing its
+// border with the next
+struct Section {
+    title: Option<Line<'static>>,
+    // A note on the right of the border above it, such as that the shoe's
+    // about to be shuffled
+    note: Option<Line<'static>>,
+    lines: Vec<Line<'static>>,
+}
+
+impl Section {
+    fn new(lines: Vec<Line<'static>>) -> Self {
+        Section { title: None, note: None, lines }
+    }
+
+    fn titled(title: &str, lines: Vec<Line<'static>>) -> Self {
+        Section { title: Some(Line::from(format!(" {} ", title))), ..Section::new(lines) }
+    }
+
+    // Lines it takes, with its borders
+    fn height(&self) -> u16 {
+        self.lines.len() as u16 + 2
+    }
+}
+
+// Returns a color in ratatui's terms, None for the terminal's own so that
+// what's drawn over keeps its color
+fn color(color: crossterm::style::Color) -> Option<Color> {
+    (color != crossterm::style::Color::Reset).then(|| Color::from_crossterm(color))
+}
+
+// Turns text colored with the codes the classic table is drawn with, such
+// as a hand's cards in their suits' colors, into ratatui's spans
+fn line(text: &str) -> Line<'static> {
+    let mut style = buffer::Style::default();
+    let mut spans = Vec::new();
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = rest.find('\x1b').unwrap_or(rest.len());
+        if end > 0 {
+            let mut span_style = Style::new();
+            span_style.fg = color(style.fg);
+            span_style.bg = color(style.bg);
+            if style.bold {
+                span_style = span_style.add_modifier(Modifier::BOLD);
+            }
+            if style.reverse {
+                span_style = span_style.add_modifier(Modifier::REVERSED);
+            }
+            spans.push(Span::styled(rest[..end].to_string(), span_style));
+        }
+        // A code runs from the escape to its closing letter
+        let code = rest[end..].strip_prefix("\x1b[").unwrap_or_default();
+        let Some(close) = code.find(|c: char| c.is_ascii_alphabetic()) else {
+            break;
+        };
+        if code[close..].starts_with('m') {
+            style.apply_sgr(&code[..close]);
+        }
+        rest = &code[close + 1..];
+    }
+    Line::from(spans)
+}
+
+impl GameUI {
+    /// Draws the frame with ratatui if it shows the table, returning false
+    /// to leave the screens over it, such as the settings, to `render`.
+    pub fn draw_tui(&mut self, backend: &mut impl Backend) -> io::Result<bool> {
+        let (width, height) = self.size;
+        let covered = self.settings.is_some()
+            || self.help_scroll.is_some()
+            || self.log_scroll.is_some()
+            || self.pause_menu.is_some()
+            || self.stats_tab.is_some()
+            || self.history_view.is_some();
+        let Some(layout) = Layout::fitting(width, height, self.large_print).filter(|_| !covered) else {
+            // The classic screens start afresh after ratatui's
+            if self.tui.take().is_some() {
+                self.screen.forget();
+            }
+            return Ok(false);
+        };
+
+        // It starts afresh when the terminal's resized, rather than resizing
+        // itself, for the same reason it doesn't clear the screen
+        let mut tui = match self.tui.take() {
+            Some(tui) if tui.size == (width, height) => tui,
+            _ => Tui::new((width, height))?,
+        };
+        self.buttons.borrow_mut().clear();
+        tui.terminal.draw(|frame| self.draw_table(frame, layout))?;
+        let output = mem::take(&mut *tui.output.0.borrow_mut());
+        self.tui = Some(tui);
+        crate::write_frame(backend, self.cast.as_mut(), &output)?;
+        Ok(true)
+    }
+
+    // The theme's text on its background
+    fn base_style(&self) -> Style {
+        let mut style = Style::new();
+        style.fg = color(self.palette.text);
+        style.bg = color(self.palette.background);
+        style
+    }
+
+    // Text in one of the theme's colors
+    fn colored(&self, c: crossterm::style::Color) -> Style {
+        let mut style = Style::new();
+        style.fg = color(c);
+        style
+    }
+
+    // Lays the table out in the middle of the frame, the wide one between
+    // its panels, with any popup over it
+    fn draw_table(&self, frame: &mut Frame, layout: Layout) {
+        let area = frame.area();
+        frame.render_widget(Block::new().style(self.base_style()), area);
+
+        let width = match layout {
+            Layout::Cards | Layout::Text => 60,
+            Layout::Compact => MIN_WIDTH,
+            Layout::Wide | Layout::LargePrint => WIDE_TABLE_WIDTH,
+        };
+        let inner_width = (width - 2) as usize;
+        let sections = match layout {
+            Layout::Compact => self.compact_sections(inner_width),
+            _ => self.sections(inner_width, layout.card_size()),
+        };
+        // Boxes share a line of border with the next
+        let height = sections.iter().map(Section::height).sum::<u16>() - (sections.len() as u16 - 1);
+
+        // The event log goes in a strip under the table where there's room
+        let strip_height = LOG_STRIP_LINES as u16 + 2;
+        let strip = self.has_log_strip(layout, height);
+        let row_height = if strip { height + strip_height } else { height };
+
+        let [row] = Split::vertical([Constraint::Length(row_height)]).flex(Flex::Center).areas(area);
+        let table = if layout == Layout::Wide {
+            let [session, table, shoe] = Split::horizontal([
+                Constraint::Length(PANEL_WIDTH),
+                Constraint::Length(width),
+                Constraint::Length(PANEL_WIDTH),
+            ])
+            .spacing(1)
+            .flex(Flex::Center)
+            .areas(row);
+            self.draw_panels(frame, session, shoe);
+            table
+        } else {
+            let [table] = Split::horizontal([Constraint::Length(width)]).flex(Flex::Center).areas(row);
+            table
+        };
+
+        let areas = Split::vertical(sections.iter().map(|section| Constraint::Length(section.height())))
+            .spacing(-1)
+            .split(table);
+        let border = self.colored(self.palette.border);
+        for (i, (section, &area)) in sections.into_iter().zip(areas.iter()).enumerate() {
+            let mut block = Block::bordered().border_style(border).merge_borders(MergeStrategy::Exact);
+            if i == 0 {
+                let title = Line::from(" ♠ BLACKJACK ♥ ").style(self.colored(self.palette.accent));
+                block = block.title(title.centered());
+            }
+            if let Some(title) = section.title {
+                block = block.title(title);
+            }
+            if let Some(note) = section.note {
+                block = block.title(note.right_aligned());
+            }
+            // The key hints are the last box, and can be clicked
+            if i == areas.len() - 1 {
+                let inner = block.inner(area);
+                for (y, text) in (inner.y..).zip(&section.lines) {
+                    let text: String = text.spans.iter().map(|span| span.content.as_ref()).collect();
+                    self.buttons.borrow_mut().extend(mouse::key_hints(&text, inner.x, y));
+                }
+            }
+            frame.render_widget(Paragraph::new(section.lines).block(block), area);
+        }
+
+        if strip {
+            let (title, lines) = self.strip_panel(inner_width);
+            let area = Rect { y: table.y + height, height: strip_height, ..table }.intersection(frame.area());
+            frame.render_widget(self.panel(&title, lines).0, area);
+        }
+
+        if let Some(size) = layout.card_size() {
+            self.draw_moving_cards(frame, table, areas[1], areas[2], size);
+        }
+
+        // Whatever the cards lead to waits until they've all landed
+        if self.dealing.is_some() {
+            return;
+        }
+        if self.confirm_quit {
+            self.draw_popup_box(frame, &self.quit_popup());
+        } else if let Some(popup) = self.table_popup() {
+            self.draw_popup_box(frame, &popup);
+        }
+    }
+
+    // The rules, the dealer's hand, the player's and the keys to press
+    fn sections(&self, inner_width: usize, card_size: Option<CardSize>) -> Vec<Section> {
+        let lines = |lines: Vec<String>| lines.iter().map(|text| line(text)).collect::<Vec<_>>();
+        let width = inner_width - 4;
+        let large_print = card_size == Some(CardSize::Huge);
+        let rules = Section::new(lines(self.wrap_items(&self.rules_info(), inner_width)));
+
+        let dealer_hand = self.shown_hand(Seat::Dealer, &self.dealer_hand);
+        let hidden = self.hole_card_face_down();
+        let mut dealer = match card_size {
+            Some(size) => {
+                let cards: Vec<Option<&Card>> = dealer_hand
+                    .cards()
+                    .iter()
+                    .enumerate()
+                    .map(|(i, card)| (i > 0 || !hidden).then_some(card))
+                    .collect();
+                card_art::row(&cards, width, self.card_back, size, &self.palette)
+                    .iter()
+                    .map(|card_line| line(&format!("  {}", card_line)))
+                    .collect()
+            }
+            None if hidden => {
+                let cards = self.palette.cards(&dealer_hand);
+                let visible = cards.split_once(' ').map(|(_, rest)| rest).unwrap_or("");
+                vec![line(&tr!("  Cards: [??] {}", visible))]
+            }
+            None => vec![line(&tr!("  Cards: {}", self.palette.cards(&dealer_hand)))],
+        };
+        dealer.extend(lines(self.value_lines(&dealer_hand, hidden, large_print)));
+        let mut dealer = Section::titled(tr!("DEALER"), dealer);
+        let gauge = tr!(" Shoe {} ", self.shoe_gauge(SHOE_GAUGE_WIDTH));
+        dealer.note = Some(Line::from(match self.shoe.cut_card_reached() {
+            true => format!("{}{}", tr!(" Shuffle coming "), gauge),
+            false => gauge,
+        }));
+
+        let mut player = Vec::new();
+        if let GameState::Betting = self.state {
+            player.push(line(&tr!("  Place your bet: {}", locale::money(self.bet_chips.total()))));
+            player.push(self.chip_line(inner_width));
+            player.extend(self.wrap_items(&self.bet_notes(), inner_width).iter().map(|note| line(note)));
+            player.extend(self.spot_markers(width).map(|markers| line(&markers)));
+            // Keep the table the same height as when the cards are out
+            if let Some(size) = card_size {
+                let value_height = if large_print { big_text::HEIGHT } else { 1 };
+                player.extend((player.len()..size.height() + value_height).map(|_| Line::default()));
+            }
+        } else if let [player_hand] = self.player_hands.as_slice() {
+            let hand = self.shown_hand(Seat::Player(0), &player_hand.hand);
+            match card_size {
+                Some(size) => {
+                    let cards: Vec<Option<&Card>> = hand.cards().iter().map(Some).collect();
+                    let rows = card_art::row(&cards, width, self.card_back, size, &self.palette);
+                    player.extend(rows.iter().map(|card_line| line(&format!("  {}", card_line))));
+                }
+                None => player.push(line(&tr!("  Cards: {}", self.palette.cards(&hand)))),
+            }
+            player.extend(lines(self.value_lines(&hand, false, large_print)));
+        } else if let Some(columns) = self.hand_columns(width, card_size) {
+            player.extend(lines(columns.lines));
+        } else {
+            player.extend((0..self.player_hands.len()).map(|i| line(&self.hand_line(i))));
+        }
+        player.extend(lines(self.status_lines()));
+
+        let mut player = Section::titled(tr!("PLAYER"), player);
+        player.note = self.count_note().map(|note| Line::from(format!(" {} ", note)));
+
+        let controls = Section::new(lines(self.wrap_items(&self.controls(false), inner_width)));
+        vec![rules, dealer, player, controls]
+    }
+
+    // The compact table's rows, split into boxes where it has dividers,
+    // and then the main keys
+    fn compact_sections(&self, inner_width: usize) -> Vec<Section> {
+        let mut sections = vec![Section::new(Vec::new())];
+        for row in self.compact_rows(inner_width) {
+            match row {
+                Some(text) => sections.last_mut().unwrap().lines.push(line(&text)),
+                None => sections.push(Section::new(Vec::new())),
+            }
+        }
+        // The rows end with a divider above the keys
+        let controls = self.wrap_items(&self.controls(true), inner_width);
+        sections.last_mut().unwrap().lines = controls.iter().map(|text| line(text)).collect();
+        sections
+    }
+
+    // The bet's chips as colored tokens, or in reverse without colors
+    fn chip_line(&self, inner_width: usize) -> Line<'static> {
+        let mut spans = vec![Span::raw("  ")];
+        for (chip, token, count) in self.chip_tokens(inner_width) {
+            let style = if self.palette.chip_colors {
+                let (face, text) = chip.colors();
+                Style::new().bg(Color::from_crossterm(face)).fg(Color::from_crossterm(text))
+            } else {
+                Style::new().add_modifier(Modifier::REVERSED)
+            };
+            spans.push(Span::styled(token, style));
+            spans.push(Span::raw(count));
+        }
+        Line::from(spans)
+    }
+
+    // Draws the cards on their way from the shoe, at the end of the dealer's
+    // row, and the hole card turning over
+    fn draw_moving_cards(&self, frame: &mut Frame, table: Rect, dealer: Rect, player: Rect, size: CardSize) {
+        let width = (table.width - 6) as usize;
+        let shoe = (table.right() - 3 - size.width() as u16, dealer.y + 1);
+        let dealer_row = (dealer.x + 3, dealer.y + 1);
+        let player_row = (player.x + 3, player.y + 1);
+
+        let dealer_hand = self.shown_hand(Seat::Dealer, &self.dealer_hand);
+        if let Some(turning) = self.turning_hole_card(&dealer_hand, size, width) {
+            self.draw_lines(frame, dealer_row, &turning);
+        }
+        // Several hands have a column each, under their labels, unless
+        // they're written as text a line each with no cards to move
+        let mut rows = vec![(Seat::Dealer, dealer_row, width)];
+        if self.player_hands.len() == 1 && !matches!(self.state, GameState::Betting) {
+            rows.push((Seat::Player(0), player_row, width));
+        } else if let Some(columns) = self.hand_columns(width, Some(size)) {
+            for (i, &(x, column)) in columns.columns.iter().enumerate() {
+                let at = (player_row.0 + x as u16, player_row.1 + columns.cards_row as u16);
+                rows.push((Seat::Player(i), at, column));
+            }
+        }
+        for (seat, row, width) in rows {
+            if let Some((card, at)) = self.moving_card(seat, size, row, shoe, width) {
+                self.draw_lines(frame, at, &card_art::row(&[card], width, self.card_back, size, &self.palette));
+            }
+        }
+    }
+
+    // Draws colored lines over whatever's at a spot, as far as the frame goes
+    fn draw_lines(&self, frame: &mut Frame, (x, y): (u16, u16), lines: &[String]) {
+        let lines: Vec<Line> = lines.iter().map(|text| line(text)).collect();
+        let width = lines.iter().map(Line::width).max().unwrap_or(0) as u16;
+        let area = Rect::new(x, y, width, lines.len() as u16).intersection(frame.area());
+        frame.render_widget(Paragraph::new(lines), area);
+    }
+
+    // A titled box of lines beside or under the table, and its height
+    fn panel(&self, title: &str, lines: Vec<String>) -> (Paragraph<'static>, u16) {
+        let title = Line::from(format!(" {} ", title)).style(self.colored(self.palette.accent));
+        let lines: Vec<Line> = lines.iter().map(|text| line(text)).collect();
+        let height = lines.len() as u16 + 2;
+        let block = Block::bordered().border_style(self.colored(self.palette.border)).title(title);
+        (Paragraph::new(lines).block(block), height)
+    }
+
+    // Draws the wide table's panels: the session and the log to its left,
+    // and the shoe and the last few rounds to its right
+    fn draw_panels(&self, frame: &mut Frame, left: Rect, right: Rect) {
+        let panel = |title: &str, lines| self.panel(title, lines);
+
+        let (session, session_height) = panel(tr!("SESSION"), self.session_lines());
+        let log_lines = self.log_lines(LOG_PANEL_LINES, (PANEL_WIDTH - 2) as usize);
+        let (log, log_height) = panel(&tr!("LOG [{}]", self.keys.log), log_lines);
+        let [session_area, log_area] =
+            Split::vertical([Constraint::Length(session_height), Constraint::Length(log_height)]).areas(left);
+        frame.render_widget(session, session_area);
+        frame.render_widget(log, log_area);
+        let (shoe, shoe_height) = panel(tr!("SHOE"), self.shoe_lines());
+        let (title, lines) = self.tray_panel();
+        let (recent, recent_height) = panel(&title, lines);
+        let [shoe_area, recent_area] =
+            Split::vertical([Constraint::Length(shoe_height), Constraint::Length(recent_height)]).areas(right);
+        frame.render_widget(shoe, shoe_area);
+        frame.render_widget(recent, recent_area);
+    }
+
+    // Draws a popup in the middle of the frame, its border and title in its
+    // color. Only its own keys can be clicked while it's up.
+    fn draw_popup_box(&self, frame: &mut Frame, popup: &Popup) {
+        // The message, a blank line and the prompt, inside the border, and
+        // wide enough for the prompt where the screen has room
+        let prompt_width = UnicodeWidthStr::width(popup.prompt.as_str()) as u16;
+        let width = POPUP_WIDTH.max(prompt_width + 4).min(frame.area().width);
+        let (message, prompt) = self.popup_lines(popup, width.saturating_sub(4) as usize);
+        let height = (message.len() + prompt.len()) as u16 + 3;
+        let message: Vec<Line> = message.iter().map(|text| line(text)).collect();
+        let area = frame.area().centered(Constraint::Length(width), Constraint::Length(height));
+
+        let style = self.colored(popup.color);
+        let block = Block::bordered()
+            .style(self.base_style())
+            .border_style(style)
+            .title(Line::from(format!(" {} ", popup.title)).style(style));
+        let inner = block.inner(area);
+        frame.render_widget(Clear, area);
+        frame.render_widget(block, area);
+
+        let [message_area, prompt_area] =
+            Split::vertical([Constraint::Min(0), Constraint::Length(prompt.len() as u16)]).areas(inner);
+        frame.render_widget(Paragraph::new(message).centered(), message_area);
+        let prompt_lines: Vec<Line> = prompt.iter().map(|text| line(text)).collect();
+        frame.render_widget(Paragraph::new(prompt_lines).centered(), prompt_area);
+
+        let mut buttons = self.buttons.borrow_mut();
+        buttons.clear();
+        for (y, text) in (prompt_area.y..).zip(&prompt) {
+            let text_width = UnicodeWidthStr::width(text.as_str()) as u16;
+            let x = prompt_area.x + prompt_area.width.saturating_sub(text_width) / 2;
+            buttons.extend(mouse::key_hints(text, x, y));
+        }
+    }
+}